@@ -1,15 +1,29 @@
 //! Browser Automation MCP Server binary.
 //!
-//! Provides three subcommands:
+//! Provides four subcommands:
 //! - `serve` (default): Start the MCP server over Streamable HTTP
 //! - `setup-login`: Open browser for manual login, save profile for reuse
 //! - `check`: Run diagnostic checks on the browser automation stack
+//! - `profile`: List, inspect, rename, clone, prune, export, import and
+//!   verify saved profiles
 
 use clap::{Parser, Subcommand};
 use mcp_browser_core::browser::{BrowserManager, BrowserManagerConfig};
 use mcp_browser_core::check;
-use mcp_browser_core::profile::{CreateOpts, ProfileManager};
+use mcp_browser_core::dialog::DialogPolicy;
+use mcp_browser_core::emulation::{self, ColorScheme, EmulationSettings, Geolocation};
+use mcp_browser_core::launch::LaunchOptions;
+use mcp_browser_core::limits::ResourceLimits;
+use mcp_browser_core::login_check::{
+    self, LoginCheck, LoginCondition, LoginSuccess, VerificationResult,
+};
+use mcp_browser_core::network::{self, Credentials, NetworkSettings, ProxySettings};
+use mcp_browser_core::profile::{CreateOpts, ProfileManager, ProfileSummary, PruneFilter};
+use mcp_browser_core::profile_archive::ImportOpts;
+use mcp_browser_core::profile_vault::VaultKey;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "browser-server", about = "Browser Automation MCP Server")]
@@ -28,6 +42,193 @@ enum Command {
 
     /// Run diagnostic checks on the browser automation stack
     Check(CheckArgs),
+
+    /// Manage saved browser profiles
+    #[command(subcommand)]
+    Profile(ProfileCommand),
+}
+
+#[derive(Subcommand)]
+enum ProfileCommand {
+    /// List saved profiles
+    List(FormatArgs),
+
+    /// Show one profile and its login state
+    Show {
+        /// Profile name
+        name: String,
+        #[clap(flatten)]
+        format: FormatArgs,
+    },
+
+    /// Delete a profile and its browser data
+    Delete {
+        /// Profile name
+        name: String,
+    },
+
+    /// Rename a profile, moving its browser data
+    Rename {
+        /// Current profile name
+        name: String,
+        /// New profile name
+        new_name: String,
+    },
+
+    /// Copy a profile, including cookies and storage, under a new name
+    Clone {
+        /// Profile to copy
+        name: String,
+        /// Name for the copy
+        new_name: String,
+    },
+
+    /// Delete profiles unused for N days and/or matching a name pattern
+    Prune(PruneArgs),
+
+    /// Write a profile to a portable archive
+    Export {
+        /// Profile name
+        name: String,
+        /// Archive file to write
+        output: PathBuf,
+        /// Encrypt the archive with this passphrase (or set BROWSER_PROFILE_PASSPHRASE)
+        #[clap(long)]
+        passphrase: Option<String>,
+    },
+
+    /// Create a profile from an archive written by `profile export`
+    Import {
+        /// Archive file to read
+        input: PathBuf,
+        /// Import under this name instead of the exported one
+        #[clap(long)]
+        name: Option<String>,
+        /// Passphrase for an encrypted archive (or set BROWSER_PROFILE_PASSPHRASE)
+        #[clap(long)]
+        passphrase: Option<String>,
+        /// Replace an existing profile with the same name
+        #[clap(long)]
+        force: bool,
+        /// Also import the archive's Chrome arguments, extensions and
+        /// network settings (proxy, headers, certificates). Only for archives
+        /// you trust: Chrome flags can run commands.
+        #[clap(long)]
+        trust_settings: bool,
+    },
+
+    /// Store a profile's browser data encrypted, decrypting it only while in use
+    ///
+    /// The key comes from BROWSER_PROFILE_KEY or the key file at
+    /// BROWSER_PROFILE_KEY_FILE (default: profile.key in the config
+    /// directory), which is created if neither exists.
+    Encrypt {
+        /// Profile name
+        name: String,
+    },
+
+    /// Store an encrypted profile's browser data as a plain directory again
+    Decrypt {
+        /// Profile name
+        name: String,
+    },
+
+    /// Set or clear how `profile verify` tells the session is logged in
+    LoginCheck(LoginCheckArgs),
+
+    /// Run the profile's login check in a headless browser and record the result
+    Verify {
+        /// Profile name
+        name: String,
+        /// Custom Chrome/Edge binary path
+        #[clap(long)]
+        browser_path: Option<String>,
+        #[clap(flatten)]
+        format: FormatArgs,
+    },
+}
+
+#[derive(clap::Args)]
+#[command(group(
+    clap::ArgGroup::new("condition")
+        .args(["selector", "url_matches", "url_not_containing", "cookie"])
+        .conflicts_with("clear")
+))]
+struct LoginCheckArgs {
+    /// Profile name
+    name: String,
+
+    /// Page to open for the check
+    #[clap(long, required_unless_present = "clear", requires = "condition")]
+    url: Option<String>,
+
+    /// Logged in if this CSS selector matches an element
+    #[clap(long)]
+    selector: Option<String>,
+
+    /// Logged in if the final URL matches this pattern (substring, or * wildcards)
+    #[clap(long)]
+    url_matches: Option<String>,
+
+    /// Logged in if the final URL does not contain this text, e.g. "/login"
+    #[clap(long)]
+    url_not_containing: Option<String>,
+
+    /// Logged in if a cookie with this name is set
+    #[clap(long)]
+    cookie: Option<String>,
+
+    /// Seconds to wait for the condition after the page loads
+    #[clap(long, default_value = "10")]
+    timeout_secs: u64,
+
+    /// Remove the profile's login check
+    #[clap(long, conflicts_with = "url")]
+    clear: bool,
+}
+
+impl LoginCheckArgs {
+    fn into_check(self) -> Option<LoginCheck> {
+        let condition = if let Some(selector) = self.selector {
+            LoginCondition::Selector(selector)
+        } else if let Some(pattern) = self.url_matches {
+            LoginCondition::UrlMatches(pattern)
+        } else if let Some(pattern) = self.url_not_containing {
+            LoginCondition::UrlNotContaining(pattern)
+        } else {
+            LoginCondition::Cookie(self.cookie?)
+        };
+        Some(LoginCheck {
+            url: self.url?,
+            condition,
+            timeout_secs: self.timeout_secs,
+        })
+    }
+}
+
+#[derive(clap::Args)]
+struct FormatArgs {
+    /// Output format: "text" or "json"
+    #[clap(long, default_value = "text")]
+    format: String,
+}
+
+#[derive(clap::Args)]
+struct PruneArgs {
+    /// Select profiles not used for at least this many days
+    #[clap(long)]
+    unused_days: Option<u64>,
+
+    /// Select profiles whose name matches this glob, e.g. "test-*"
+    #[clap(long)]
+    pattern: Option<String>,
+
+    /// List the selected profiles without deleting them
+    #[clap(long)]
+    dry_run: bool,
+
+    #[clap(flatten)]
+    format: FormatArgs,
 }
 
 #[derive(Parser)]
@@ -58,7 +259,8 @@ struct ServeArgs {
     #[clap(long)]
     browser_path: Option<String>,
 
-    /// Connect to already-running browser via CDP URL
+    /// Connect to an already-running browser: its WebSocket URL, or its HTTP
+    /// debugging endpoint such as http://localhost:9222
     #[clap(long)]
     cdp_url: Option<String>,
 
@@ -69,6 +271,197 @@ struct ServeArgs {
     /// Named profile to use for session persistence
     #[clap(long)]
     profile: Option<String>,
+
+    /// Use a fresh temporary user-data-dir per launch, deleted on shutdown
+    #[clap(long, conflicts_with_all = ["profile", "cdp_url"])]
+    ephemeral: bool,
+
+    /// Answer JavaScript dialogs automatically: "accept", "dismiss" or "leave"
+    #[clap(long, default_value = "leave")]
+    dialog_policy: DialogPolicy,
+
+    /// Viewport size as WIDTHxHEIGHT (default: 1280x720, or the device preset's size)
+    #[clap(long, value_parser = emulation::parse_viewport)]
+    viewport: Option<(u32, u32)>,
+
+    /// Emulate a device preset, e.g. "iphone-15", "pixel-7", "ipad"
+    #[clap(long)]
+    device: Option<String>,
+
+    /// Device scale factor (devicePixelRatio)
+    #[clap(long)]
+    device_scale_factor: Option<f64>,
+
+    /// Emulate a mobile device with touch events
+    #[clap(long)]
+    mobile: bool,
+
+    /// User agent override
+    #[clap(long)]
+    user_agent: Option<String>,
+
+    /// Locale override, e.g. "de-DE" (also sets Accept-Language)
+    #[clap(long)]
+    locale: Option<String>,
+
+    /// Timezone override, e.g. "Europe/Berlin"
+    #[clap(long)]
+    timezone: Option<String>,
+
+    /// Geolocation override as "latitude,longitude[,accuracy]"
+    #[clap(long)]
+    geolocation: Option<Geolocation>,
+
+    /// prefers-color-scheme: "light", "dark" or "no-preference"
+    #[clap(long)]
+    color_scheme: Option<ColorScheme>,
+
+    #[clap(flatten)]
+    network: NetworkArgs,
+
+    /// TOML file with extra Chrome `args` and unpacked `extensions`
+    #[clap(long)]
+    launch_config: Option<std::path::PathBuf>,
+
+    #[clap(flatten)]
+    launch: LaunchArgs,
+
+    /// Shut the browser down after this many minutes without tool calls;
+    /// the next call relaunches it and reopens its tabs
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..), conflicts_with = "cdp_url")]
+    idle_timeout_mins: Option<u64>,
+
+    /// Recycle the browser once it has been up this many minutes
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..), conflicts_with = "cdp_url")]
+    max_lifetime_mins: Option<u64>,
+
+    /// Maximum number of open pages (tabs)
+    #[clap(long, default_value = "20", value_parser = clap::value_parser!(u64).range(1..))]
+    max_pages: u64,
+
+    /// Maximum DOM or text returned by one call, in KiB; more is truncated
+    #[clap(long, default_value = "2048", value_parser = clap::value_parser!(u64).range(1..))]
+    max_content_kb: u64,
+
+    /// Largest screenshot as WIDTHxHEIGHT in CSS pixels
+    #[clap(long, default_value = "4096x8192", value_parser = emulation::parse_viewport)]
+    max_screenshot_size: (u32, u32),
+
+    /// Seconds any single browser (CDP) operation may take
+    #[clap(long, default_value = "30", value_parser = clap::value_parser!(u64).range(1..))]
+    cdp_timeout_secs: u64,
+
+    /// Separate worker browsers for parallel execute_code runs (0: run
+    /// scripts on the shared browser)
+    #[clap(long, default_value = "0", conflicts_with = "cdp_url")]
+    code_workers: usize,
+}
+
+/// Proxy, header, auth and certificate options shared by `serve` and
+/// `setup-login`.
+#[derive(clap::Args)]
+struct NetworkArgs {
+    /// Upstream proxy server, e.g. "http://proxy.corp:3128" or "socks5://127.0.0.1:1080"
+    #[clap(long)]
+    proxy: Option<String>,
+
+    /// Host that bypasses the proxy (repeatable, or comma-separated)
+    #[clap(long, value_delimiter = ',')]
+    proxy_bypass: Vec<String>,
+
+    /// Proxy credentials as "user:password" (or set BROWSER_PROXY_AUTH);
+    /// without --proxy they are used for the profile's stored proxy
+    #[clap(long)]
+    proxy_auth: Option<Credentials>,
+
+    /// Extra HTTP header sent with every request, as "Name: value" (repeatable)
+    #[clap(long = "header", value_parser = network::parse_header)]
+    headers: Vec<(String, String)>,
+
+    /// HTTP Basic/Digest credentials as "user:password" (or set BROWSER_HTTP_CREDENTIALS)
+    #[clap(long)]
+    http_credentials: Option<Credentials>,
+
+    /// Fail on TLS certificate errors (by default they are ignored)
+    #[clap(long)]
+    strict_https: bool,
+
+    /// Ignore certificate errors for a certificate's public key, given as its
+    /// base64 SHA-256 SPKI hash (repeatable); errors for any other
+    /// certificate then fail as with --strict-https. Compute with:
+    /// openssl x509 -pubkey -noout -in cert.pem | openssl pkey -pubin -outform der |
+    /// openssl dgst -sha256 -binary | base64
+    #[clap(long)]
+    ignore_certificate_spki: Vec<String>,
+}
+
+impl NetworkArgs {
+    fn into_settings(self) -> anyhow::Result<NetworkSettings> {
+        let from_env = |var: &str| -> anyhow::Result<Option<Credentials>> {
+            match std::env::var(var) {
+                Ok(value) => value
+                    .parse()
+                    .map(Some)
+                    .map_err(|e| anyhow::anyhow!("{}: {}", var, e)),
+                Err(_) => Ok(None),
+            }
+        };
+        let proxy_auth = match self.proxy_auth {
+            Some(creds) => Some(creds),
+            None => from_env("BROWSER_PROXY_AUTH")?,
+        };
+        let http_credentials = match self.http_credentials {
+            Some(creds) => Some(creds),
+            None => from_env("BROWSER_HTTP_CREDENTIALS")?,
+        };
+
+        // Credentials without --proxy are for the profile's proxy, checked
+        // once the settings are overlaid on it.
+        let (proxy, proxy_auth) = match self.proxy {
+            Some(server) => (
+                Some(ProxySettings {
+                    server,
+                    bypass: self.proxy_bypass,
+                    auth: proxy_auth,
+                }),
+                None,
+            ),
+            None if !self.proxy_bypass.is_empty() => {
+                anyhow::bail!("--proxy-bypass requires --proxy")
+            }
+            None => (None, proxy_auth),
+        };
+
+        Ok(NetworkSettings {
+            proxy,
+            proxy_auth,
+            extra_headers: self.headers.into_iter().collect(),
+            http_credentials,
+            strict_https: self.strict_https,
+            ignore_certificate_spki: self.ignore_certificate_spki,
+        })
+    }
+}
+
+/// Extra Chrome arguments and extensions shared by `serve` and `setup-login`.
+#[derive(clap::Args)]
+struct LaunchArgs {
+    /// Extra Chrome argument, e.g. --chrome-arg=--lang=de-DE (repeatable)
+    #[clap(long = "chrome-arg", allow_hyphen_values = true)]
+    chrome_args: Vec<String>,
+
+    /// Load an unpacked extension from this directory (repeatable)
+    #[clap(long = "load-extension")]
+    extensions: Vec<std::path::PathBuf>,
+}
+
+impl LaunchArgs {
+    fn into_options(self) -> LaunchOptions {
+        LaunchOptions {
+            args: self.chrome_args,
+            extensions: self.extensions,
+        }
+    }
 }
 
 #[derive(Parser)]
@@ -85,6 +478,21 @@ struct SetupLoginArgs {
     #[clap(long, default_value = "300")]
     timeout_secs: u64,
 
+    /// Finish automatically once the page URL matches this pattern (substring,
+    /// or a full-URL pattern with * wildcards). Stored as the profile's login check.
+    #[clap(long)]
+    success_url: Option<String>,
+
+    /// Finish automatically once this CSS selector matches an element, e.g. the
+    /// account avatar. Stored as the profile's login check.
+    #[clap(long)]
+    success_selector: Option<String>,
+
+    /// Browser channel for a new profile: "chrome", "msedge" or "chromium"
+    /// (default: chrome). An existing profile keeps its channel.
+    #[clap(long)]
+    channel: Option<String>,
+
     /// Description for this profile
     #[clap(long)]
     description: Option<String>,
@@ -96,6 +504,14 @@ struct SetupLoginArgs {
     /// Custom Chrome/Edge binary path
     #[clap(long)]
     browser_path: Option<String>,
+
+    /// Network settings to store in the profile and use for login
+    #[clap(flatten)]
+    network: NetworkArgs,
+
+    /// Chrome arguments and extensions to store in the profile and use for login
+    #[clap(flatten)]
+    launch: LaunchArgs,
 }
 
 #[tokio::main]
@@ -113,16 +529,56 @@ async fn main() -> anyhow::Result<()> {
         }
         Some(Command::SetupLogin(args)) => run_setup_login(args).await,
         Some(Command::Check(args)) => run_check(args).await,
+        Some(Command::Profile(command)) => run_profile(command).await,
     }
 }
 
 async fn run_serve(args: ServeArgs) -> anyhow::Result<()> {
+    let emulation = EmulationSettings {
+        device: args.device,
+        width: args.viewport.map(|(w, _)| w),
+        height: args.viewport.map(|(_, h)| h),
+        device_scale_factor: args.device_scale_factor,
+        mobile: args.mobile.then_some(true),
+        touch: args.mobile.then_some(true),
+        user_agent: args.user_agent,
+        locale: args.locale,
+        timezone: args.timezone,
+        geolocation: args.geolocation,
+        color_scheme: args.color_scheme,
+    };
+    let resolved = emulation.resolve()?;
+
+    let file_options = match args.launch_config {
+        Some(ref path) => LaunchOptions::from_file(path)?,
+        None => LaunchOptions::default(),
+    };
+    let launch_options = file_options.overlay(&args.launch.into_options());
+
     let config = BrowserManagerConfig {
         browser_path: args.browser_path,
         cdp_url: args.cdp_url,
         headless: args.headless,
-        window_size: (1280, 720),
+        window_size: resolved.viewport.unwrap_or((1280, 720)),
         profile: args.profile,
+        ephemeral: args.ephemeral,
+        dialog_policy: args.dialog_policy,
+        emulation,
+        network: args.network.into_settings()?,
+        launch: launch_options.validated()?,
+        idle_timeout: args
+            .idle_timeout_mins
+            .map(|mins| Duration::from_secs(mins * 60)),
+        max_lifetime: args
+            .max_lifetime_mins
+            .map(|mins| Duration::from_secs(mins * 60)),
+        limits: ResourceLimits {
+            max_pages: args.max_pages as usize,
+            max_content_bytes: args.max_content_kb as usize * 1024,
+            max_screenshot_size: args.max_screenshot_size,
+            cdp_timeout: Duration::from_secs(args.cdp_timeout_secs),
+        },
+        code_workers: args.code_workers,
     };
 
     let (server, manager) = mcp_browser_core::build_server(config)?;
@@ -132,6 +588,9 @@ async fn run_serve(args: ServeArgs) -> anyhow::Result<()> {
         _ = tokio::signal::ctrl_c() => {
             tracing::info!("Ctrl+C received — shutting down browser");
             manager.shutdown().await;
+            if let Some(pool) = manager.worker_pool() {
+                pool.shutdown().await;
+            }
             Ok(())
         }
     }
@@ -147,9 +606,17 @@ async fn run_setup_login(args: SetupLoginArgs) -> anyhow::Result<()> {
     let profile_manager = Arc::new(ProfileManager::new()?);
 
     // Create or reuse profile
-    let _profile = profile_manager.get_or_create_profile(
+    let network = args.network.into_settings()?;
+    let channel = args
+        .channel
+        .as_deref()
+        .map(check::normalize_channel)
+        .transpose()?;
+
+    let profile = profile_manager.get_or_create_profile(
         &args.profile,
         CreateOpts {
+            browser_channel: channel.unwrap_or(check::DEFAULT_CHANNEL).to_string(),
             description: args
                 .description
                 .unwrap_or_else(|| format!("Login profile for {}", args.url)),
@@ -161,6 +628,44 @@ async fn run_setup_login(args: SetupLoginArgs) -> anyhow::Result<()> {
         },
     )?;
 
+    if let Some(channel) = channel {
+        if profile.browser_channel != channel {
+            anyhow::bail!(
+                "Profile '{}' was created with browser channel '{}' and must be reused with it \
+                 (requested '{}'). Use a different profile name for {}.",
+                args.profile,
+                profile.browser_channel,
+                channel,
+                channel
+            );
+        }
+    }
+
+    // Remember network settings and launch options so every later launch of
+    // this profile uses them. Credentials are used for this login only.
+    profile.network.overlay(&network).auth_credentials()?;
+    let stored = network.without_credentials();
+    if !stored.is_empty() {
+        profile_manager.set_network_settings(&args.profile, profile.network.overlay(&stored))?;
+        tracing::info!(profile = %args.profile, "Stored network settings in profile");
+    }
+    if stored != network {
+        tracing::info!(
+            "Proxy and HTTP credentials are not stored in the profile; pass them again \
+             (or set BROWSER_PROXY_AUTH / BROWSER_HTTP_CREDENTIALS) when serving it"
+        );
+    }
+    let launch = args.launch.into_options();
+    if !launch.is_empty() {
+        let launch = profile.launch.overlay(&launch.validated()?);
+        profile_manager.set_launch_options(&args.profile, launch)?;
+        tracing::info!(profile = %args.profile, "Stored launch options in profile");
+    }
+
+    // Keep other processes off the profile while the user logs in
+    let _lock = profile_manager.lock_profile(&args.profile)?;
+    let data = profile_manager.open_profile_data(&args.profile)?;
+
     tracing::info!(
         profile = %args.profile,
         url = %args.url,
@@ -168,39 +673,113 @@ async fn run_setup_login(args: SetupLoginArgs) -> anyhow::Result<()> {
     );
 
     // Launch non-headless browser pointed at the login URL
-    let _browser = BrowserManager::launch_for_login(
+    let (mut browser, page) = BrowserManager::launch_for_login(
         profile_manager.clone(),
         &args.profile,
+        data.dir(),
         &args.url,
+        &network,
         args.browser_path,
     )
     .await?;
 
+    let success = LoginSuccess {
+        url: args.success_url,
+        selector: args.success_selector,
+    };
+
     println!();
     println!("Browser opened at: {}", args.url);
-    println!(
-        "Please log in. Press Enter when done (or wait {}s)...",
-        args.timeout_secs
-    );
+    if success.is_empty() {
+        println!(
+            "Please log in. Press Enter when done (or wait {}s)...",
+            args.timeout_secs
+        );
+    } else {
+        println!(
+            "Please log in. This finishes automatically once login is detected \
+             (or press Enter, or wait {}s)...",
+            args.timeout_secs
+        );
+    }
     println!();
 
     // Wait for user input or timeout
     let timeout = tokio::time::Duration::from_secs(args.timeout_secs);
-    let stdin_future = tokio::task::spawn_blocking(|| {
+    // A detached thread, not spawn_blocking: the runtime waits for blocking
+    // tasks on shutdown, so an unanswered read would keep the process alive
+    // after a detected login or timeout.
+    let (enter_tx, stdin_future) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
         let mut line = String::new();
-        std::io::stdin().read_line(&mut line)
+        let _ = std::io::stdin().read_line(&mut line);
+        let _ = enter_tx.send(());
     });
 
-    tokio::select! {
-        _ = stdin_future => {}
+    let detected = tokio::select! {
+        _ = stdin_future => None,
+        url = success.wait(&page), if !success.is_empty() => Some(url),
         _ = tokio::time::sleep(timeout) => {
             println!("Timeout reached.");
+            None
         }
+    };
+    if let Some(ref final_url) = detected {
+        println!("Login detected at {}", final_url);
     }
 
+    match browser.get_cookies().await {
+        Ok(cookies) if !cookies.is_empty() => {
+            let domains = login_check::cookie_domains(&cookies);
+            println!();
+            println!(
+                "Captured {} cookies for {} domains:",
+                cookies.len(),
+                domains.len()
+            );
+            for (domain, names) in &domains {
+                println!("  {}: {}", domain, names.join(", "));
+            }
+        }
+        Ok(_) => println!("No cookies were captured."),
+        Err(e) => tracing::warn!("Could not list captured cookies: {}", e),
+    }
+
+    // Close the browser so the session is flushed to disk before an
+    // encrypted profile is sealed again. Sealing comes before any metadata
+    // update, so a failed write cannot discard the logged-in session.
+    let _ = browser.close().await;
+    let _ = browser.wait().await;
+    data.close()?;
+
     // Update profile metadata
     profile_manager.touch_profile(&args.profile)?;
 
+    match detected {
+        Some(final_url) => {
+            if let Some(check) = success.to_check(&final_url) {
+                profile_manager.set_login_check(&args.profile, Some(check))?;
+                profile_manager.record_verification(
+                    &args.profile,
+                    &VerificationResult {
+                        logged_in: true,
+                        final_url,
+                        detail: "Login detected during setup-login".to_string(),
+                    },
+                )?;
+                println!(
+                    "Stored the success condition as the profile's login check \
+                     (run `browser-server profile verify {}` to re-check later).",
+                    args.profile
+                );
+            }
+        }
+        None if !success.is_empty() => {
+            println!("Login was not detected; the login check was not updated.");
+        }
+        None => {}
+    }
+
     println!();
     println!("Profile '{}' saved.", args.profile);
     println!("Use --profile {} to reuse this session.", args.profile);
@@ -231,3 +810,276 @@ async fn run_check(args: CheckArgs) -> anyhow::Result<()> {
         std::process::exit(1);
     }
 }
+
+async fn run_profile(command: ProfileCommand) -> anyhow::Result<()> {
+    let manager = ProfileManager::new()?;
+
+    match command {
+        ProfileCommand::List(args) => {
+            let mut profiles: Vec<ProfileSummary> = manager
+                .list_profiles()?
+                .iter()
+                .map(|p| manager.summarize(p))
+                .collect();
+            profiles.sort_by(|a, b| a.name.cmp(&b.name));
+
+            if args.format == "json" {
+                println!("{}", serde_json::to_string_pretty(&profiles)?);
+            } else if profiles.is_empty() {
+                println!("No profiles");
+            } else {
+                for p in &profiles {
+                    println!(
+                        "{:<24} {:<8} last used {}  uses {:<4}{}",
+                        p.name,
+                        p.browser_channel,
+                        p.last_used.format("%Y-%m-%d %H:%M"),
+                        p.usage_count,
+                        if p.needs_login { "  needs login" } else { "" }
+                    );
+                }
+            }
+        }
+        ProfileCommand::Show { name, format } => {
+            let summary = manager.summarize(&manager.get_profile(&name)?);
+            if format.format == "json" {
+                println!("{}", serde_json::to_string_pretty(&summary)?);
+            } else {
+                print!("{}", format_profile_text(&summary));
+            }
+        }
+        ProfileCommand::Delete { name } => {
+            manager.delete_profile(&name)?;
+            println!("Deleted profile '{}'", name);
+        }
+        ProfileCommand::Rename { name, new_name } => {
+            manager.rename_profile(&name, &new_name)?;
+            println!("Renamed profile '{}' to '{}'", name, new_name);
+        }
+        ProfileCommand::Clone { name, new_name } => {
+            let profile = manager.clone_profile(&name, &new_name)?;
+            println!(
+                "Cloned profile '{}' to '{}' ({})",
+                name,
+                new_name,
+                profile.user_data_dir.display()
+            );
+        }
+        ProfileCommand::Prune(args) => {
+            let selected = manager.prune_candidates(&PruneFilter {
+                unused_days: args.unused_days,
+                pattern: args.pattern,
+            })?;
+            // Profiles a running browser holds are left alone
+            let (in_use, names): (Vec<&str>, Vec<&str>) = selected
+                .iter()
+                .map(|p| p.name.as_str())
+                .partition(|name| manager.lock_owner(name).is_some());
+
+            if !args.dry_run {
+                for name in &names {
+                    manager.delete_profile(name)?;
+                }
+            }
+
+            if args.format.format == "json" {
+                let report = serde_json::json!({
+                    "dry_run": args.dry_run,
+                    "profiles": names,
+                    "count": names.len(),
+                    "skipped_in_use": in_use,
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                let verb = if args.dry_run {
+                    "Would delete"
+                } else {
+                    "Deleted"
+                };
+                for name in &names {
+                    println!("{} {}", verb, name);
+                }
+                for name in &in_use {
+                    println!("Skipped {} (in use)", name);
+                }
+                println!("{} {} profile(s)", verb, names.len());
+            }
+        }
+        ProfileCommand::Export {
+            name,
+            output,
+            passphrase,
+        } => {
+            let passphrase =
+                passphrase.or_else(|| std::env::var("BROWSER_PROFILE_PASSPHRASE").ok());
+            let summary = manager.export_profile(&name, &output, passphrase.as_deref())?;
+            let encryption = if summary.encrypted {
+                "encrypted"
+            } else {
+                "not encrypted"
+            };
+            println!(
+                "Exported profile '{}' ({} files, {}) to {}",
+                summary.profile,
+                summary.files,
+                encryption,
+                summary.path.display()
+            );
+            if !summary.encrypted {
+                eprintln!("The archive contains session cookies; store it like a password.");
+            }
+        }
+        ProfileCommand::Import {
+            input,
+            name,
+            passphrase,
+            force,
+            trust_settings,
+        } => {
+            let passphrase =
+                passphrase.or_else(|| std::env::var("BROWSER_PROFILE_PASSPHRASE").ok());
+            let summary = manager.import_profile(
+                &input,
+                ImportOpts {
+                    name,
+                    passphrase,
+                    force,
+                    trust_settings,
+                },
+            )?;
+            let profile = summary.profile;
+            println!(
+                "Imported profile '{}' into {}",
+                profile.name,
+                profile.user_data_dir.display()
+            );
+            if summary.dropped_settings {
+                println!(
+                    "The archive's launch options and network settings were not imported; \
+                     re-import with --trust-settings to keep them."
+                );
+            }
+            if !profile.launch.is_empty() {
+                println!("Imported launch options:");
+                for arg in &profile.launch.args {
+                    println!("  argument  {}", arg);
+                }
+                for extension in &profile.launch.extensions {
+                    println!("  extension {}", extension.display());
+                }
+            }
+            if !profile.network.is_empty() {
+                println!("Imported network settings:");
+                for line in
+                    serde_json::to_string_pretty(&profile.network.without_credentials())?.lines()
+                {
+                    println!("  {}", line);
+                }
+            }
+        }
+        ProfileCommand::Encrypt { name } => {
+            let (key, created) = VaultKey::load_or_create()?;
+            if let Some(path) = created {
+                println!("Created profile key {}", path.display());
+                println!("Back it up: encrypted profiles cannot be opened without it.");
+            }
+            manager.with_vault_key(key).encrypt_profile(&name)?;
+            println!("Encrypted profile '{}'", name);
+        }
+        ProfileCommand::Decrypt { name } => {
+            manager.decrypt_profile(&name)?;
+            println!("Decrypted profile '{}'", name);
+        }
+        ProfileCommand::LoginCheck(args) => {
+            let name = args.name.clone();
+            match args.into_check() {
+                Some(check) => {
+                    manager.set_login_check(&name, Some(check))?;
+                    println!("Set login check for profile '{}'", name);
+                }
+                None => {
+                    manager.set_login_check(&name, None)?;
+                    println!("Cleared login check for profile '{}'", name);
+                }
+            }
+        }
+        ProfileCommand::Verify {
+            name,
+            browser_path,
+            format,
+        } => {
+            let config = BrowserManagerConfig {
+                browser_path,
+                ..Default::default()
+            };
+            let browser = BrowserManager::new(config, Arc::new(manager));
+            let result = browser.verify_profile(&name).await?;
+
+            if format.format == "json" {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else {
+                println!(
+                    "Profile '{}': {} ({})",
+                    name,
+                    if result.logged_in {
+                        "logged in"
+                    } else {
+                        "NOT logged in"
+                    },
+                    result.detail
+                );
+                println!("Final URL: {}", result.final_url);
+            }
+            if !result.logged_in {
+                std::process::exit(1);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn format_profile_text(p: &ProfileSummary) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("Profile:        {}\n", p.name));
+    if !p.description.is_empty() {
+        out.push_str(&format!("Description:    {}\n", p.description));
+    }
+    out.push_str(&format!("Channel:        {}\n", p.browser_channel));
+    out.push_str(&format!("Data dir:       {}\n", p.user_data_dir.display()));
+    out.push_str(&format!("Created:        {}\n", p.created_at.to_rfc3339()));
+    out.push_str(&format!("Last used:      {}\n", p.last_used.to_rfc3339()));
+    out.push_str(&format!("Uses:           {}\n", p.usage_count));
+    if p.encrypted {
+        out.push_str("Encrypted:      yes\n");
+    }
+    out.push_str(&format!("Cookies:        {}\n", yes_no(p.has_cookies)));
+    out.push_str(&format!(
+        "Session:        {} ({}h timeout)\n",
+        if p.session_valid { "valid" } else { "expired" },
+        p.session_timeout_hours
+    ));
+    if p.requires_human_login {
+        out.push_str(&format!("Needs login:    {}\n", yes_no(p.needs_login)));
+        if !p.login_notes.is_empty() {
+            out.push_str(&format!("Login notes:    {}\n", p.login_notes));
+        }
+    }
+    for arg in &p.launch.args {
+        out.push_str(&format!("Chrome arg:     {}\n", arg));
+    }
+    for ext in &p.launch.extensions {
+        out.push_str(&format!("Extension:      {}\n", ext.display()));
+    }
+    if p.has_network_settings {
+        out.push_str("Network:        custom settings stored\n");
+    }
+    out
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
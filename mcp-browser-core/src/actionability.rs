@@ -0,0 +1,405 @@
+//! Actionability checks for element-based tools.
+//!
+//! Before acting on an element, tools wait until it satisfies a set of
+//! checks (attached, visible, stable, enabled, editable, unobscured). The
+//! checks run inside the page: a probe function watches the DOM with a
+//! `MutationObserver` and re-checks on every animation frame, so state
+//! changes are picked up as soon as they happen rather than on a fixed poll.
+//! Background tabs get no animation frames, so a timer re-checks there.
+//!
+//! A single probe call is bounded by `PROBE_SLICE_MS` so it never runs into
+//! the CDP request timeout; the Rust side re-issues probes until the overall
+//! deadline passes. Navigations that destroy the execution context mid-probe
+//! are retried transparently.
+
+use chromiumoxide::element::Element;
+use chromiumoxide::error::CdpError;
+use chromiumoxide::Page;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+/// Default time to wait for an element to become actionable.
+pub const DEFAULT_TIMEOUT_MS: u64 = 5000;
+
+/// Upper bound for a single in-page probe (CDP requests time out at 30s).
+const PROBE_SLICE_MS: u64 = 5000;
+
+/// A single actionability check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// Element matching the selector is present in the DOM.
+    Attached,
+    /// Element has a non-empty box and is not `visibility: hidden`.
+    Visible,
+    /// Element's bounding box is unchanged across two animation frames.
+    Stable,
+    /// Element is not disabled (native, `fieldset`, or `aria-disabled`).
+    Enabled,
+    /// Element accepts text input and is not read-only.
+    Editable,
+    /// Element is the hit target at its center point (not covered).
+    Unobscured,
+}
+
+impl Check {
+    /// Name used in the in-page probe and in error messages.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Check::Attached => "attached",
+            Check::Visible => "visible",
+            Check::Stable => "stable",
+            Check::Enabled => "enabled",
+            Check::Editable => "editable",
+            Check::Unobscured => "unobscured",
+        }
+    }
+}
+
+/// Checks required before clicking.
+pub const CLICK: &[Check] = &[
+    Check::Attached,
+    Check::Visible,
+    Check::Stable,
+    Check::Enabled,
+    Check::Unobscured,
+];
+
+/// Checks required before filling a form field.
+pub const FILL: &[Check] = &[
+    Check::Attached,
+    Check::Visible,
+    Check::Stable,
+    Check::Enabled,
+    Check::Editable,
+    Check::Unobscured,
+];
+
+/// Checks required before hovering.
+pub const HOVER: &[Check] = &[
+    Check::Attached,
+    Check::Visible,
+    Check::Stable,
+    Check::Unobscured,
+];
+
+/// Checks required before taking an element screenshot.
+pub const SCREENSHOT: &[Check] = &[Check::Attached, Check::Visible, Check::Stable];
+
+/// Checks required before reading from an element.
+pub const ATTACHED: &[Check] = &[Check::Attached];
+
+/// Errors returned when an element never becomes actionable.
+#[derive(Debug, thiserror::Error)]
+pub enum ActionabilityError {
+    #[error("Timed out after {timeout_ms}ms waiting for '{selector}' to be {check}: {reason}")]
+    Timeout {
+        selector: String,
+        timeout_ms: u64,
+        check: String,
+        reason: String,
+    },
+    #[error("Invalid selector '{selector}': {reason}")]
+    InvalidSelector { selector: String, reason: String },
+    #[error("Browser error while checking '{selector}': {message}")]
+    Browser { selector: String, message: String },
+    #[error("Browser error while checking '{selector}': {source}")]
+    Cdp {
+        selector: String,
+        #[source]
+        source: CdpError,
+    },
+}
+
+/// Result of one in-page probe.
+#[derive(Debug, Deserialize)]
+struct ProbeResult {
+    ok: bool,
+    #[serde(default)]
+    fatal: bool,
+    #[serde(default)]
+    failed: Option<String>,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// In-page probe. Resolves `{ ok: true }` as soon as every requested check
+/// passes, or the last failing check once `budget` milliseconds elapse.
+const PROBE_JS: &str = r#"
+(selector, checks, budget) => new Promise((resolve) => {
+    const deadline = performance.now() + budget;
+    let lastRect = null;
+    let observer = null;
+    let timer = null;
+    let frame = null;
+    let fallback = null;
+    let done = false;
+
+    const finish = (result) => {
+        if (done) return;
+        done = true;
+        if (observer) observer.disconnect();
+        if (timer) clearTimeout(timer);
+        cancelAnimationFrame(frame);
+        clearTimeout(fallback);
+        resolve(result);
+    };
+
+    const isDisabled = (el) => {
+        if (el.disabled === true) return true;
+        if (el.getAttribute && el.getAttribute('aria-disabled') === 'true') return true;
+        const fieldset = el.closest && el.closest('fieldset[disabled]');
+        if (fieldset) {
+            const legend = fieldset.querySelector(':scope > legend');
+            if (!legend || !legend.contains(el)) return true;
+        }
+        return false;
+    };
+
+    const evaluate = () => {
+        let el;
+        try {
+            el = document.querySelector(selector);
+        } catch (e) {
+            return { fatal: true, failed: 'selector', reason: e.message };
+        }
+        if (!el) return { failed: 'attached', reason: 'no element matches the selector' };
+
+        for (const check of checks) {
+            if (check === 'attached') {
+                if (!el.isConnected) return { failed: check, reason: 'element is detached from the document' };
+            } else if (check === 'visible') {
+                const style = getComputedStyle(el);
+                const rect = el.getBoundingClientRect();
+                if (style.visibility === 'hidden' || style.visibility === 'collapse') {
+                    return { failed: check, reason: 'element has visibility:' + style.visibility };
+                }
+                if (rect.width === 0 || rect.height === 0) {
+                    return { failed: check, reason: 'element has an empty bounding box' };
+                }
+            } else if (check === 'stable') {
+                const r = el.getBoundingClientRect();
+                const rect = [r.x, r.y, r.width, r.height].join(',');
+                const previous = lastRect;
+                lastRect = rect;
+                if (previous !== rect) return { failed: check, reason: 'element is still moving or resizing' };
+            } else if (check === 'enabled') {
+                if (isDisabled(el)) return { failed: check, reason: 'element is disabled' };
+            } else if (check === 'editable') {
+                const editable = el.isContentEditable
+                    || el instanceof HTMLTextAreaElement
+                    || (el instanceof HTMLInputElement
+                        && !['button', 'checkbox', 'color', 'file', 'hidden', 'image', 'radio', 'range', 'reset', 'submit'].includes(el.type))
+                    || el instanceof HTMLSelectElement;
+                if (!editable) return { failed: check, reason: '<' + el.tagName.toLowerCase() + '> does not accept text input' };
+                if (el.readOnly) return { failed: check, reason: 'element is read-only' };
+            } else if (check === 'unobscured') {
+                let r = el.getBoundingClientRect();
+                if (r.bottom < 0 || r.right < 0 || r.top > innerHeight || r.left > innerWidth) {
+                    el.scrollIntoView({ block: 'center', inline: 'center', behavior: 'instant' });
+                    r = el.getBoundingClientRect();
+                    lastRect = null;
+                }
+                const x = r.left + r.width / 2;
+                const y = r.top + r.height / 2;
+                const root = el.getRootNode();
+                const hit = (root.elementFromPoint ? root : document).elementFromPoint(x, y);
+                if (hit && hit !== el && !el.contains(hit)) {
+                    const desc = hit.tagName.toLowerCase()
+                        + (hit.id ? '#' + hit.id : '')
+                        + (typeof hit.className === 'string' && hit.className ? '.' + hit.className.trim().split(/\s+/).join('.') : '');
+                    return { failed: check, reason: 'element is covered by <' + desc + '>' };
+                }
+            }
+        }
+        return null;
+    };
+
+    let last = { failed: 'attached', reason: 'no element matches the selector' };
+
+    const tick = () => {
+        if (done) return;
+        const failure = evaluate();
+        if (!failure) return finish({ ok: true });
+        last = failure;
+        if (failure.fatal) return finish({ ok: false, fatal: true, failed: last.failed, reason: last.reason });
+        if (performance.now() >= deadline) return finish({ ok: false, failed: last.failed, reason: last.reason });
+        // rAF is paused in hidden tabs; whichever of the frame and the timer
+        // comes first runs the next check.
+        frame = requestAnimationFrame(next);
+        fallback = setTimeout(next, 100);
+    };
+
+    const next = () => {
+        cancelAnimationFrame(frame);
+        clearTimeout(fallback);
+        tick();
+    };
+
+    // Catch elements that appear between frames without starting a second
+    // rAF chain.
+    observer = new MutationObserver(() => {
+        if (done || last.failed !== 'attached') return;
+        const failure = evaluate();
+        if (!failure) finish({ ok: true });
+        else last = failure;
+    });
+    observer.observe(document, { childList: true, subtree: true, attributes: true });
+    timer = setTimeout(() => finish({ ok: false, failed: last.failed, reason: last.reason }), budget + 50);
+    tick();
+})
+"#;
+
+/// Whether an evaluation failed because the page navigated away, which is
+/// retried in the new document. Script errors are not.
+pub(crate) fn is_navigation_error(e: &CdpError) -> bool {
+    match e {
+        CdpError::Chrome(error) => {
+            let message = error.message.to_lowercase();
+            // "Execution context was destroyed", "Cannot find context with
+            // specified id", "Inspected target navigated or closed".
+            message.contains("context") || message.contains("navigated")
+        }
+        _ => false,
+    }
+}
+
+/// Wait until the element matching `selector` passes all `checks`, then
+/// return a handle to it.
+pub async fn wait_for_element(
+    page: &Page,
+    selector: &str,
+    checks: &[Check],
+    timeout_ms: u64,
+) -> Result<Element, ActionabilityError> {
+    let start = Instant::now();
+    let timeout = Duration::from_millis(timeout_ms);
+    let names: Vec<&str> = checks.iter().map(Check::as_str).collect();
+
+    let mut last = ProbeResult {
+        ok: false,
+        fatal: false,
+        failed: Some(Check::Attached.as_str().to_string()),
+        reason: Some("no element matches the selector".to_string()),
+    };
+
+    loop {
+        let remaining = timeout.saturating_sub(start.elapsed()).as_millis() as u64;
+        let budget = remaining.min(PROBE_SLICE_MS);
+
+        let js = format!(
+            "({})({}, {}, {})",
+            PROBE_JS,
+            serde_json::to_string(selector).unwrap(),
+            serde_json::to_string(&names).unwrap(),
+            budget
+        );
+
+        match page.evaluate_expression(js).await {
+            Ok(result) => {
+                let probe: ProbeResult =
+                    result
+                        .into_value()
+                        .map_err(|e| ActionabilityError::Browser {
+                            selector: selector.to_string(),
+                            message: format!("unexpected probe result: {}", e),
+                        })?;
+                if probe.ok {
+                    return page.find_element(selector).await.map_err(|e| {
+                        ActionabilityError::Browser {
+                            selector: selector.to_string(),
+                            message: format!("element vanished after checks passed: {}", e),
+                        }
+                    });
+                }
+                if probe.fatal {
+                    return Err(ActionabilityError::InvalidSelector {
+                        selector: selector.to_string(),
+                        reason: probe.reason.unwrap_or_default(),
+                    });
+                }
+                last = probe;
+            }
+            // The page navigated mid-probe (context destroyed) — retry in
+            // the new document until the deadline.
+            Err(e) if is_navigation_error(&e) && start.elapsed() < timeout => {
+                tracing::debug!("Actionability probe for '{}' interrupted: {}", selector, e);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            Err(e) => {
+                return Err(ActionabilityError::Cdp {
+                    selector: selector.to_string(),
+                    source: e,
+                });
+            }
+        }
+
+        if start.elapsed() >= timeout {
+            return Err(ActionabilityError::Timeout {
+                selector: selector.to_string(),
+                timeout_ms,
+                check: last.failed.unwrap_or_else(|| "actionable".to_string()),
+                reason: last.reason.unwrap_or_else(|| "unknown".to_string()),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_navigation_errors_are_retried() {
+        let chrome = |message: &str| {
+            CdpError::Chrome(chromiumoxide::types::Error {
+                code: -32000,
+                message: message.to_string(),
+            })
+        };
+        assert!(is_navigation_error(&chrome(
+            "Execution context was destroyed."
+        )));
+        assert!(is_navigation_error(&chrome(
+            "Cannot find context with specified id"
+        )));
+        assert!(!is_navigation_error(&chrome("Invalid parameters")));
+        assert!(!is_navigation_error(&CdpError::Timeout));
+    }
+
+    #[test]
+    fn test_check_names_are_unique() {
+        let all = [
+            Check::Attached,
+            Check::Visible,
+            Check::Stable,
+            Check::Enabled,
+            Check::Editable,
+            Check::Unobscured,
+        ];
+        let mut names: Vec<&str> = all.iter().map(Check::as_str).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), all.len());
+    }
+
+    #[test]
+    fn test_presets_start_with_attached() {
+        for preset in [CLICK, FILL, HOVER, SCREENSHOT, ATTACHED] {
+            assert_eq!(preset[0], Check::Attached);
+        }
+    }
+
+    #[test]
+    fn test_timeout_error_names_failed_check() {
+        let err = ActionabilityError::Timeout {
+            selector: "#submit".to_string(),
+            timeout_ms: 5000,
+            check: "enabled".to_string(),
+            reason: "element is disabled".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Timed out after 5000ms waiting for '#submit' to be enabled: element is disabled"
+        );
+    }
+}
@@ -4,20 +4,102 @@
 //! `--user-data-dir` pointing to the saved profile so cookies/sessions persist.
 //! Supports multiple pages (tabs) with an active page index.
 
+use crate::check;
+use crate::dialog::{DialogPolicy, DialogTracker};
+use crate::emulation::{EmulationSettings, ResolvedEmulation};
+use crate::ephemeral::{self, EphemeralDir};
+use crate::launch::LaunchOptions;
+use crate::lifecycle::{self, ActivityGuard, ActivityTracker, LifecycleStats, ParkReason};
+use crate::limits::{LimitExceeded, ResourceLimits};
+use crate::login_check::{LoginSuccess, VerificationResult};
+use crate::network::{NetworkConditions, NetworkConditionsUpdate, NetworkSettings};
+use crate::pool::{WorkerLease, WorkerPool};
 use crate::profile::ProfileManager;
+use crate::profile_lock::ProfileLock;
+use crate::profile_vault::ProfileData;
+use crate::recovery::{CrashStats, RecoveryReport, TabSnapshot, TabTracker};
+use crate::remote;
+use crate::status::{
+    BrowserStatus, BrowserVersion, LaunchMode, PageMetrics, ProcessUsage, WorkerPoolStatus,
+};
 use anyhow::{Context, Result};
 use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::cdp::browser_protocol::target::TargetId;
+use chromiumoxide::handler::{Handler, HandlerConfig};
 use chromiumoxide::Page;
 use futures::StreamExt;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
+fn close_profile(held: HeldProfile) {
+    let profile = held.lock.profile().to_string();
+    if let Err(e) = held.data.close() {
+        tracing::error!(profile = %profile, "Failed to save profile data: {:#}", e);
+    }
+}
+
+/// Connect to the browser behind `--cdp-url`.
+async fn connect_remote(cdp_url: &str, config: HandlerConfig) -> Result<(Browser, Handler)> {
+    let ws_url = remote::resolve_websocket_url(cdp_url).await?;
+    Browser::connect_with_config(ws_url, config)
+        .await
+        .with_context(|| format!("Failed to connect to browser at {}", cdp_url))
+}
+
+/// Connect to the browser behind `--cdp-url` after the connection dropped,
+/// retrying with backoff while it restarts or the network recovers.
+async fn reconnect_remote(cdp_url: &str, config: HandlerConfig) -> Result<(Browser, Handler)> {
+    let mut last_error = None;
+    for (attempt, delay) in remote::reconnect_delays().enumerate() {
+        tokio::time::sleep(delay).await;
+        match connect_remote(cdp_url, config.clone()).await {
+            Ok(connected) => return Ok(connected),
+            Err(e) => {
+                tracing::warn!(attempt = attempt + 1, "Reconnect failed: {:#}", e);
+                last_error = Some(e);
+            }
+        }
+    }
+    Err(last_error
+        .expect("at least one reconnect attempt")
+        .context(format!(
+            "Gave up reconnecting after {} attempts",
+            remote::RECONNECT_ATTEMPTS
+        )))
+}
+
+/// A remote tab's page, once chromiumoxide has attached to it.
+async fn attached_page(browser: &Browser, target: &TargetId) -> Option<Page> {
+    let deadline = tokio::time::Instant::now() + REMOTE_ATTACH_TIMEOUT;
+    loop {
+        if let Ok(page) = browser.get_page(target.clone()).await {
+            return Some(page);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return None;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// Whether a visible browser window can be shown.
+fn display_available() -> bool {
+    if cfg!(target_os = "linux") {
+        std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some()
+    } else {
+        true
+    }
+}
+
 /// Configuration for the BrowserManager.
 #[derive(Debug, Clone)]
 pub struct BrowserManagerConfig {
     /// Custom Chrome/Edge binary path.
     pub browser_path: Option<String>,
-    /// Connect to an already-running browser via CDP URL.
+    /// Connect to an already-running browser via CDP: a WebSocket URL, or an
+    /// HTTP debugging endpoint (`http://host:9222`) to discover it from.
     pub cdp_url: Option<String>,
     /// Run headless (default: true).
     pub headless: bool,
@@ -25,6 +107,31 @@ pub struct BrowserManagerConfig {
     pub window_size: (u32, u32),
     /// Named profile to use for session persistence.
     pub profile: Option<String>,
+    /// Without a profile, launch with a fresh user-data-dir that is deleted
+    /// on shutdown, so nothing persists and concurrent servers never share
+    /// one.
+    pub ephemeral: bool,
+    /// How JavaScript dialogs are answered when they open (default: leave).
+    pub dialog_policy: DialogPolicy,
+    /// Device, viewport and locale emulation applied to every page.
+    pub emulation: EmulationSettings,
+    /// Proxy, extra headers, HTTP auth and certificate settings, overlaid on
+    /// the profile's own settings.
+    pub network: NetworkSettings,
+    /// Extra Chrome arguments and extensions, appended to the profile's own.
+    pub launch: LaunchOptions,
+    /// Shut a launched browser down after this long without tool calls;
+    /// the next call relaunches it and reopens its tabs.
+    pub idle_timeout: Option<Duration>,
+    /// Shut a launched browser down once it has been up this long, at the
+    /// first moment no call is running, to bound its memory growth.
+    pub max_lifetime: Option<Duration>,
+    /// Open pages, returned content size, screenshot size and CDP call
+    /// timeout.
+    pub limits: ResourceLimits,
+    /// Worker browsers for parallel `execute_code` runs; 0 runs scripts on
+    /// the shared browser.
+    pub code_workers: usize,
 }
 
 impl Default for BrowserManagerConfig {
@@ -35,6 +142,15 @@ impl Default for BrowserManagerConfig {
             headless: true,
             window_size: (1280, 720),
             profile: None,
+            ephemeral: false,
+            dialog_policy: DialogPolicy::default(),
+            emulation: EmulationSettings::default(),
+            network: NetworkSettings::default(),
+            launch: LaunchOptions::default(),
+            idle_timeout: None,
+            max_lifetime: None,
+            limits: ResourceLimits::default(),
+            code_workers: 0,
         }
     }
 }
@@ -47,6 +163,48 @@ pub struct PageInfo {
     pub active: bool,
 }
 
+/// How a human login handoff ended.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandoffOutcome {
+    /// The success condition matched.
+    LoggedIn,
+    /// The user closed the login window to say they are done.
+    WindowClosed,
+    /// Neither happened before the timeout.
+    TimedOut,
+}
+
+/// Result of `BrowserManager::human_login`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct HandoffResult {
+    pub outcome: HandoffOutcome,
+    /// Page the headless browser was resumed at.
+    pub url: String,
+    /// True if the success condition was stored as the profile's login check.
+    pub login_check_saved: bool,
+}
+
+/// The active profile while a launched browser uses it.
+struct HeldProfile {
+    /// Declared before `lock` so encrypted data is sealed while still locked.
+    data: ProfileData,
+    lock: ProfileLock,
+}
+
+/// When and with which executable the running browser was launched.
+struct LaunchInfo {
+    at: chrono::DateTime<chrono::Utc>,
+    started: std::time::Instant,
+    executable: Option<String>,
+}
+
+/// How long an adopted remote tab may take to attach.
+const REMOTE_ATTACH_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long each CDP call made for `browser_status` may take.
+const STATUS_CALL_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Tracks all open pages and which one is active.
 #[derive(Default)]
 struct PageState {
@@ -66,16 +224,73 @@ pub struct BrowserManager {
     browser: RwLock<Option<Browser>>,
     handler_handle: RwLock<Option<tokio::task::JoinHandle<()>>>,
     state: RwLock<PageState>,
+    dialogs: Arc<DialogTracker>,
+    /// Last known URL of each tab, for reopening tabs after a crash.
+    tabs: Arc<TabTracker>,
+    crash_stats: std::sync::Mutex<CrashStats>,
+    launch_info: std::sync::Mutex<Option<LaunchInfo>>,
+    /// Recovery not yet reported in a tool response.
+    pending_recovery: std::sync::Mutex<Option<RecoveryReport>>,
+    activity: ActivityTracker,
+    /// Tabs of a browser shut down for idleness, reopened on the next launch.
+    parked_tabs: std::sync::Mutex<Option<TabSnapshot>>,
+    lifecycle_stats: std::sync::Mutex<LifecycleStats>,
+    emulation: RwLock<EmulationSettings>,
+    network: RwLock<NetworkConditions>,
+    /// Effective network settings of the running browser (profile + config).
+    network_settings: RwLock<NetworkSettings>,
+    /// Active profile; starts as `config.profile`, changed by `switch_profile`.
+    profile: RwLock<Option<String>>,
+    /// Lock and (possibly decrypted) data of the active profile, held while
+    /// a launched browser uses it.
+    held_profile: std::sync::Mutex<Option<HeldProfile>>,
+    /// User-data-dir of the running browser in ephemeral mode.
+    ephemeral_dir: std::sync::Mutex<Option<EphemeralDir>>,
+    /// Launch headless; cleared while a human login handoff runs headed.
+    headless: AtomicBool,
+    /// Workers leased by `execute_code`, if `code_workers` is set.
+    workers: Option<Arc<WorkerPool>>,
     config: BrowserManagerConfig,
     profile_manager: Arc<ProfileManager>,
 }
 
 impl BrowserManager {
     pub fn new(config: BrowserManagerConfig, profile_manager: Arc<ProfileManager>) -> Self {
+        let workers = match config.code_workers {
+            0 => None,
+            _ if config.cdp_url.is_some() => {
+                tracing::warn!(
+                    "Code workers are not available for a browser connected via --cdp-url; \
+                     scripts run on the shared browser"
+                );
+                None
+            }
+            size => Some(Arc::new(WorkerPool::new(
+                size,
+                &config,
+                profile_manager.clone(),
+            ))),
+        };
         Self {
             browser: RwLock::new(None),
             handler_handle: RwLock::new(None),
             state: RwLock::new(PageState::default()),
+            dialogs: Arc::new(DialogTracker::default()),
+            tabs: Arc::new(TabTracker::default()),
+            crash_stats: std::sync::Mutex::new(CrashStats::default()),
+            launch_info: std::sync::Mutex::new(None),
+            pending_recovery: std::sync::Mutex::new(None),
+            activity: ActivityTracker::default(),
+            parked_tabs: std::sync::Mutex::new(None),
+            lifecycle_stats: std::sync::Mutex::new(LifecycleStats::default()),
+            emulation: RwLock::new(config.emulation.clone()),
+            network: RwLock::new(NetworkConditions::default()),
+            network_settings: RwLock::new(NetworkSettings::default()),
+            profile: RwLock::new(config.profile.clone()),
+            held_profile: std::sync::Mutex::new(None),
+            ephemeral_dir: std::sync::Mutex::new(None),
+            headless: AtomicBool::new(config.headless),
+            workers,
             config,
             profile_manager,
         }
@@ -89,7 +304,8 @@ impl BrowserManager {
 
     /// Ensure a browser is running, launching one if needed.
     /// Detects crashed browsers by checking if the CDP handler task has exited,
-    /// and automatically re-launches.
+    /// and automatically re-launches, reopening the tabs that were open. Tabs
+    /// parked by an idle shutdown are reopened the same way.
     pub async fn ensure_browser(&self) -> Result<()> {
         // Fast path: browser already running and handler is alive
         {
@@ -99,31 +315,79 @@ impl BrowserManager {
             }
         }
 
-        // Clear stale page state before acquiring browser write lock.
-        // (Lock ordering: state before browser, matching page()'s slow path.)
-        {
-            let mut state = self.state.write().await;
-            if !state.pages.is_empty() {
-                tracing::info!("Clearing {} stale page references", state.pages.len());
-                state.pages.clear();
-                state.active_idx = 0;
-            }
-        }
-
+        // Lock ordering: state before browser, matching page()'s slow path.
+        let mut state = self.state.write().await;
         let mut browser_guard = self.browser.write().await;
-        // Double-check after acquiring write lock
+        // Double-check after acquiring the locks
         if browser_guard.is_some() && !self.is_browser_dead().await {
             return Ok(());
         }
 
+        // A browser that is still set here died under us. For a remote
+        // browser that means the connection dropped; the browser itself
+        // likely still runs, with its tabs.
+        let died = browser_guard.is_some();
+        let remote = self.config.cdp_url.is_some();
+        let crashed = died && !remote;
+        let reconnecting = died && remote;
+        // Remote tabs are adopted again, so only local ones are reopened.
+        // Without a crash, tabs parked by an idle shutdown are.
+        let snapshot = if !died {
+            self.parked_tabs.lock().unwrap().take()
+        } else if crashed {
+            self.snapshot_tabs(&state)
+        } else {
+            None
+        };
+        let previous_targets: Vec<TargetId> = state
+            .pages
+            .iter()
+            .map(|page| page.target_id().clone())
+            .collect();
+        let previous_active = previous_targets.get(state.active_idx).cloned();
+
+        if !state.pages.is_empty() {
+            tracing::info!("Clearing {} stale page references", state.pages.len());
+            state.pages.clear();
+            state.active_idx = 0;
+        }
+        self.dialogs.clear();
+        self.tabs.clear();
+
         if let Some(mut old_browser) = browser_guard.take() {
-            tracing::warn!("Browser CDP handler exited — closing stale browser before re-launch");
-            let _ = old_browser.close().await;
-            let _ = old_browser.wait().await;
-            let _ = old_browser.kill().await;
+            if reconnecting {
+                tracing::warn!("Connection to the remote browser dropped — reconnecting");
+            } else {
+                tracing::warn!(
+                    "Browser CDP handler exited — closing stale browser before re-launch"
+                );
+                let _ = old_browser.close().await;
+                let _ = old_browser.wait().await;
+                let _ = old_browser.kill().await;
+            }
+        }
+        if died {
+            let mut stats = self.crash_stats.lock().unwrap();
+            if reconnecting {
+                stats.disconnects += 1;
+                stats.last_disconnect_at = Some(chrono::Utc::now());
+            } else {
+                stats.crashes += 1;
+                stats.last_crash_at = Some(chrono::Utc::now());
+            }
         }
 
-        let (browser, handle) = self.launch_browser().await?;
+        let (mut browser, handle) = match self.launch_browser(reconnecting).await {
+            Ok(launched) => launched,
+            Err(e) => {
+                self.release_profile();
+                // Keep the tabs for the next attempt.
+                if snapshot.is_some() {
+                    *self.parked_tabs.lock().unwrap() = snapshot;
+                }
+                return Err(e);
+            }
+        };
 
         // Store handler handle for liveness checking
         {
@@ -131,17 +395,421 @@ impl BrowserManager {
             *handle_guard = Some(handle);
         }
 
+        if crashed {
+            let report = match snapshot {
+                Some(snapshot) => {
+                    let report = self.restore_tabs(&browser, &mut state, snapshot).await;
+                    tracing::warn!(
+                        restored = report.restored.len(),
+                        failed = report.failed.len(),
+                        "Browser relaunched after a crash; reopened its tabs"
+                    );
+                    Some(report)
+                }
+                None => None,
+            };
+            let mut stats = self.crash_stats.lock().unwrap();
+            stats.restarts += 1;
+            if report.is_some() {
+                stats.last_recovery = report.clone();
+            }
+            *self.pending_recovery.lock().unwrap() = report;
+        } else if let Some(snapshot) = snapshot {
+            let report = self.restore_tabs(&browser, &mut state, snapshot).await;
+            tracing::info!(
+                restored = report.restored.len(),
+                failed = report.failed.len(),
+                "Browser relaunched; reopened the previous browser's tabs"
+            );
+        } else if remote {
+            self.adopt_remote_tabs(&mut browser, &mut state, &previous_targets, previous_active)
+                .await;
+            if reconnecting {
+                self.crash_stats.lock().unwrap().reconnects += 1;
+                tracing::info!(
+                    tabs = state.pages.len(),
+                    "Reconnected to the remote browser"
+                );
+            }
+        }
+
         *browser_guard = Some(browser);
         Ok(())
     }
 
+    /// Adopt the tabs a remote browser already has, keeping the order and
+    /// active tab from before a reconnect where those tabs still exist.
+    async fn adopt_remote_tabs(
+        &self,
+        browser: &mut Browser,
+        state: &mut PageState,
+        previous: &[TargetId],
+        previous_active: Option<TargetId>,
+    ) {
+        let targets = match browser.fetch_targets().await {
+            Ok(targets) => targets,
+            Err(e) => {
+                tracing::warn!("Failed to list the remote browser's tabs: {}", e);
+                return;
+            }
+        };
+        let found = targets
+            .into_iter()
+            .filter(|target| target.r#type == "page")
+            .map(|target| target.target_id)
+            .collect();
+
+        for target in remote::order_targets(found, previous) {
+            match attached_page(browser, &target).await {
+                Some(page) => {
+                    self.prepare_page(&page).await;
+                    state.pages.push(page);
+                }
+                None => tracing::warn!(target = ?target, "Remote tab did not attach; skipping it"),
+            }
+        }
+        state.active_idx = previous_active
+            .and_then(|active| {
+                state
+                    .pages
+                    .iter()
+                    .position(|page| page.target_id() == &active)
+            })
+            .unwrap_or(0);
+        if !state.pages.is_empty() {
+            tracing::info!("Adopted {} tabs of the remote browser", state.pages.len());
+        }
+    }
+
+    /// URLs of the open tabs and which one is active, if any are open.
+    fn snapshot_tabs(&self, state: &PageState) -> Option<TabSnapshot> {
+        (!state.pages.is_empty()).then(|| TabSnapshot {
+            urls: state
+                .pages
+                .iter()
+                .map(|page| {
+                    self.tabs
+                        .url(page.target_id())
+                        .unwrap_or_else(|| "about:blank".to_string())
+                })
+                .collect(),
+            active_idx: state.active_idx,
+        })
+    }
+
+    /// Reopen a crashed or parked browser's tabs in order. A tab whose URL
+    /// fails to load stays blank so tab indices are unchanged.
+    async fn restore_tabs(
+        &self,
+        browser: &Browser,
+        state: &mut PageState,
+        snapshot: TabSnapshot,
+    ) -> RecoveryReport {
+        let mut failed = Vec::new();
+        for url in &snapshot.urls {
+            let page = match browser.new_page("about:blank").await {
+                Ok(page) => page,
+                Err(e) => {
+                    tracing::warn!("Failed to reopen tab {}: {}", url, e);
+                    failed.push(url.clone());
+                    continue;
+                }
+            };
+            self.prepare_page(&page).await;
+            if url != "about:blank" {
+                if let Err(e) = page.goto(url.as_str()).await {
+                    tracing::warn!("Failed to reload {} after relaunch: {}", url, e);
+                    failed.push(url.clone());
+                }
+            }
+            state.pages.push(page);
+        }
+        state.active_idx = snapshot.active_idx.min(state.pages.len().saturating_sub(1));
+
+        RecoveryReport {
+            recovered_at: chrono::Utc::now(),
+            restored: snapshot.urls,
+            failed,
+            active_index: state.active_idx,
+        }
+    }
+
+    /// Whether a browser is up, without launching one.
+    pub async fn is_running(&self) -> bool {
+        self.browser.read().await.is_some() && !self.is_browser_dead().await
+    }
+
+    /// Crash and relaunch counters since the manager was created.
+    pub fn crash_stats(&self) -> CrashStats {
+        self.crash_stats.lock().unwrap().clone()
+    }
+
+    fn record_launch(&self, executable: Option<String>) {
+        *self.launch_info.lock().unwrap() = Some(LaunchInfo {
+            at: chrono::Utc::now(),
+            started: std::time::Instant::now(),
+            executable,
+        });
+    }
+
+    /// Status and resource usage of the browser, without launching one.
+    /// Parts that cannot be collected are listed in `warnings`.
+    pub async fn status(&self) -> BrowserStatus {
+        let running = self.is_running().await;
+        let mode = if self.config.cdp_url.is_some() {
+            LaunchMode::CdpUrl
+        } else {
+            LaunchMode::Local
+        };
+        let (launched_at, uptime_secs, executable) = match &*self.launch_info.lock().unwrap() {
+            Some(info) if running => (
+                Some(info.at),
+                Some(info.started.elapsed().as_secs()),
+                info.executable.clone(),
+            ),
+            _ => (None, None, None),
+        };
+        let pages = self.state.read().await.pages.clone();
+
+        let mut status = BrowserStatus {
+            running,
+            mode,
+            cdp_url: self.config.cdp_url.clone(),
+            executable,
+            version: None,
+            profile: self.current_profile().await,
+            ephemeral: self.config.ephemeral,
+            headless: self.headless.load(Ordering::SeqCst),
+            launched_at,
+            uptime_secs,
+            pages: pages.len(),
+            crash_stats: self.crash_stats(),
+            lifecycle_stats: self.lifecycle_stats(),
+            idle_secs: self.activity.idle_for().as_secs(),
+            parked_tabs: self
+                .parked_tabs
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map_or(0, |tabs| tabs.urls.len()),
+            code_workers: self.workers.as_ref().map(|pool| WorkerPoolStatus {
+                size: pool.size(),
+                busy: pool.busy(),
+                queued: pool.queued(),
+            }),
+            processes: Vec::new(),
+            page_metrics: Vec::new(),
+            total_memory_bytes: None,
+            total_cpu_time_secs: 0.0,
+            warnings: Vec::new(),
+        };
+        if !running {
+            return status;
+        }
+
+        {
+            let browser_guard = self.browser.read().await;
+            if let Some(browser) = browser_guard.as_ref() {
+                match tokio::time::timeout(STATUS_CALL_TIMEOUT, browser.version()).await {
+                    Ok(Ok(version)) => {
+                        status.version = Some(BrowserVersion {
+                            product: version.product,
+                            protocol_version: version.protocol_version,
+                            user_agent: version.user_agent,
+                        })
+                    }
+                    Ok(Err(e)) => status.warnings.push(format!("version: {}", e)),
+                    Err(_) => status.warnings.push("version: timed out".to_string()),
+                }
+
+                let process_info = browser.execute(
+                    chromiumoxide::cdp::browser_protocol::system_info::GetProcessInfoParams::default(),
+                );
+                match tokio::time::timeout(STATUS_CALL_TIMEOUT, process_info).await {
+                    Ok(Ok(info)) => {
+                        // Remote pids belong to another host.
+                        let local = mode == LaunchMode::Local;
+                        status.processes = info
+                            .result
+                            .process_info
+                            .iter()
+                            .map(|p| ProcessUsage {
+                                kind: p.r#type.clone(),
+                                pid: p.id,
+                                cpu_time_secs: p.cpu_time,
+                                memory_bytes: if local {
+                                    crate::status::process_memory(p.id)
+                                } else {
+                                    None
+                                },
+                            })
+                            .collect();
+                    }
+                    Ok(Err(e)) => status.warnings.push(format!("processes: {}", e)),
+                    Err(_) => status.warnings.push("processes: timed out".to_string()),
+                }
+            }
+        }
+
+        for (index, page) in pages.iter().enumerate() {
+            let url = self.tabs.url(page.target_id()).unwrap_or_default();
+            let metrics = async {
+                page.execute(
+                    chromiumoxide::cdp::browser_protocol::performance::EnableParams::default(),
+                )
+                .await?;
+                let metrics = page
+                    .execute(
+                        chromiumoxide::cdp::browser_protocol::performance::GetMetricsParams::default(),
+                    )
+                    .await;
+                // A status poll leaves no metrics collection running.
+                let _ = page
+                    .execute(
+                        chromiumoxide::cdp::browser_protocol::performance::DisableParams::default(),
+                    )
+                    .await;
+                metrics
+            };
+            match tokio::time::timeout(STATUS_CALL_TIMEOUT, metrics).await {
+                Ok(Ok(metrics)) => status.page_metrics.push(PageMetrics::from_metrics(
+                    index,
+                    url,
+                    &metrics.result.metrics,
+                )),
+                Ok(Err(e)) => status
+                    .warnings
+                    .push(format!("page {} metrics: {}", index, e)),
+                Err(_) => status
+                    .warnings
+                    .push(format!("page {} metrics: timed out", index)),
+            }
+        }
+
+        status.with_totals()
+    }
+
+    /// Mark a tool call as running until the guard is dropped. The browser
+    /// is never shut down for idleness while a call is running.
+    pub async fn begin_activity(&self) -> ActivityGuard<'_> {
+        self.activity.begin().await
+    }
+
+    /// Idle shutdown and recycle counters since the manager was created.
+    pub fn lifecycle_stats(&self) -> LifecycleStats {
+        self.lifecycle_stats.lock().unwrap().clone()
+    }
+
+    /// Start shutting the browser down when `idle_timeout` or
+    /// `max_lifetime` passes. Does nothing unless one is configured; must be
+    /// called within a Tokio runtime otherwise. The task ends when the
+    /// manager is dropped.
+    pub fn spawn_lifecycle_monitor(self: &Arc<Self>) {
+        let (idle_timeout, max_lifetime) = (self.config.idle_timeout, self.config.max_lifetime);
+        if idle_timeout.is_none() && max_lifetime.is_none() {
+            return;
+        }
+        if self.config.cdp_url.is_some() {
+            tracing::warn!(
+                "Idle timeout and max lifetime are ignored for a browser connected via --cdp-url"
+            );
+            return;
+        }
+
+        let interval = lifecycle::poll_interval(idle_timeout, max_lifetime);
+        let manager = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                manager.check_lifecycle().await;
+            }
+        });
+    }
+
+    /// Park the browser if it is idle or past its max lifetime and no call
+    /// is running.
+    async fn check_lifecycle(&self) {
+        if !self.is_running().await {
+            return;
+        }
+        let uptime = match &*self.launch_info.lock().unwrap() {
+            Some(info) => info.started.elapsed(),
+            None => return,
+        };
+        let Some(reason) = lifecycle::park_reason(
+            self.activity.idle_for(),
+            uptime,
+            self.config.idle_timeout,
+            self.config.max_lifetime,
+        ) else {
+            return;
+        };
+        // Calls that start now wait until the browser is parked, then
+        // relaunch it.
+        let Some(_quiet) = self.activity.try_quiesce() else {
+            return;
+        };
+        self.park(reason).await;
+    }
+
+    /// Shut the browser down, keeping its tabs' URLs for the next launch.
+    async fn park(&self, reason: ParkReason) {
+        {
+            let mut state = self.state.write().await;
+            let snapshot = self.snapshot_tabs(&state);
+            state.pages.clear();
+            state.active_idx = 0;
+            self.dialogs.clear();
+            self.tabs.clear();
+            *self.parked_tabs.lock().unwrap() = snapshot;
+        }
+        match reason {
+            ParkReason::Idle => tracing::info!("Shutting down idle browser"),
+            ParkReason::MaxLifetime => {
+                tracing::info!("Recycling browser that reached its max lifetime")
+            }
+        }
+        self.shutdown().await;
+        self.lifecycle_stats.lock().unwrap().record(reason);
+    }
+
+    /// A crash recovery that no tool response has reported yet.
+    pub fn take_recovery(&self) -> Option<RecoveryReport> {
+        self.pending_recovery.lock().unwrap().take()
+    }
+
     /// Launch (or connect to) a browser, returning the Browser and the handler task.
-    async fn launch_browser(&self) -> Result<(Browser, tokio::task::JoinHandle<()>)> {
+    /// `reconnect` retries connecting to a remote browser with backoff.
+    async fn launch_browser(
+        &self,
+        reconnect: bool,
+    ) -> Result<(Browser, tokio::task::JoinHandle<()>)> {
+        let profile = self.current_profile().await;
+        let network = self.effective_network_settings(profile.as_deref())?;
+        *self.network_settings.write().await = network.clone();
+
         if let Some(ref cdp_url) = self.config.cdp_url {
-            let (browser, mut handler) = Browser::connect(cdp_url)
-                .await
-                .with_context(|| format!("Failed to connect to browser at {}", cdp_url))?;
+            if !network.chrome_args().is_empty() || !self.config.launch.is_empty() {
+                tracing::warn!(
+                    "Proxy, certificate and launch settings are launch flags and have no \
+                     effect on a browser connected via --cdp-url"
+                );
+            }
+            let handler_config = HandlerConfig {
+                ignore_https_errors: network.ignores_https_errors(),
+                request_timeout: self.config.limits.cdp_timeout,
+                ..Default::default()
+            };
+            let (browser, mut handler) = if reconnect {
+                reconnect_remote(cdp_url, handler_config).await?
+            } else {
+                connect_remote(cdp_url, handler_config).await?
+            };
 
+            self.record_launch(None);
             let url = cdp_url.clone();
             let handle = tokio::spawn(async move {
                 while let Some(h) = handler.next().await {
@@ -156,47 +824,76 @@ impl BrowserManager {
             Ok((browser, handle))
         } else {
             let mut builder = BrowserConfig::builder();
+            let mut executable = self.config.browser_path.clone();
 
+            // An explicit browser path wins; otherwise launch the browser the
+            // profile was created with.
             if let Some(ref path) = self.config.browser_path {
                 builder = builder.chrome_executable(path);
+            } else if let Some(ref profile_name) = profile {
+                let channel = self
+                    .profile_manager
+                    .get_profile(profile_name)?
+                    .browser_channel;
+                let detected = check::find_browser_for_channel(&channel)
+                    .with_context(|| format!("Cannot launch profile '{}'", profile_name))?;
+                tracing::info!(
+                    "Using {} at {} for profile '{}'",
+                    detected.name,
+                    detected.path,
+                    profile_name
+                );
+                builder = builder.chrome_executable(&detected.path);
+                executable = Some(detected.path);
             }
 
-            if !self.config.headless {
+            let launch = self.effective_launch_options(profile.as_deref())?;
+
+            if !self.headless.load(Ordering::SeqCst) {
                 builder = builder.with_head();
+            } else if !launch.extensions.is_empty() {
+                // Only the new headless mode can load extensions.
+                builder = builder.new_headless_mode();
             }
 
             builder = builder.window_size(self.config.window_size.0, self.config.window_size.1);
 
             // Profile support: set user-data-dir for session persistence
-            if let Some(ref profile_name) = self.config.profile {
-                let user_data_dir = self.profile_manager.user_data_dir(profile_name)?;
+            if let Some(ref profile_name) = profile {
+                let user_data_dir = self.hold_profile(profile_name)?;
                 builder = builder.user_data_dir(user_data_dir);
                 let _ = self.profile_manager.touch_profile(profile_name);
+            } else if self.config.ephemeral {
+                builder = builder.user_data_dir(self.fresh_ephemeral_dir()?);
+            } else {
+                ephemeral::remove_stale_singleton_lock(&ephemeral::shared_dir());
             }
 
             // Chrome args for stability and compatibility
             builder = builder
                 .arg("--disable-dev-shm-usage")
-                .arg("--remote-allow-origins=*");
-
-            // Remove stale SingletonLock left by crashed browsers. When no
-            // profile is set, chromiumoxide uses a shared temp dir that can
-            // accumulate lock files across ungraceful shutdowns.
-            if self.config.profile.is_none() {
-                let lock_path = std::env::temp_dir()
-                    .join("chromiumoxide-runner")
-                    .join("SingletonLock");
-                if lock_path.exists() {
-                    tracing::warn!("Removing stale SingletonLock at {:?}", lock_path);
-                    let _ = std::fs::remove_file(&lock_path);
-                }
+                .arg("--remote-allow-origins=*")
+                .args(network.chrome_args())
+                .args(launch.args.iter())
+                .extensions(launch.extension_paths());
+
+            if !network.ignores_https_errors() {
+                builder = builder.respect_https_errors();
             }
+            builder = builder.request_timeout(self.config.limits.cdp_timeout);
 
             let config = builder.build().map_err(|e| anyhow::anyhow!("{}", e))?;
 
             let (browser, mut handler) = Browser::launch(config)
                 .await
                 .context("Failed to launch browser")?;
+            // Without an explicit path chromiumoxide detects the browser the
+            // same way.
+            self.record_launch(executable.or_else(|| {
+                chromiumoxide::detection::default_executable(Default::default())
+                    .ok()
+                    .map(|path| path.display().to_string())
+            }));
 
             let handle = tokio::spawn(async move {
                 while let Some(h) = handler.next().await {
@@ -239,6 +936,7 @@ impl BrowserManager {
             .new_page("about:blank")
             .await
             .context("Failed to create new page")?;
+        self.prepare_page(&page).await;
 
         state.pages.push(page.clone());
         state.active_idx = 0;
@@ -246,18 +944,40 @@ impl BrowserManager {
     }
 
     /// Create a new page (tab) and make it active. Returns the page index.
+    ///
+    /// Fails with `LimitExceeded` once `limits.max_pages` pages are open.
     pub async fn create_new_page(&self, url: &str) -> Result<(usize, Page)> {
         self.ensure_browser().await?;
+        let max_pages = self.config.limits.max_pages;
+        if self.state.read().await.pages.len() >= max_pages {
+            return Err(LimitExceeded::max_pages(max_pages).into());
+        }
 
-        let browser_guard = self.browser.read().await;
-        let browser = browser_guard.as_ref().context("Browser not initialized")?;
-
-        let page = browser
-            .new_page(url)
-            .await
-            .with_context(|| format!("Failed to create page for {}", url))?;
+        // Open a blank page first so headers, auth and emulation are in
+        // place before the target URL loads. The browser lock is released
+        // before taking the state lock (see ensure_browser).
+        let page = {
+            let browser_guard = self.browser.read().await;
+            let browser = browser_guard.as_ref().context("Browser not initialized")?;
+            browser
+                .new_page("about:blank")
+                .await
+                .with_context(|| format!("Failed to create page for {}", url))?
+        };
+        self.prepare_page(&page).await;
+        if url != "about:blank" {
+            page.goto(url)
+                .await
+                .with_context(|| format!("Failed to navigate to {}", url))?;
+        }
 
         let mut state = self.state.write().await;
+        // Another call may have opened a page meanwhile.
+        if state.pages.len() >= max_pages {
+            drop(state);
+            let _ = page.close().await;
+            return Err(LimitExceeded::max_pages(max_pages).into());
+        }
         let idx = state.pages.len();
         state.pages.push(page.clone());
         state.active_idx = idx;
@@ -290,18 +1010,25 @@ impl BrowserManager {
         Ok(infos)
     }
 
-    /// Switch the active page by index.
+    /// Switch the active page by index and bring it to the front, so it
+    /// renders frames like a foreground tab.
     pub async fn select_page(&self, idx: usize) -> Result<Page> {
-        let mut state = self.state.write().await;
-        if idx >= state.pages.len() {
-            anyhow::bail!(
-                "Page index {} out of range (have {} pages)",
-                idx,
-                state.pages.len()
-            );
+        let page = {
+            let mut state = self.state.write().await;
+            if idx >= state.pages.len() {
+                anyhow::bail!(
+                    "Page index {} out of range (have {} pages)",
+                    idx,
+                    state.pages.len()
+                );
+            }
+            state.active_idx = idx;
+            state.pages[idx].clone()
+        };
+        if let Err(e) = page.bring_to_front().await {
+            tracing::warn!("Failed to bring page {} to the front: {}", idx, e);
         }
-        state.active_idx = idx;
-        Ok(state.pages[idx].clone())
+        Ok(page)
     }
 
     /// Close a page by index. Cannot close the last page.
@@ -318,7 +1045,9 @@ impl BrowserManager {
             anyhow::bail!("Cannot close the last page");
         }
 
-        state.pages.remove(idx);
+        let page = state.pages.remove(idx);
+        self.dialogs.forget(page.target_id());
+        self.tabs.forget(page.target_id());
 
         // Adjust active index if needed
         if state.active_idx >= state.pages.len() {
@@ -328,6 +1057,493 @@ impl BrowserManager {
         Ok(())
     }
 
+    /// Dialog records for all pages.
+    pub fn dialogs(&self) -> &DialogTracker {
+        &self.dialogs
+    }
+
+    /// Set up a new page: dialog tracking, network settings and emulation.
+    /// Failures are logged, not fatal.
+    async fn prepare_page(&self, page: &Page) {
+        if let Err(e) = self.dialogs.watch(page, self.config.dialog_policy).await {
+            tracing::warn!("Dialog tracking unavailable for page: {:#}", e);
+        }
+        if let Err(e) = self.tabs.watch(page).await {
+            tracing::warn!("Tab tracking unavailable for page: {:#}", e);
+        }
+
+        let network = self.network_settings.read().await.clone();
+        if let Err(e) = network.apply(page).await {
+            tracing::warn!("Failed to apply network settings to page: {:#}", e);
+        }
+
+        self.apply_emulation(page).await;
+    }
+
+    /// Take the profile's lock and open its data unless already held,
+    /// returning the user-data-dir to launch with. Kept across crash
+    /// relaunches of the same profile.
+    fn hold_profile(&self, name: &str) -> Result<std::path::PathBuf> {
+        let mut held = self.held_profile.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(current) = held.as_ref().filter(|h| h.lock.profile() == name) {
+            return Ok(current.data.dir().to_path_buf());
+        }
+        if let Some(previous) = held.take() {
+            close_profile(previous);
+        }
+        let lock = self.profile_manager.lock_profile(name)?;
+        let data = self.profile_manager.open_profile_data(name)?;
+        let dir = data.dir().to_path_buf();
+        *held = Some(HeldProfile { data, lock });
+        Ok(dir)
+    }
+
+    /// Save (re-encrypt) the active profile's data and release its lock.
+    /// Call only once the browser has exited.
+    fn release_profile(&self) {
+        let held = self
+            .held_profile
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(held) = held {
+            close_profile(held);
+        }
+    }
+
+    /// Replace the ephemeral user-data-dir with a new one, deleting the
+    /// previous browser's. Only called once that browser has exited.
+    fn fresh_ephemeral_dir(&self) -> Result<std::path::PathBuf> {
+        let mut held = self.ephemeral_dir.lock().unwrap_or_else(|e| e.into_inner());
+        *held = None;
+        let dir = EphemeralDir::create()?;
+        let path = dir.path().to_path_buf();
+        *held = Some(dir);
+        Ok(path)
+    }
+
+    /// The profile's launch options followed by the configured ones.
+    fn effective_launch_options(&self, profile: Option<&str>) -> Result<LaunchOptions> {
+        let profile_launch = match profile {
+            Some(name) => self.profile_manager.get_profile(name)?.launch,
+            None => LaunchOptions::default(),
+        };
+        profile_launch.overlay(&self.config.launch).validated()
+    }
+
+    /// The profile's network settings overlaid with the configured ones.
+    fn effective_network_settings(&self, profile: Option<&str>) -> Result<NetworkSettings> {
+        let profile_network = match profile {
+            Some(name) => self.profile_manager.get_profile(name)?.network,
+            None => NetworkSettings::default(),
+        };
+        let network = profile_network.overlay(&self.config.network);
+        network.auth_credentials()?;
+        Ok(network)
+    }
+
+    /// Current emulation settings.
+    pub async fn emulation(&self) -> EmulationSettings {
+        self.emulation.read().await.clone()
+    }
+
+    /// Update emulation settings and apply them to every open page.
+    ///
+    /// Fields set in `update` override the current settings. With `reset`,
+    /// all existing overrides are cleared first and only `update` remains.
+    /// New pages pick up the stored settings when they are created.
+    pub async fn set_emulation(
+        &self,
+        update: EmulationSettings,
+        reset: bool,
+    ) -> Result<ResolvedEmulation> {
+        let mut settings = if reset {
+            EmulationSettings::default()
+        } else {
+            self.emulation().await
+        };
+        settings.merge(update);
+        let resolved = settings.resolve()?;
+
+        self.ensure_browser().await?;
+        let default_user_agent = if reset {
+            Some(self.default_user_agent().await?)
+        } else {
+            None
+        };
+        let pages = self.state.read().await.pages.clone();
+        for page in &pages {
+            if let Some(ref user_agent) = default_user_agent {
+                ResolvedEmulation::clear(page, user_agent).await?;
+            }
+            resolved.apply(page).await?;
+        }
+
+        *self.emulation.write().await = settings;
+        Ok(resolved)
+    }
+
+    /// The browser's user agent without any page override.
+    async fn default_user_agent(&self) -> Result<String> {
+        let browser_guard = self.browser.read().await;
+        let browser = browser_guard.as_ref().context("Browser not initialized")?;
+        let version = browser
+            .version()
+            .await
+            .context("Failed to read browser version")?;
+        Ok(version.user_agent)
+    }
+
+    /// Current network conditions.
+    pub async fn network_conditions(&self) -> NetworkConditions {
+        *self.network.read().await
+    }
+
+    /// Update network conditions and apply them to every open page.
+    /// New pages pick up the stored conditions when they are created.
+    pub async fn set_network_conditions(
+        &self,
+        update: &NetworkConditionsUpdate,
+    ) -> Result<NetworkConditions> {
+        let conditions = self.network_conditions().await.updated(update)?;
+
+        self.ensure_browser().await?;
+        let pages = self.state.read().await.pages.clone();
+        for page in &pages {
+            conditions.apply(page).await?;
+        }
+
+        *self.network.write().await = conditions;
+        Ok(conditions)
+    }
+
+    /// Apply the stored emulation settings and network conditions to a new
+    /// page. Failure is logged, not fatal.
+    async fn apply_emulation(&self, page: &Page) {
+        let settings = self.emulation.read().await;
+        if !settings.is_empty() {
+            let result = match settings.resolve() {
+                Ok(resolved) => resolved.apply(page).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                tracing::warn!("Failed to apply emulation to page: {:#}", e);
+            }
+        }
+
+        let network = self.network_conditions().await;
+        if !network.is_default() {
+            if let Err(e) = network.apply(page).await {
+                tracing::warn!("Failed to apply network conditions to page: {:#}", e);
+            }
+        }
+    }
+
+    /// The profile manager backing this browser.
+    pub fn profile_manager(&self) -> &ProfileManager {
+        &self.profile_manager
+    }
+
+    /// Lease a browser for one `execute_code` run: a pooled worker when
+    /// `code_workers` is set, waiting for one to be free, otherwise this
+    /// manager's own browser.
+    pub async fn lease_code_worker(self: &Arc<Self>) -> WorkerLease {
+        match &self.workers {
+            Some(pool) => pool.lease().await,
+            None => WorkerLease::shared(self.clone()),
+        }
+    }
+
+    /// Start a clean session for the next `execute_code` run on a worker:
+    /// close every page, move into a fresh incognito context so no cookies
+    /// or storage carry over, and drop the previous run's emulation and
+    /// network conditions and parked tabs.
+    pub(crate) async fn reset_session(&self) -> Result<()> {
+        self.parked_tabs.lock().unwrap().take();
+        self.pending_recovery.lock().unwrap().take();
+        *self.emulation.write().await = self.config.emulation.clone();
+        *self.network.write().await = NetworkConditions::default();
+
+        let mut state = self.state.write().await;
+        let mut browser_guard = self.browser.write().await;
+        let Some(browser) = browser_guard.as_mut() else {
+            return Ok(());
+        };
+        for page in state.pages.drain(..) {
+            self.dialogs.forget(page.target_id());
+            self.tabs.forget(page.target_id());
+            let _ = page.close().await;
+        }
+        state.active_idx = 0;
+        browser
+            .quit_incognito_context()
+            .await
+            .context("Failed to close the previous browser context")?;
+        browser
+            .start_incognito_context()
+            .await
+            .context("Failed to create a browser context")?;
+        Ok(())
+    }
+
+    /// Worker browsers for `execute_code`, if configured.
+    pub fn worker_pool(&self) -> Option<&Arc<WorkerPool>> {
+        self.workers.as_ref()
+    }
+
+    /// Limits on pages, returned content, screenshots and CDP calls.
+    pub fn limits(&self) -> &ResourceLimits {
+        &self.config.limits
+    }
+
+    /// Name of the active profile, if any.
+    pub async fn current_profile(&self) -> Option<String> {
+        self.profile.read().await.clone()
+    }
+
+    /// Shut down the browser and relaunch it with another profile's
+    /// user-data-dir. Open pages are closed.
+    ///
+    /// If the new profile fails to launch, the previous profile is restored
+    /// (and relaunched on the next operation) and the error is returned.
+    pub async fn switch_profile(&self, name: &str) -> Result<()> {
+        if self.config.cdp_url.is_some() {
+            anyhow::bail!("Cannot switch profiles on a browser connected via --cdp-url");
+        }
+        self.profile_manager.get_profile(name)?;
+
+        self.shutdown().await;
+        {
+            let mut state = self.state.write().await;
+            state.pages.clear();
+            state.active_idx = 0;
+        }
+        self.dialogs.clear();
+        self.tabs.clear();
+        self.parked_tabs.lock().unwrap().take();
+
+        let previous = self.profile.write().await.replace(name.to_string());
+        tracing::info!(from = ?previous, to = %name, "Switching browser profile");
+
+        if let Err(e) = self.ensure_browser().await {
+            *self.profile.write().await = previous;
+            return Err(e.context(format!("Failed to launch profile '{}'", name)));
+        }
+        Ok(())
+    }
+
+    /// Run a profile's login check in a headless browser and record the
+    /// result in its metadata.
+    ///
+    /// The active profile is checked in a new tab of the running browser,
+    /// since Chrome cannot open the same user-data-dir twice; any other
+    /// profile gets a short-lived browser of its own.
+    pub async fn verify_profile(&self, name: &str) -> Result<VerificationResult> {
+        let profile = self.profile_manager.get_profile(name)?;
+        let check = profile
+            .login_check
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' has no login check configured", name))?;
+
+        let result = if self.current_profile().await.as_deref() == Some(name) {
+            self.ensure_browser().await?;
+            let page = {
+                let browser_guard = self.browser.read().await;
+                let browser = browser_guard.as_ref().context("Browser not initialized")?;
+                browser
+                    .new_page("about:blank")
+                    .await
+                    .context("Failed to open page for login check")?
+            };
+            self.prepare_page(&page).await;
+            let result = check.run(&page).await;
+            let _ = page.close().await;
+            result?
+        } else {
+            // The check sees the profile as a plain desktop browser would.
+            let config = BrowserManagerConfig {
+                cdp_url: None,
+                headless: true,
+                profile: Some(name.to_string()),
+                emulation: EmulationSettings::default(),
+                idle_timeout: None,
+                max_lifetime: None,
+                code_workers: 0,
+                ..self.config.clone()
+            };
+            let verifier = BrowserManager::new(config, self.profile_manager.clone());
+            let result = async { check.run(&verifier.page().await?).await }.await;
+            verifier.shutdown().await;
+            result?
+        };
+
+        self.profile_manager.record_verification(name, &result)?;
+        Ok(result)
+    }
+
+    /// Hand the browser to a human to log in, then resume.
+    ///
+    /// Relaunches the active profile headed at `url` (default: the active
+    /// page's URL, or the profile's login check URL), waits until `success`
+    /// matches, the user closes the window, or `timeout` passes, then
+    /// relaunches headless with the same profile, reopening the tabs that
+    /// were open plus the page the user ended on as the active tab. A
+    /// detected login is recorded as a passed verification, and the
+    /// success condition becomes the profile's login check if it has none.
+    pub async fn human_login(
+        &self,
+        url: Option<&str>,
+        success: &LoginSuccess,
+        timeout: Duration,
+    ) -> Result<HandoffResult> {
+        if self.config.cdp_url.is_some() {
+            anyhow::bail!("Cannot hand off a browser connected via --cdp-url");
+        }
+        let name = self.current_profile().await.ok_or_else(|| {
+            anyhow::anyhow!(
+                "A human login needs a profile (start the server with --profile) so the \
+                 session survives relaunching the browser"
+            )
+        })?;
+        if !display_available() {
+            anyhow::bail!(
+                "No display is available for a visible browser window. Run `browser-server \
+                 setup-login --profile {}` on a machine with a display, then copy the profile \
+                 with `profile export` / `profile import`.",
+                name
+            );
+        }
+
+        let profile = self.profile_manager.get_profile(&name)?;
+        let url = match url {
+            Some(url) => url.to_string(),
+            None => match self.active_url().await {
+                Some(url) => url,
+                None => profile
+                    .login_check
+                    .as_ref()
+                    .map(|check| check.url.clone())
+                    .context("No page is open; pass the URL where the user should log in")?,
+            },
+        };
+        let success = if success.is_empty() {
+            profile
+                .login_check
+                .as_ref()
+                .map(LoginSuccess::from_check)
+                .unwrap_or_default()
+        } else {
+            success.clone()
+        };
+
+        let tabs = {
+            let state = self.state.read().await;
+            self.snapshot_tabs(&state)
+        };
+
+        tracing::info!(profile = %name, url = %url, "Handing browser to a human for login");
+        if let Err(e) = self.relaunch(false, None).await {
+            // The next call relaunches headless with the tabs.
+            self.headless.store(self.config.headless, Ordering::SeqCst);
+            *self.parked_tabs.lock().unwrap() = tabs;
+            return Err(e);
+        }
+        let handoff = async {
+            let (_, page) = self.create_new_page(&url).await?;
+            let outcome = tokio::select! {
+                found = success.wait(&page), if !success.is_empty() => {
+                    Some(found)
+                }
+                _ = self.wait_window_closed(&page) => None,
+                _ = tokio::time::sleep(timeout) => {
+                    return Ok::<_, anyhow::Error>((HandoffOutcome::TimedOut, url.clone()));
+                }
+            };
+            match outcome {
+                Some(found) => Ok((HandoffOutcome::LoggedIn, found)),
+                None => Ok((HandoffOutcome::WindowClosed, url.clone())),
+            }
+        }
+        .await;
+
+        // Resume headless whatever happened; a clean shutdown also flushes
+        // the new cookies to disk.
+        let mut tabs = tabs.unwrap_or(TabSnapshot {
+            urls: Vec::new(),
+            active_idx: 0,
+        });
+        if let Ok((_, ref resume_url)) = handoff {
+            tabs.urls.push(resume_url.clone());
+            tabs.active_idx = tabs.urls.len() - 1;
+        }
+        self.relaunch(true, (!tabs.urls.is_empty()).then_some(tabs))
+            .await?;
+        let (outcome, resume_url) = handoff?;
+
+        let mut login_check_saved = false;
+        if outcome == HandoffOutcome::LoggedIn {
+            self.profile_manager.record_verification(
+                &name,
+                &VerificationResult {
+                    logged_in: true,
+                    final_url: resume_url.clone(),
+                    detail: "Login detected during human login handoff".to_string(),
+                },
+            )?;
+            if profile.login_check.is_none() {
+                if let Some(check) = success.to_check(&resume_url) {
+                    self.profile_manager.set_login_check(&name, Some(check))?;
+                    login_check_saved = true;
+                }
+            }
+        }
+
+        Ok(HandoffResult {
+            outcome,
+            url: resume_url,
+            login_check_saved,
+        })
+    }
+
+    /// Shut down and relaunch with the given headless mode, closing pages
+    /// and reopening `tabs` instead.
+    async fn relaunch(&self, headless: bool, tabs: Option<TabSnapshot>) -> Result<()> {
+        self.shutdown().await;
+        {
+            let mut state = self.state.write().await;
+            state.pages.clear();
+            state.active_idx = 0;
+        }
+        self.dialogs.clear();
+        self.tabs.clear();
+        *self.parked_tabs.lock().unwrap() = tabs;
+        self.headless
+            .store(headless && self.config.headless, Ordering::SeqCst);
+        self.ensure_browser().await
+    }
+
+    /// URL of the active page, unless nothing useful is open.
+    async fn active_url(&self) -> Option<String> {
+        let page = {
+            let state = self.state.read().await;
+            state.pages.get(state.active_idx).cloned()?
+        };
+        page.url()
+            .await
+            .ok()
+            .flatten()
+            .filter(|url| url != "about:blank")
+    }
+
+    /// Resolves once the user closes the login tab or the whole browser.
+    async fn wait_window_closed(&self, page: &Page) {
+        loop {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            if self.is_browser_dead().await || page.url().await.is_err() {
+                return;
+            }
+        }
+    }
+
     /// Gracefully shut down the browser.
     ///
     /// Sends a CDP close, waits for the process to exit, then force-kills as
@@ -340,26 +1556,54 @@ impl BrowserManager {
             let _ = browser.wait().await;
             let _ = browser.kill().await;
         }
+        self.launch_info.lock().unwrap().take();
+        self.release_profile();
+        self.ephemeral_dir
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
     }
 
     /// Launch a non-headless browser for manual login (used by setup-login).
+    ///
+    /// Uses the profile's network settings (proxy, headers, auth) overlaid
+    /// with `network`, e.g. credentials the profile does not store, and its
+    /// launch options (extra arguments, extensions) with `user_data_dir`
+    /// from `ProfileManager::open_profile_data`. Returns the browser and the
+    /// login page so the caller can watch for the login to finish.
     pub async fn launch_for_login(
         profile_manager: Arc<ProfileManager>,
         profile_name: &str,
+        user_data_dir: &std::path::Path,
         url: &str,
+        network: &NetworkSettings,
         browser_path: Option<String>,
-    ) -> Result<Browser> {
-        let user_data_dir = profile_manager.user_data_dir(profile_name)?;
+    ) -> Result<(Browser, Page)> {
+        let profile = profile_manager.get_profile(profile_name)?;
+        let network = profile.network.overlay(network);
+        network.auth_credentials()?;
+        let launch = profile.launch.validated()?;
 
         let mut builder = BrowserConfig::builder()
             .with_head()
             .window_size(1280, 900)
             .user_data_dir(user_data_dir)
             .arg("--disable-dev-shm-usage")
-            .arg("--remote-allow-origins=*");
+            .arg("--remote-allow-origins=*")
+            .args(network.chrome_args())
+            .args(launch.args.iter())
+            .extensions(launch.extension_paths());
+
+        if !network.ignores_https_errors() {
+            builder = builder.respect_https_errors();
+        }
 
         if let Some(ref path) = browser_path {
             builder = builder.chrome_executable(path);
+        } else {
+            let detected = check::find_browser_for_channel(&profile.browser_channel)
+                .with_context(|| format!("Cannot launch profile '{}'", profile_name))?;
+            builder = builder.chrome_executable(&detected.path);
         }
 
         let config = builder.build().map_err(|e| anyhow::anyhow!("{}", e))?;
@@ -378,15 +1622,14 @@ impl BrowserManager {
 
         // Navigate to the login URL
         let page = browser
-            .new_page(url)
+            .new_page("about:blank")
             .await
             .context("Failed to open login page")?;
+        network.apply(&page).await?;
+        page.goto(url).await.context("Failed to open login page")?;
 
         tracing::info!("Browser opened at {}", url);
 
-        // Keep page alive (it's attached to the browser)
-        drop(page);
-
-        Ok(browser)
+        Ok((browser, page))
     }
 }
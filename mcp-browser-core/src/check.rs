@@ -168,6 +168,81 @@ pub fn detect_browsers() -> Vec<DetectedBrowser> {
     found
 }
 
+/// Browser channels a profile can be bound to.
+pub const CHANNELS: &[&str] = &["chrome", "msedge", "chromium"];
+
+/// Normalize a channel name, accepting "edge" as an alias for "msedge".
+pub fn normalize_channel(channel: &str) -> anyhow::Result<&'static str> {
+    let lower = channel.trim().to_lowercase();
+    let lower = if lower == "edge" { "msedge" } else { &lower };
+    CHANNELS
+        .iter()
+        .find(|c| **c == lower)
+        .copied()
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown browser channel '{}'. Expected one of: {}",
+                channel,
+                CHANNELS.join(", ")
+            )
+        })
+}
+
+/// The channel a profile gets when none is chosen.
+pub const DEFAULT_CHANNEL: &str = "chrome";
+
+/// Find the installed browser for a channel.
+///
+/// The default channel falls back to chromiumoxide's own detection, which
+/// also finds Chromium and browsers outside the probed names, so profiles
+/// that never chose a channel launch wherever they did before.
+pub fn find_browser_for_channel(channel: &str) -> anyhow::Result<DetectedBrowser> {
+    let error = match select_channel(&detect_browsers(), channel) {
+        Ok(browser) => return Ok(browser),
+        Err(e) => e,
+    };
+    if normalize_channel(channel)? != DEFAULT_CHANNEL {
+        return Err(error);
+    }
+    let path =
+        chromiumoxide::detection::default_executable(Default::default()).map_err(|_| error)?;
+    let path = path.to_string_lossy().into_owned();
+    tracing::warn!(
+        "Google Chrome not found; using auto-detected browser at {}",
+        path
+    );
+    Ok(DetectedBrowser {
+        name: "Auto-detected browser".to_string(),
+        channel: DEFAULT_CHANNEL.to_string(),
+        version: get_browser_version(&path),
+        path,
+        would_auto_select: true,
+    })
+}
+
+fn select_channel(browsers: &[DetectedBrowser], channel: &str) -> anyhow::Result<DetectedBrowser> {
+    let channel = normalize_channel(channel)?;
+    if let Some(browser) = browsers.iter().find(|b| b.channel == channel) {
+        return Ok(browser.clone());
+    }
+
+    let installed = if browsers.is_empty() {
+        "none".to_string()
+    } else {
+        browsers
+            .iter()
+            .map(|b| format!("{} ({})", b.channel, b.path))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    anyhow::bail!(
+        "Browser channel '{}' is not installed (detected: {}). Install it, or pass \
+         --browser-path to use a specific executable.",
+        channel,
+        installed
+    )
+}
+
 fn get_browser_version(path: &str) -> Option<String> {
     let output = std::process::Command::new(path)
         .arg("--version")
@@ -506,3 +581,41 @@ pub fn format_text(report: &CheckReport, verbose: bool) -> String {
 fn current_platform() -> String {
     format!("{}/{}", std::env::consts::OS, std::env::consts::ARCH)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(channel: &str, path: &str) -> DetectedBrowser {
+        DetectedBrowser {
+            name: channel.to_string(),
+            channel: channel.to_string(),
+            path: path.to_string(),
+            version: None,
+            would_auto_select: false,
+        }
+    }
+
+    #[test]
+    fn test_normalize_channel() {
+        assert_eq!(normalize_channel("Chrome").unwrap(), "chrome");
+        assert_eq!(normalize_channel("edge").unwrap(), "msedge");
+        assert!(normalize_channel("firefox").is_err());
+    }
+
+    #[test]
+    fn test_select_channel() {
+        let browsers = vec![
+            detected("chrome", "google-chrome"),
+            detected("msedge", "microsoft-edge-stable"),
+        ];
+        let edge = select_channel(&browsers, "msedge").unwrap();
+        assert_eq!(edge.path, "microsoft-edge-stable");
+
+        let err = select_channel(&browsers, "chromium")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("'chromium' is not installed"), "{}", err);
+        assert!(err.contains("chrome (google-chrome)"), "{}", err);
+    }
+}
@@ -16,22 +16,31 @@
 //! | Method | Path | Body | Description |
 //! |--------|------|------|-------------|
 //! | POST | `/navigate` | `{ url, timeout_ms? }` | Navigate to URL |
-//! | POST | `/click` | `{ selector }` | Click element |
-//! | POST | `/fill` | `{ selector, value }` | Fill form field |
-//! | POST | `/screenshot` | `{ selector?, full_page? }` | Screenshot (base64 PNG) |
-//! | POST | `/get_text` | `{ selector }` | Get element text |
-//! | POST | `/extract_table` | `{ selector }` | Extract HTML table as JSON |
-//! | POST | `/wait` | `{ selector?, timeout_ms? }` | Wait for selector/duration |
-//! | POST | `/press_key` | `{ key, selector? }` | Press keyboard key |
-//! | POST | `/hover` | `{ selector }` | Hover over element |
+//! | POST | `/click` | `{ selector, timeout_ms? }` | Click element once actionable |
+//! | POST | `/fill` | `{ selector, value, timeout_ms? }` | Fill form field once editable |
+//! | POST | `/screenshot` | `{ selector?, full_page?, timeout_ms? }` | Screenshot (base64 PNG) |
+//! | POST | `/get_text` | `{ selector, all?, timeout_ms? }` | Get element text (or all matches) |
+//! | POST | `/inspect_element` | `{ selector, styles?, max_html_length?, timeout_ms? }` | Inspect element state and geometry |
+//! | POST | `/query_all` | `{ selector, limit?, styles?, max_html_length? }` | Inspect every match |
+//! | POST | `/extract_table` | `{ selector, timeout_ms? }` | Extract HTML table as JSON |
+//! | POST | `/wait` | `{ selector?, state?, text?, url?, function?, network_idle?, timeout_ms? }` | Wait for a condition or duration |
+//! | POST | `/press_key` | `{ key, selector?, timeout_ms? }` | Press keyboard key |
+//! | POST | `/hover` | `{ selector, timeout_ms? }` | Hover over element once actionable |
+//! | POST | `/list_links` | `{ pattern?, same_origin_only?, dedup?, limit? }` | List links on the page |
+//! | POST | `/list_forms` | `{ selector? }` | Describe forms and fields |
 //! | POST | `/evaluate` | `{ expression }` | Evaluate JavaScript |
-//! | POST | `/new_page` | `{ url }` | Open new tab |
+//! | POST | `/handle_dialog` | `{ accept, prompt_text? }` | Accept or dismiss dialog |
+//! | POST | `/emulate` | `{ device?, width?, height?, locale?, timezone?, geolocation?, color_scheme?, reset?, ... }` | Device/locale emulation |
+//! | POST | `/emulate_network` | `{ preset?, offline?, latency_ms?, download_kbps?, upload_kbps?, cache_disabled? }` | Network throttling/offline |
+//! | POST | `/new_page` | `{ url }` | Open new tab (up to the page limit) |
 //! | POST | `/select_page` | `{ index }` | Switch tab |
-//! | GET | `/dom` | — | Get page DOM |
+//! | GET | `/dom` | — | Get page DOM (truncated past the content limit) |
 //! | GET | `/url` | — | Get page URL |
 //! | GET | `/pages` | — | List open pages |
+//! | GET | `/dialog` | — | Pending/last auto-handled dialog |
 
 use crate::browser::BrowserManager;
+use crate::limits;
 use crate::tools;
 use pmcp_code_mode::{ExecutionConfig, ExecutionError, HttpExecutor, PlanCompiler, PlanExecutor};
 use serde_json::Value as JsonValue;
@@ -119,6 +128,30 @@ impl BrowserHttpExecutor {
                     })
             }
 
+            "/inspect_element" => {
+                let input: tools::inspect_element::InspectElementInput =
+                    serde_json::from_value(body).map_err(|e| ExecutionError::RuntimeError {
+                        message: format!("/inspect_element: invalid input: {}", e),
+                    })?;
+                tools::inspect_element::execute(&self.manager, input)
+                    .await
+                    .map_err(|e| ExecutionError::RuntimeError {
+                        message: format!("/inspect_element failed: {}", e),
+                    })
+            }
+
+            "/query_all" => {
+                let input: tools::query_all::QueryAllInput =
+                    serde_json::from_value(body).map_err(|e| ExecutionError::RuntimeError {
+                        message: format!("/query_all: invalid input: {}", e),
+                    })?;
+                tools::query_all::execute(&self.manager, input)
+                    .await
+                    .map_err(|e| ExecutionError::RuntimeError {
+                        message: format!("/query_all failed: {}", e),
+                    })
+            }
+
             "/extract_table" => {
                 let input: tools::extract_table::ExtractTableInput =
                     serde_json::from_value(body).map_err(|e| ExecutionError::RuntimeError {
@@ -167,6 +200,30 @@ impl BrowserHttpExecutor {
                     })
             }
 
+            "/list_links" => {
+                let input: tools::list_links::ListLinksInput = serde_json::from_value(body)
+                    .map_err(|e| ExecutionError::RuntimeError {
+                        message: format!("/list_links: invalid input: {}", e),
+                    })?;
+                tools::list_links::execute(&self.manager, input)
+                    .await
+                    .map_err(|e| ExecutionError::RuntimeError {
+                        message: format!("/list_links failed: {}", e),
+                    })
+            }
+
+            "/list_forms" => {
+                let input: tools::list_forms::ListFormsInput = serde_json::from_value(body)
+                    .map_err(|e| ExecutionError::RuntimeError {
+                        message: format!("/list_forms: invalid input: {}", e),
+                    })?;
+                tools::list_forms::execute(&self.manager, input)
+                    .await
+                    .map_err(|e| ExecutionError::RuntimeError {
+                        message: format!("/list_forms failed: {}", e),
+                    })
+            }
+
             "/evaluate" => {
                 let input: tools::evaluate_script::EvaluateScriptInput =
                     serde_json::from_value(body).map_err(|e| ExecutionError::RuntimeError {
@@ -191,6 +248,30 @@ impl BrowserHttpExecutor {
                     })
             }
 
+            "/emulate" => {
+                let input: tools::emulate::EmulateInput =
+                    serde_json::from_value(body).map_err(|e| ExecutionError::RuntimeError {
+                        message: format!("/emulate: invalid input: {}", e),
+                    })?;
+                tools::emulate::execute(&self.manager, input)
+                    .await
+                    .map_err(|e| ExecutionError::RuntimeError {
+                        message: format!("/emulate failed: {}", e),
+                    })
+            }
+
+            "/emulate_network" => {
+                let input: tools::emulate_network::EmulateNetworkInput =
+                    serde_json::from_value(body).map_err(|e| ExecutionError::RuntimeError {
+                        message: format!("/emulate_network: invalid input: {}", e),
+                    })?;
+                tools::emulate_network::execute(&self.manager, input)
+                    .await
+                    .map_err(|e| ExecutionError::RuntimeError {
+                        message: format!("/emulate_network failed: {}", e),
+                    })
+            }
+
             "/new_page" => {
                 let url = body
                     .get("url")
@@ -233,13 +314,21 @@ impl BrowserHttpExecutor {
                     .map_err(|e| ExecutionError::RuntimeError {
                         message: format!("/dom: browser error: {}", e),
                     })?;
-                let html = page
+                let mut html = page
                     .content()
                     .await
                     .map_err(|e| ExecutionError::RuntimeError {
                         message: format!("/dom: failed to get content: {}", e),
                     })?;
-                Ok(serde_json::json!({ "dom": html }))
+                let max = self.manager.limits().max_content_bytes;
+                match limits::truncate(&mut html, max) {
+                    Some(total_bytes) => Ok(serde_json::json!({
+                        "dom": html,
+                        "truncated": true,
+                        "total_bytes": total_bytes,
+                    })),
+                    None => Ok(serde_json::json!({ "dom": html })),
+                }
             }
 
             "/url" => {
@@ -270,6 +359,15 @@ impl BrowserHttpExecutor {
                     })
             }
 
+            "/dialog" => {
+                let input = tools::get_pending_dialog::GetPendingDialogInput {};
+                tools::get_pending_dialog::execute(&self.manager, input)
+                    .await
+                    .map_err(|e| ExecutionError::RuntimeError {
+                        message: format!("/dialog failed: {}", e),
+                    })
+            }
+
             _ => Err(ExecutionError::RuntimeError {
                 message: format!("Unknown browser endpoint: GET {}", path),
             }),
@@ -0,0 +1,251 @@
+//! JavaScript dialog tracking and automatic dialog policy.
+//!
+//! Each page gets a listener on `Page.javascriptDialogOpening` and
+//! `Page.javascriptDialogClosed`. Open dialogs are recorded per page so tools
+//! can report what a dialog says before handling it. With an `accept` or
+//! `dismiss` policy, dialogs are answered as soon as they open so `alert()`
+//! and `beforeunload` prompts never block automation.
+
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::page::{
+    EventJavascriptDialogClosed, EventJavascriptDialogOpening, HandleJavaScriptDialogParams,
+};
+use chromiumoxide::cdp::browser_protocol::target::TargetId;
+use chromiumoxide::Page;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// What to do automatically when a JavaScript dialog opens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DialogPolicy {
+    /// Accept the dialog (prompts receive their default text).
+    Accept,
+    /// Dismiss the dialog.
+    Dismiss,
+    /// Leave the dialog open for `handle_dialog`.
+    #[default]
+    Leave,
+}
+
+impl FromStr for DialogPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "accept" => Ok(DialogPolicy::Accept),
+            "dismiss" => Ok(DialogPolicy::Dismiss),
+            "leave" => Ok(DialogPolicy::Leave),
+            other => Err(format!(
+                "Unknown dialog policy '{}': expected accept, dismiss or leave",
+                other
+            )),
+        }
+    }
+}
+
+/// A JavaScript dialog observed on a page.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DialogInfo {
+    /// `alert`, `confirm`, `prompt` or `beforeunload`.
+    pub dialog_type: String,
+    pub message: String,
+    pub default_prompt: Option<String>,
+    /// URL of the frame that opened the dialog.
+    pub url: String,
+    pub opened_at: DateTime<Utc>,
+    /// Set when the dialog was answered by the auto-policy.
+    pub action: Option<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct PageDialogs {
+    pending: Option<DialogInfo>,
+    last_auto_handled: Option<DialogInfo>,
+}
+
+/// Per-page record of open and auto-handled dialogs.
+#[derive(Default)]
+pub struct DialogTracker {
+    pages: Mutex<HashMap<TargetId, PageDialogs>>,
+}
+
+impl DialogTracker {
+    /// Start listening for dialogs on `page`, applying `policy` as they open.
+    ///
+    /// Subscriptions are registered before this returns, so no dialog opened
+    /// afterwards is missed. The listener task ends when the page closes.
+    pub async fn watch(self: &Arc<Self>, page: &Page, policy: DialogPolicy) -> Result<()> {
+        let mut opening = page
+            .event_listener::<EventJavascriptDialogOpening>()
+            .await
+            .context("Failed to subscribe to dialog events")?;
+        let mut closed = page
+            .event_listener::<EventJavascriptDialogClosed>()
+            .await
+            .context("Failed to subscribe to dialog events")?;
+
+        let tracker = self.clone();
+        let page = page.clone();
+        let target = page.target_id().clone();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = opening.next() => {
+                        let Some(event) = event else { break };
+                        let info = DialogInfo {
+                            dialog_type: event.r#type.as_ref().to_string(),
+                            message: event.message.clone(),
+                            default_prompt: event.default_prompt.clone(),
+                            url: event.url.clone(),
+                            opened_at: Utc::now(),
+                            action: None,
+                        };
+                        tracker.on_opened(&page, &target, info, policy).await;
+                    }
+                    event = closed.next() => {
+                        if event.is_none() {
+                            break;
+                        }
+                        tracker.update(&target, |dialogs| dialogs.pending = None);
+                    }
+                }
+            }
+            tracker.forget(&target);
+        });
+
+        Ok(())
+    }
+
+    async fn on_opened(
+        &self,
+        page: &Page,
+        target: &TargetId,
+        mut info: DialogInfo,
+        policy: DialogPolicy,
+    ) {
+        let accept = match policy {
+            DialogPolicy::Leave => {
+                tracing::info!(
+                    "JavaScript {} dialog opened: {:?}",
+                    info.dialog_type,
+                    info.message
+                );
+                self.update(target, |dialogs| dialogs.pending = Some(info));
+                return;
+            }
+            DialogPolicy::Accept => true,
+            DialogPolicy::Dismiss => false,
+        };
+
+        let mut params = HandleJavaScriptDialogParams::new(accept);
+        if accept {
+            params.prompt_text = info.default_prompt.clone();
+        }
+
+        match page.execute(params).await {
+            Ok(_) => {
+                info.action = Some(if accept { "accepted" } else { "dismissed" }.to_string());
+                tracing::info!(
+                    "Auto-{} JavaScript {} dialog: {:?}",
+                    if accept { "accepted" } else { "dismissed" },
+                    info.dialog_type,
+                    info.message
+                );
+                self.update(target, |dialogs| {
+                    dialogs.pending = None;
+                    dialogs.last_auto_handled = Some(info);
+                });
+            }
+            Err(e) => {
+                tracing::warn!("Failed to auto-handle dialog: {}", e);
+                self.update(target, |dialogs| dialogs.pending = Some(info));
+            }
+        }
+    }
+
+    /// The dialog currently open on a page, if any.
+    pub fn pending(&self, target: &TargetId) -> Option<DialogInfo> {
+        let pages = self.pages.lock().unwrap();
+        pages.get(target).and_then(|d| d.pending.clone())
+    }
+
+    /// The most recent dialog answered by the auto-policy on a page.
+    pub fn last_auto_handled(&self, target: &TargetId) -> Option<DialogInfo> {
+        let pages = self.pages.lock().unwrap();
+        pages.get(target).and_then(|d| d.last_auto_handled.clone())
+    }
+
+    /// Remove and return the open dialog on a page.
+    pub fn take_pending(&self, target: &TargetId) -> Option<DialogInfo> {
+        let mut pages = self.pages.lock().unwrap();
+        pages.get_mut(target).and_then(|d| d.pending.take())
+    }
+
+    /// Drop all records for a page.
+    pub fn forget(&self, target: &TargetId) {
+        self.pages.lock().unwrap().remove(target);
+    }
+
+    /// Drop all records (e.g. after a browser relaunch).
+    pub fn clear(&self) {
+        self.pages.lock().unwrap().clear();
+    }
+
+    fn update(&self, target: &TargetId, f: impl FnOnce(&mut PageDialogs)) {
+        let mut pages = self.pages.lock().unwrap();
+        f(pages.entry(target.clone()).or_default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(message: &str) -> DialogInfo {
+        DialogInfo {
+            dialog_type: "alert".to_string(),
+            message: message.to_string(),
+            default_prompt: None,
+            url: "about:blank".to_string(),
+            opened_at: Utc::now(),
+            action: None,
+        }
+    }
+
+    #[test]
+    fn test_dialog_policy_from_str() {
+        assert_eq!("accept".parse::<DialogPolicy>(), Ok(DialogPolicy::Accept));
+        assert_eq!("Dismiss".parse::<DialogPolicy>(), Ok(DialogPolicy::Dismiss));
+        assert_eq!("leave".parse::<DialogPolicy>(), Ok(DialogPolicy::Leave));
+        assert!("ignore".parse::<DialogPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_tracker_pending_roundtrip() {
+        let tracker = DialogTracker::default();
+        let target = TargetId::from("page-1".to_string());
+
+        assert!(tracker.pending(&target).is_none());
+        tracker.update(&target, |d| d.pending = Some(info("hello")));
+        assert_eq!(tracker.pending(&target).unwrap().message, "hello");
+
+        let taken = tracker.take_pending(&target).unwrap();
+        assert_eq!(taken.message, "hello");
+        assert!(tracker.pending(&target).is_none());
+    }
+
+    #[test]
+    fn test_tracker_forget() {
+        let tracker = DialogTracker::default();
+        let target = TargetId::from("page-2".to_string());
+        tracker.update(&target, |d| d.last_auto_handled = Some(info("bye")));
+        assert!(tracker.last_auto_handled(&target).is_some());
+        tracker.forget(&target);
+        assert!(tracker.last_auto_handled(&target).is_none());
+    }
+}
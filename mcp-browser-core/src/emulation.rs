@@ -0,0 +1,545 @@
+//! Device, viewport, locale and media emulation.
+//!
+//! Settings are kept on the `BrowserManager` and applied through the CDP
+//! `Emulation` domain to every page: existing pages when settings change, and
+//! new pages as they are created. Named device presets fill in viewport,
+//! scale factor, mobile/touch flags and user agent; explicit fields override
+//! the preset.
+
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::browser::{GrantPermissionsParams, PermissionType};
+use chromiumoxide::cdp::browser_protocol::emulation::{
+    ClearDeviceMetricsOverrideParams, ClearGeolocationOverrideParams, MediaFeature,
+    SetDeviceMetricsOverrideParams, SetEmulatedMediaParams, SetGeolocationOverrideParams,
+    SetLocaleOverrideParams, SetTimezoneOverrideParams, SetTouchEmulationEnabledParams,
+    SetUserAgentOverrideParams,
+};
+use chromiumoxide::Page;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// A named device profile.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct DevicePreset {
+    pub name: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub device_scale_factor: f64,
+    pub mobile: bool,
+    pub touch: bool,
+    pub user_agent: &'static str,
+}
+
+const IPHONE_UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+const IPAD_UA: &str = "Mozilla/5.0 (iPad; CPU OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+const PIXEL_UA: &str = "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Mobile Safari/537.36";
+
+/// Built-in device presets. Lookup is case-insensitive and treats spaces,
+/// dashes and underscores alike (`"iPhone 15"` == `"iphone-15"`).
+pub const DEVICE_PRESETS: &[DevicePreset] = &[
+    DevicePreset {
+        name: "iphone-se",
+        width: 375,
+        height: 667,
+        device_scale_factor: 2.0,
+        mobile: true,
+        touch: true,
+        user_agent: IPHONE_UA,
+    },
+    DevicePreset {
+        name: "iphone-15",
+        width: 393,
+        height: 852,
+        device_scale_factor: 3.0,
+        mobile: true,
+        touch: true,
+        user_agent: IPHONE_UA,
+    },
+    DevicePreset {
+        name: "iphone-15-pro-max",
+        width: 430,
+        height: 932,
+        device_scale_factor: 3.0,
+        mobile: true,
+        touch: true,
+        user_agent: IPHONE_UA,
+    },
+    DevicePreset {
+        name: "pixel-7",
+        width: 412,
+        height: 915,
+        device_scale_factor: 2.625,
+        mobile: true,
+        touch: true,
+        user_agent: PIXEL_UA,
+    },
+    DevicePreset {
+        name: "pixel-8",
+        width: 412,
+        height: 915,
+        device_scale_factor: 2.625,
+        mobile: true,
+        touch: true,
+        user_agent: PIXEL_UA,
+    },
+    DevicePreset {
+        name: "ipad",
+        width: 820,
+        height: 1180,
+        device_scale_factor: 2.0,
+        mobile: true,
+        touch: true,
+        user_agent: IPAD_UA,
+    },
+    DevicePreset {
+        name: "ipad-pro",
+        width: 1024,
+        height: 1366,
+        device_scale_factor: 2.0,
+        mobile: true,
+        touch: true,
+        user_agent: IPAD_UA,
+    },
+];
+
+/// Look up a device preset by name.
+pub fn find_device(name: &str) -> Option<&'static DevicePreset> {
+    let normalized = name.trim().to_lowercase().replace([' ', '_'], "-");
+    DEVICE_PRESETS.iter().find(|d| d.name == normalized)
+}
+
+/// `prefers-color-scheme` value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorScheme {
+    Light,
+    Dark,
+    NoPreference,
+}
+
+impl ColorScheme {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ColorScheme::Light => "light",
+            ColorScheme::Dark => "dark",
+            ColorScheme::NoPreference => "no-preference",
+        }
+    }
+}
+
+impl FromStr for ColorScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "light" => Ok(ColorScheme::Light),
+            "dark" => Ok(ColorScheme::Dark),
+            "no-preference" => Ok(ColorScheme::NoPreference),
+            other => Err(format!(
+                "Unknown color scheme '{}': expected light, dark or no-preference",
+                other
+            )),
+        }
+    }
+}
+
+/// Emulated geolocation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Geolocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Accuracy in meters (default: 10).
+    #[serde(default)]
+    pub accuracy: Option<f64>,
+}
+
+impl FromStr for Geolocation {
+    type Err = String;
+
+    /// Parse `"lat,lon"` or `"lat,lon,accuracy"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+        if parts.len() < 2 || parts.len() > 3 {
+            return Err(format!(
+                "Invalid geolocation '{}': expected 'latitude,longitude[,accuracy]'",
+                s
+            ));
+        }
+        let num = |v: &str| {
+            v.parse::<f64>()
+                .map_err(|_| format!("Invalid number '{}' in geolocation '{}'", v, s))
+        };
+        let latitude = num(parts[0])?;
+        let longitude = num(parts[1])?;
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return Err(format!("Geolocation '{}' is out of range", s));
+        }
+        let accuracy = parts.get(2).map(|v| num(v)).transpose()?;
+        Ok(Self {
+            latitude,
+            longitude,
+            accuracy,
+        })
+    }
+}
+
+/// Parse a viewport like `"1280x720"`.
+pub fn parse_viewport(s: &str) -> Result<(u32, u32), String> {
+    let (w, h) = s
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("Invalid viewport '{}': expected WIDTHxHEIGHT", s))?;
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|n| (1..=10000).contains(n))
+            .ok_or_else(|| format!("Invalid viewport '{}': expected WIDTHxHEIGHT", s))
+    };
+    Ok((parse(w)?, parse(h)?))
+}
+
+/// Emulation settings. Every field is optional; unset fields leave the
+/// browser default (or the device preset's value) in place.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct EmulationSettings {
+    /// Named device preset, e.g. "iphone-15", "pixel-7", "ipad"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Device preset: iphone-se, iphone-15, iphone-15-pro-max, pixel-7, pixel-8, ipad, ipad-pro"
+    )]
+    pub device: Option<String>,
+
+    /// Viewport width in CSS pixels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Viewport width in CSS pixels")]
+    pub width: Option<u32>,
+
+    /// Viewport height in CSS pixels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Viewport height in CSS pixels")]
+    pub height: Option<u32>,
+
+    /// Device scale factor (devicePixelRatio)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Device scale factor / devicePixelRatio, e.g. 2 for retina")]
+    pub device_scale_factor: Option<f64>,
+
+    /// Emulate a mobile device (meta viewport, overlay scrollbars)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Emulate a mobile device")]
+    pub mobile: Option<bool>,
+
+    /// Enable touch events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Enable touch event emulation")]
+    pub touch: Option<bool>,
+
+    /// User agent override
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "User agent string override")]
+    pub user_agent: Option<String>,
+
+    /// Locale, e.g. "de-DE"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Locale (ICU format), e.g. 'de-DE'. Also sets Accept-Language.")]
+    pub locale: Option<String>,
+
+    /// Timezone, e.g. "Europe/Berlin"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "IANA timezone ID, e.g. 'Europe/Berlin'")]
+    pub timezone: Option<String>,
+
+    /// Geolocation override
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Geolocation override; also grants the geolocation permission")]
+    pub geolocation: Option<Geolocation>,
+
+    /// prefers-color-scheme
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "prefers-color-scheme: 'light', 'dark' or 'no-preference'")]
+    pub color_scheme: Option<ColorScheme>,
+}
+
+/// Settings after resolving the device preset.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ResolvedEmulation {
+    pub device: Option<String>,
+    pub viewport: Option<(u32, u32)>,
+    pub device_scale_factor: f64,
+    pub mobile: bool,
+    pub touch: bool,
+    pub user_agent: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub geolocation: Option<Geolocation>,
+    pub color_scheme: Option<ColorScheme>,
+}
+
+impl EmulationSettings {
+    /// True if no field is set.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Overlay the fields set in `other` on top of `self`.
+    pub fn merge(&mut self, other: EmulationSettings) {
+        // Picking a new device replaces the previous device's derived values.
+        if other.device.is_some() {
+            self.width = None;
+            self.height = None;
+            self.device_scale_factor = None;
+            self.mobile = None;
+            self.touch = None;
+            self.user_agent = None;
+            self.device = other.device;
+        }
+        macro_rules! take {
+            ($($field:ident),*) => {
+                $(if other.$field.is_some() { self.$field = other.$field; })*
+            };
+        }
+        take!(
+            width,
+            height,
+            device_scale_factor,
+            mobile,
+            touch,
+            user_agent,
+            locale,
+            timezone,
+            geolocation,
+            color_scheme
+        );
+    }
+
+    /// Resolve the device preset and explicit overrides.
+    pub fn resolve(&self) -> Result<ResolvedEmulation> {
+        let preset = match self.device {
+            Some(ref name) => Some(find_device(name).ok_or_else(|| {
+                let names: Vec<&str> = DEVICE_PRESETS.iter().map(|d| d.name).collect();
+                anyhow::anyhow!("Unknown device '{}'. Available: {}", name, names.join(", "))
+            })?),
+            None => None,
+        };
+
+        let width = self.width.or(preset.map(|p| p.width));
+        let height = self.height.or(preset.map(|p| p.height));
+        let viewport = match (width, height) {
+            (Some(w), Some(h)) => Some((w, h)),
+            (None, None) => None,
+            _ => anyhow::bail!("Viewport emulation needs both width and height"),
+        };
+
+        Ok(ResolvedEmulation {
+            device: preset.map(|p| p.name.to_string()),
+            viewport,
+            device_scale_factor: self
+                .device_scale_factor
+                .or(preset.map(|p| p.device_scale_factor))
+                .unwrap_or(1.0),
+            mobile: self.mobile.or(preset.map(|p| p.mobile)).unwrap_or(false),
+            touch: self.touch.or(preset.map(|p| p.touch)).unwrap_or(false),
+            user_agent: self
+                .user_agent
+                .clone()
+                .or(preset.map(|p| p.user_agent.to_string())),
+            locale: self.locale.clone(),
+            timezone: self.timezone.clone(),
+            geolocation: self.geolocation,
+            color_scheme: self.color_scheme,
+        })
+    }
+}
+
+impl ResolvedEmulation {
+    /// Apply these settings to a page.
+    pub async fn apply(&self, page: &Page) -> Result<()> {
+        if let Some((width, height)) = self.viewport {
+            page.execute(SetDeviceMetricsOverrideParams::new(
+                width as i64,
+                height as i64,
+                self.device_scale_factor,
+                self.mobile,
+            ))
+            .await
+            .context("Failed to set device metrics")?;
+        }
+
+        let mut touch = SetTouchEmulationEnabledParams::new(self.touch);
+        if self.touch {
+            touch.max_touch_points = Some(5);
+        }
+        page.execute(touch)
+            .await
+            .context("Failed to set touch emulation")?;
+
+        if self.user_agent.is_some() || self.locale.is_some() {
+            let user_agent = match self.user_agent {
+                Some(ref ua) => ua.clone(),
+                None => page
+                    .user_agent()
+                    .await
+                    .context("Failed to read current user agent")?,
+            };
+            let mut params = SetUserAgentOverrideParams::new(user_agent);
+            params.accept_language = self.locale.clone();
+            page.execute(params)
+                .await
+                .context("Failed to set user agent")?;
+        }
+
+        if let Some(ref locale) = self.locale {
+            let params = SetLocaleOverrideParams {
+                locale: Some(locale.clone()),
+            };
+            page.execute(params)
+                .await
+                .with_context(|| format!("Failed to set locale '{}'", locale))?;
+        }
+
+        if let Some(ref timezone) = self.timezone {
+            page.execute(SetTimezoneOverrideParams::new(timezone.clone()))
+                .await
+                .with_context(|| format!("Failed to set timezone '{}'", timezone))?;
+        }
+
+        if let Some(geo) = self.geolocation {
+            if let Err(e) = page
+                .execute(GrantPermissionsParams::new(vec![
+                    PermissionType::Geolocation,
+                ]))
+                .await
+            {
+                tracing::warn!("Failed to grant geolocation permission: {}", e);
+            }
+            let params = SetGeolocationOverrideParams {
+                latitude: Some(geo.latitude),
+                longitude: Some(geo.longitude),
+                accuracy: Some(geo.accuracy.unwrap_or(10.0)),
+                ..Default::default()
+            };
+            page.execute(params)
+                .await
+                .context("Failed to set geolocation")?;
+        }
+
+        if let Some(scheme) = self.color_scheme {
+            let params = SetEmulatedMediaParams {
+                features: Some(vec![MediaFeature::new(
+                    "prefers-color-scheme",
+                    scheme.as_str(),
+                )]),
+                ..Default::default()
+            };
+            page.execute(params)
+                .await
+                .context("Failed to set color scheme")?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove overrides this module may have set on a page. `user_agent` is
+    /// the browser's own user agent, which replaces any emulated one along
+    /// with its Accept-Language.
+    pub async fn clear(page: &Page, user_agent: &str) -> Result<()> {
+        page.execute(ClearDeviceMetricsOverrideParams::default())
+            .await
+            .context("Failed to clear device metrics")?;
+        page.execute(SetTouchEmulationEnabledParams::new(false))
+            .await
+            .context("Failed to clear touch emulation")?;
+        page.execute(SetUserAgentOverrideParams::new(user_agent))
+            .await
+            .context("Failed to restore user agent")?;
+        page.execute(SetLocaleOverrideParams::default())
+            .await
+            .context("Failed to clear locale")?;
+        page.execute(SetTimezoneOverrideParams::new(String::new()))
+            .await
+            .context("Failed to clear timezone")?;
+        page.execute(ClearGeolocationOverrideParams::default())
+            .await
+            .context("Failed to clear geolocation")?;
+        page.execute(SetEmulatedMediaParams::default())
+            .await
+            .context("Failed to clear emulated media")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_device_normalizes_name() {
+        assert_eq!(find_device("iPhone 15").unwrap().name, "iphone-15");
+        assert_eq!(find_device("PIXEL_7").unwrap().name, "pixel-7");
+        assert!(find_device("nokia-3310").is_none());
+    }
+
+    #[test]
+    fn test_parse_viewport() {
+        assert_eq!(parse_viewport("1280x720"), Ok((1280, 720)));
+        assert_eq!(parse_viewport("390X844"), Ok((390, 844)));
+        assert!(parse_viewport("1280").is_err());
+        assert!(parse_viewport("0x720").is_err());
+    }
+
+    #[test]
+    fn test_parse_geolocation() {
+        let geo: Geolocation = "52.52,13.405".parse().unwrap();
+        assert_eq!(geo.latitude, 52.52);
+        assert_eq!(geo.accuracy, None);
+
+        let geo: Geolocation = "52.52, 13.405, 50".parse().unwrap();
+        assert_eq!(geo.accuracy, Some(50.0));
+
+        assert!("91,0".parse::<Geolocation>().is_err());
+        assert!("abc".parse::<Geolocation>().is_err());
+    }
+
+    #[test]
+    fn test_resolve_device_with_override() {
+        let settings = EmulationSettings {
+            device: Some("iphone-15".to_string()),
+            width: Some(400),
+            ..Default::default()
+        };
+        let resolved = settings.resolve().unwrap();
+        assert_eq!(resolved.viewport, Some((400, 852)));
+        assert!(resolved.mobile);
+        assert!(resolved.touch);
+        assert!(resolved.user_agent.unwrap().contains("iPhone"));
+    }
+
+    #[test]
+    fn test_resolve_rejects_partial_viewport() {
+        let settings = EmulationSettings {
+            width: Some(400),
+            ..Default::default()
+        };
+        assert!(settings.resolve().is_err());
+    }
+
+    #[test]
+    fn test_merge_device_resets_derived_fields() {
+        let mut settings = EmulationSettings {
+            device: Some("iphone-15".to_string()),
+            width: Some(400),
+            locale: Some("de-DE".to_string()),
+            ..Default::default()
+        };
+        settings.merge(EmulationSettings {
+            device: Some("ipad".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(settings.device.as_deref(), Some("ipad"));
+        assert_eq!(settings.width, None);
+        assert_eq!(settings.locale.as_deref(), Some("de-DE"));
+    }
+}
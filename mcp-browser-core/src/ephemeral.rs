@@ -0,0 +1,183 @@
+//! Throwaway user-data-dirs for `--ephemeral` launches.
+//!
+//! Each launch gets a fresh private directory in the temp dir named
+//! `mcp-browser-ephemeral-<pid>-<random>`, removed when the browser shuts
+//! down or is relaunched after a crash. Directories left by a server that
+//! was killed are swept on the next launch, but only when the PID in the
+//! name no longer exists, so another server's browser is never disturbed.
+//!
+//! Without `--ephemeral` or a profile, every launch shares chromiumoxide's
+//! default dir; its `SingletonLock` is removed before launch when the
+//! browser that left it has exited.
+
+use crate::profile_lock::{current_host, process_exists};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+const PREFIX: &str = "mcp-browser-ephemeral-";
+
+/// A fresh user-data-dir, removed on drop.
+#[derive(Debug)]
+pub struct EphemeralDir(tempfile::TempDir);
+
+impl EphemeralDir {
+    /// Create a new directory in the system temp dir, sweeping stale ones
+    /// first.
+    pub fn create() -> Result<Self> {
+        Self::create_in(&std::env::temp_dir())
+    }
+
+    fn create_in(base: &Path) -> Result<Self> {
+        sweep_stale(base);
+        let dir = tempfile::Builder::new()
+            .prefix(&format!("{}{}-", PREFIX, std::process::id()))
+            .tempdir_in(base)
+            .with_context(|| format!("Failed to create a user-data-dir in {}", base.display()))?;
+        tracing::debug!(dir = %dir.path().display(), "Created ephemeral user-data-dir");
+        Ok(Self(dir))
+    }
+
+    pub fn path(&self) -> &Path {
+        self.0.path()
+    }
+}
+
+/// Remove ephemeral dirs whose owning process has exited.
+fn sweep_stale(base: &Path) {
+    let Ok(entries) = std::fs::read_dir(base) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(pid) = owner_pid(&name.to_string_lossy()) else {
+            continue;
+        };
+        if pid == std::process::id() || process_exists(pid) {
+            continue;
+        }
+        let path: PathBuf = entry.path();
+        tracing::info!(dir = %path.display(), "Removing ephemeral user-data-dir of exited process {}", pid);
+        if let Err(e) = std::fs::remove_dir_all(&path) {
+            tracing::warn!(dir = %path.display(), "Failed to remove stale user-data-dir: {}", e);
+        }
+    }
+}
+
+/// The user-data-dir chromiumoxide launches with when given none.
+pub fn shared_dir() -> PathBuf {
+    std::env::temp_dir().join("chromiumoxide-runner")
+}
+
+/// Remove `dir`'s `SingletonLock` if the browser holding it has exited, so
+/// a crashed or killed browser does not block the next launch.
+pub fn remove_stale_singleton_lock(dir: &Path) {
+    let lock = dir.join("SingletonLock");
+    if std::fs::symlink_metadata(&lock).is_err() {
+        return;
+    }
+    // On Linux and macOS the lock is a symlink to "<host>-<pid>". Elsewhere
+    // it is a plain file that cannot be removed while the browser runs.
+    if let Ok(target) = std::fs::read_link(&lock) {
+        let target = target.to_string_lossy();
+        let Some((host, pid)) = singleton_owner(&target) else {
+            return;
+        };
+        if host != current_host() || process_exists(pid) {
+            return;
+        }
+    }
+    match std::fs::remove_file(&lock) {
+        Ok(()) => tracing::info!(lock = %lock.display(), "Removed stale SingletonLock"),
+        Err(e) => tracing::debug!(lock = %lock.display(), "SingletonLock kept: {}", e),
+    }
+}
+
+/// The host and PID a `SingletonLock` symlink points at.
+fn singleton_owner(target: &str) -> Option<(&str, u32)> {
+    let (host, pid) = target.rsplit_once('-')?;
+    Some((host, pid.parse().ok()?))
+}
+
+/// The PID embedded in an ephemeral dir name.
+fn owner_pid(name: &str) -> Option<u32> {
+    let rest = name.strip_prefix(PREFIX)?;
+    let (pid, _) = rest.split_once('-')?;
+    pid.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dirs_are_unique_and_removed_on_drop() {
+        let base = tempfile::tempdir().unwrap();
+        let a = EphemeralDir::create_in(base.path()).unwrap();
+        let b = EphemeralDir::create_in(base.path()).unwrap();
+        assert_ne!(a.path(), b.path());
+        assert_eq!(
+            owner_pid(&a.path().file_name().unwrap().to_string_lossy()),
+            Some(std::process::id())
+        );
+
+        let path = a.path().to_path_buf();
+        drop(a);
+        assert!(!path.exists());
+        assert!(b.path().exists());
+    }
+
+    #[test]
+    fn test_sweep_removes_only_dead_owners() {
+        let base = tempfile::tempdir().unwrap();
+        // Far above any real PID limit, so never a live process.
+        let dead = base.path().join(format!("{}{}-abc", PREFIX, i32::MAX));
+        let live = base
+            .path()
+            .join(format!("{}{}-abc", PREFIX, std::process::id()));
+        let unrelated = base.path().join("chromiumoxide-runner");
+        for dir in [&dead, &live, &unrelated] {
+            std::fs::create_dir_all(dir.join("Default")).unwrap();
+        }
+        std::fs::write(unrelated.join("SingletonLock"), "").unwrap();
+
+        let fresh = EphemeralDir::create_in(base.path()).unwrap();
+        assert!(!dead.exists());
+        assert!(live.exists());
+        assert!(unrelated.join("SingletonLock").exists());
+        assert!(fresh.path().exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_singleton_lock_removed_only_when_owner_exited() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock = dir.path().join("SingletonLock");
+        let host = current_host();
+
+        std::os::unix::fs::symlink(format!("{}-{}", host, std::process::id()), &lock).unwrap();
+        remove_stale_singleton_lock(dir.path());
+        assert!(std::fs::symlink_metadata(&lock).is_ok());
+
+        std::fs::remove_file(&lock).unwrap();
+        std::os::unix::fs::symlink(format!("{}-{}", host, i32::MAX), &lock).unwrap();
+        remove_stale_singleton_lock(dir.path());
+        assert!(std::fs::symlink_metadata(&lock).is_err());
+
+        std::os::unix::fs::symlink(format!("other-host-{}", i32::MAX), &lock).unwrap();
+        remove_stale_singleton_lock(dir.path());
+        assert!(std::fs::symlink_metadata(&lock).is_ok());
+    }
+
+    #[test]
+    fn test_singleton_owner() {
+        assert_eq!(singleton_owner("my-host-4321"), Some(("my-host", 4321)));
+        assert_eq!(singleton_owner("garbage"), None);
+    }
+
+    #[test]
+    fn test_owner_pid() {
+        assert_eq!(owner_pid("mcp-browser-ephemeral-1234-x1y2"), Some(1234));
+        assert_eq!(owner_pid("mcp-browser-ephemeral-abc-x1y2"), None);
+        assert_eq!(owner_pid("chromiumoxide-runner"), None);
+    }
+}
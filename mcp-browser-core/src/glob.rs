@@ -0,0 +1,75 @@
+//! `*` wildcard patterns, as used for URLs (`wait`, `list_links`, login
+//! checks) and profile names (`prune`).
+
+/// Match `text` against a pattern where `*` matches any run of characters.
+/// The pattern must cover the whole text.
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+
+    let first = parts[0];
+    let last = parts[parts.len() - 1];
+    if !text.starts_with(first) {
+        return false;
+    }
+
+    let mut rest = &text[first.len()..];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Match a URL against a pattern: with `*` it is a glob covering the whole
+/// URL, without `*` a substring match.
+pub fn url_matches(pattern: &str, url: &str) -> bool {
+    if pattern.contains('*') {
+        glob_matches(pattern, url)
+    } else {
+        url.contains(pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("test-*", "test-123-4"));
+        assert!(glob_matches("test-*-0", "test-99-0"));
+        assert!(!glob_matches("test-*-0", "test-99-1"));
+        assert!(glob_matches("*", "anything"));
+        assert!(glob_matches("work", "work"));
+        assert!(!glob_matches("work", "workday"));
+        assert!(!glob_matches("a*a", "a"));
+    }
+
+    #[test]
+    fn test_url_matches_substring() {
+        assert!(url_matches(
+            "/dashboard",
+            "https://app.test/dashboard?tab=1"
+        ));
+        assert!(!url_matches("/login", "https://app.test/dashboard"));
+    }
+
+    #[test]
+    fn test_url_matches_glob() {
+        assert!(url_matches("https://*.test/*", "https://app.test/home"));
+        assert!(url_matches(
+            "*/orders/*/done",
+            "https://x.test/orders/42/done"
+        ));
+        assert!(!url_matches(
+            "*/orders/*/done",
+            "https://x.test/orders/42/done/extra"
+        ));
+        assert!(!url_matches("https://*.test/*", "http://app.test/home"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Extra Chrome launch arguments and unpacked extensions.
+//!
+//! Launch options come from three places, combined in this order: the
+//! profile's stored options, a TOML launch config file, and repeated
+//! command-line flags. Arguments are appended and extensions are unioned.
+//!
+//! ```toml
+//! args = ["--lang=de-DE", "--disable-features=Translate"]
+//! extensions = ["/opt/extensions/corp-sso"]
+//! ```
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Flags the `BrowserManager` sets itself; overriding them would break the
+/// CDP connection or profile handling.
+const RESERVED_ARGS: &[&str] = &[
+    "--remote-debugging-port",
+    "--remote-debugging-pipe",
+    "--user-data-dir",
+    "--headless",
+];
+
+/// Extra Chrome arguments and extensions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LaunchOptions {
+    /// Extra Chrome command-line arguments, e.g. `--lang=de-DE`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Unpacked extension directories (each containing a `manifest.json`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<PathBuf>,
+}
+
+impl LaunchOptions {
+    /// Load options from a TOML launch config file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read launch config {}", path.display()))?;
+        let mut options: LaunchOptions = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse launch config {}", path.display()))?;
+
+        // Relative extension paths are relative to the config file.
+        if let Some(dir) = path.parent() {
+            for ext in &mut options.extensions {
+                if ext.is_relative() {
+                    *ext = dir.join(&*ext);
+                }
+            }
+        }
+        Ok(options)
+    }
+
+    /// True if nothing is configured.
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty() && self.extensions.is_empty()
+    }
+
+    /// Append `other`'s arguments and extensions, skipping duplicates.
+    pub fn overlay(&self, other: &LaunchOptions) -> LaunchOptions {
+        let mut merged = self.clone();
+        for arg in &other.args {
+            if !merged.args.contains(arg) {
+                merged.args.push(arg.clone());
+            }
+        }
+        for ext in &other.extensions {
+            if !merged.extensions.contains(ext) {
+                merged.extensions.push(ext.clone());
+            }
+        }
+        merged
+    }
+
+    /// Check arguments and extension directories, returning the options
+    /// with extension paths made absolute.
+    pub fn validated(&self) -> Result<LaunchOptions> {
+        for arg in &self.args {
+            if !arg.starts_with("--") {
+                anyhow::bail!("Chrome argument '{}' must start with '--'", arg);
+            }
+            let name = arg.split('=').next().unwrap_or(arg);
+            if RESERVED_ARGS.contains(&name) {
+                anyhow::bail!(
+                    "Chrome argument '{}' is managed by the browser server and cannot be set",
+                    name
+                );
+            }
+        }
+
+        let extensions = self
+            .extensions
+            .iter()
+            .map(|dir| {
+                if !dir.join("manifest.json").is_file() {
+                    anyhow::bail!(
+                        "Extension directory {} does not contain a manifest.json",
+                        dir.display()
+                    );
+                }
+                dir.canonicalize()
+                    .with_context(|| format!("Failed to resolve {}", dir.display()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(LaunchOptions {
+            args: self.args.clone(),
+            extensions,
+        })
+    }
+
+    /// Extension paths as strings for `BrowserConfig::extensions`.
+    pub fn extension_paths(&self) -> Vec<String> {
+        self.extensions
+            .iter()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_file_resolves_relative_extensions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("launch.toml");
+        std::fs::write(
+            &path,
+            "args = [\"--lang=de-DE\"]\nextensions = [\"ext/sso\", \"/opt/ext\"]\n",
+        )
+        .unwrap();
+
+        let options = LaunchOptions::from_file(&path).unwrap();
+        assert_eq!(options.args, vec!["--lang=de-DE"]);
+        assert_eq!(options.extensions[0], tmp.path().join("ext/sso"));
+        assert_eq!(options.extensions[1], PathBuf::from("/opt/ext"));
+    }
+
+    #[test]
+    fn test_from_file_rejects_unknown_keys() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("launch.toml");
+        std::fs::write(&path, "argz = []\n").unwrap();
+        assert!(LaunchOptions::from_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_overlay_skips_duplicates() {
+        let profile = LaunchOptions {
+            args: vec!["--lang=de-DE".to_string()],
+            extensions: vec![PathBuf::from("/a")],
+        };
+        let cli = LaunchOptions {
+            args: vec!["--lang=de-DE".to_string(), "--mute-audio".to_string()],
+            extensions: vec![PathBuf::from("/a"), PathBuf::from("/b")],
+        };
+        let merged = profile.overlay(&cli);
+        assert_eq!(merged.args, vec!["--lang=de-DE", "--mute-audio"]);
+        assert_eq!(merged.extensions.len(), 2);
+    }
+
+    #[test]
+    fn test_validated_checks_args_and_extensions() {
+        let reserved = LaunchOptions {
+            args: vec!["--user-data-dir=/tmp/x".to_string()],
+            ..Default::default()
+        };
+        assert!(reserved.validated().is_err());
+
+        let bare = LaunchOptions {
+            args: vec!["lang=de".to_string()],
+            ..Default::default()
+        };
+        assert!(bare.validated().is_err());
+
+        let tmp = tempfile::tempdir().unwrap();
+        let missing = LaunchOptions {
+            extensions: vec![tmp.path().to_path_buf()],
+            ..Default::default()
+        };
+        assert!(missing.validated().is_err());
+
+        std::fs::write(tmp.path().join("manifest.json"), "{}").unwrap();
+        let ok = missing.validated().unwrap();
+        assert!(ok.extensions[0].is_absolute());
+    }
+}
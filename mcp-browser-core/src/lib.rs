@@ -3,11 +3,28 @@
 //! Provides `build_server()` which constructs a fully-configured MCP `Server`
 //! with browser automation tools, ready to be served over HTTP.
 
+pub mod actionability;
 pub mod browser;
 pub mod check;
 pub mod code_mode;
+pub mod dialog;
+pub mod emulation;
+pub mod ephemeral;
+pub mod glob;
+pub mod launch;
+pub mod lifecycle;
+pub mod limits;
+pub mod login_check;
+pub mod network;
+pub mod pool;
 pub mod profile;
+pub mod profile_archive;
+pub mod profile_lock;
+pub mod profile_vault;
+pub mod recovery;
+pub mod remote;
 pub mod resources;
+pub mod status;
 pub mod tools;
 
 use browser::{BrowserManager, BrowserManagerConfig};
@@ -19,12 +36,15 @@ use std::sync::Arc;
 /// Build a fully-configured MCP server with browser automation capabilities.
 ///
 /// Returns both the server and the `BrowserManager` handle so the caller can
-/// trigger a graceful browser shutdown (e.g. on Ctrl+C).
+/// trigger a graceful browser shutdown (e.g. on Ctrl+C). With an idle
+/// timeout or max lifetime configured, this must be called within a Tokio
+/// runtime, which runs the lifecycle monitor.
 pub fn build_server(config: BrowserManagerConfig) -> pmcp::Result<(Server, Arc<BrowserManager>)> {
     let profile_manager =
         Arc::new(ProfileManager::new().map_err(|e| pmcp::Error::internal(e.to_string()))?);
 
     let manager = Arc::new(BrowserManager::new(config, profile_manager));
+    manager.spawn_lifecycle_monitor();
 
     let builder = Server::builder()
         .name("browser")
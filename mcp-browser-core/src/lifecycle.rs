@@ -0,0 +1,171 @@
+//! Idle shutdown and max-lifetime recycling of a launched browser.
+//!
+//! Tool calls are tracked as activity. When no call has run for the idle
+//! timeout, or the browser has been up longer than its max lifetime, the
+//! browser is shut down with its tabs' URLs parked; the next call relaunches
+//! it and reopens them. A browser is never shut down while a call is in
+//! flight.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Why an idle-monitored browser was shut down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParkReason {
+    /// No tool call for the idle timeout.
+    Idle,
+    /// Up for longer than the max lifetime.
+    MaxLifetime,
+}
+
+/// Idle shutdown and recycle counters.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LifecycleStats {
+    /// Times the browser was shut down after the idle timeout.
+    pub idle_shutdowns: u64,
+    /// Times the browser was shut down after its max lifetime.
+    pub recycles: u64,
+    pub last_shutdown_at: Option<DateTime<Utc>>,
+}
+
+impl LifecycleStats {
+    pub(crate) fn record(&mut self, reason: ParkReason) {
+        match reason {
+            ParkReason::Idle => self.idle_shutdowns += 1,
+            ParkReason::MaxLifetime => self.recycles += 1,
+        }
+        self.last_shutdown_at = Some(Utc::now());
+    }
+}
+
+/// Tool calls in flight and when the last one ended.
+pub struct ActivityTracker {
+    calls: RwLock<()>,
+    last: Mutex<Instant>,
+}
+
+impl Default for ActivityTracker {
+    fn default() -> Self {
+        Self {
+            calls: RwLock::new(()),
+            last: Mutex::new(Instant::now()),
+        }
+    }
+}
+
+impl ActivityTracker {
+    /// Mark a call as running until the guard is dropped.
+    pub async fn begin(&self) -> ActivityGuard<'_> {
+        let call = self.calls.read().await;
+        self.touch();
+        ActivityGuard {
+            _call: call,
+            tracker: self,
+        }
+    }
+
+    /// Time since the last call ended (or began, if one is running).
+    pub fn idle_for(&self) -> Duration {
+        self.last.lock().unwrap().elapsed()
+    }
+
+    /// Hold off new calls, unless one is running.
+    pub(crate) fn try_quiesce(&self) -> Option<RwLockWriteGuard<'_, ()>> {
+        self.calls.try_write().ok()
+    }
+
+    fn touch(&self) {
+        *self.last.lock().unwrap() = Instant::now();
+    }
+}
+
+/// A tool call in progress; see `ActivityTracker::begin`.
+pub struct ActivityGuard<'a> {
+    _call: RwLockReadGuard<'a, ()>,
+    tracker: &'a ActivityTracker,
+}
+
+impl Drop for ActivityGuard<'_> {
+    fn drop(&mut self) {
+        self.tracker.touch();
+    }
+}
+
+/// Whether a browser idle for `idle` and up for `uptime` should be shut
+/// down. The max lifetime wins when both have passed.
+pub fn park_reason(
+    idle: Duration,
+    uptime: Duration,
+    idle_timeout: Option<Duration>,
+    max_lifetime: Option<Duration>,
+) -> Option<ParkReason> {
+    if max_lifetime.is_some_and(|max| uptime >= max) {
+        Some(ParkReason::MaxLifetime)
+    } else if idle_timeout.is_some_and(|timeout| idle >= timeout) {
+        Some(ParkReason::Idle)
+    } else {
+        None
+    }
+}
+
+/// How often to check the timeouts: a quarter of the shorter one, between
+/// 1 and 30 seconds.
+pub fn poll_interval(idle_timeout: Option<Duration>, max_lifetime: Option<Duration>) -> Duration {
+    let shortest = match (idle_timeout, max_lifetime) {
+        (Some(a), Some(b)) => a.min(b),
+        (Some(a), None) | (None, Some(a)) => a,
+        (None, None) => return Duration::from_secs(30),
+    };
+    (shortest / 4).clamp(Duration::from_secs(1), Duration::from_secs(30))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_park_reason() {
+        assert_eq!(park_reason(10 * MIN, 10 * MIN, None, None), None);
+        assert_eq!(
+            park_reason(5 * MIN, MIN, Some(5 * MIN), None),
+            Some(ParkReason::Idle)
+        );
+        assert_eq!(park_reason(4 * MIN, MIN, Some(5 * MIN), None), None);
+        assert_eq!(
+            park_reason(Duration::ZERO, 60 * MIN, None, Some(60 * MIN)),
+            Some(ParkReason::MaxLifetime)
+        );
+        assert_eq!(
+            park_reason(10 * MIN, 90 * MIN, Some(5 * MIN), Some(60 * MIN)),
+            Some(ParkReason::MaxLifetime)
+        );
+    }
+
+    #[test]
+    fn test_poll_interval() {
+        assert_eq!(poll_interval(Some(60 * MIN), None), Duration::from_secs(30));
+        assert_eq!(
+            poll_interval(Some(MIN), Some(60 * MIN)),
+            Duration::from_secs(15)
+        );
+        assert_eq!(
+            poll_interval(None, Some(Duration::from_secs(2))),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_running_call_blocks_quiesce() {
+        let tracker = ActivityTracker::default();
+        let call = tracker.begin().await;
+        assert!(tracker.try_quiesce().is_none());
+        drop(call);
+        assert!(tracker.idle_for() < MIN);
+        assert!(tracker.try_quiesce().is_some());
+    }
+}
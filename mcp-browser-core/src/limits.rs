@@ -0,0 +1,216 @@
+//! Limits that keep scripts from exhausting the host: open pages, the size
+//! of DOM and text returned, screenshot size, and how long any single CDP
+//! call may take.
+//!
+//! Oversized DOM, text and script results are cut rather than refused:
+//! text gets a marker, lists lose their trailing items. The
+//! other limits fail the call with a `LIMIT_EXCEEDED` error whose data names
+//! the limit, e.g. `{"limit": "max_pages", "max": 20, "actual": 21}`.
+
+use pmcp::ErrorCode;
+use serde::Serialize;
+use std::time::Duration;
+
+/// JSON-RPC error code of a call refused by a limit.
+pub const LIMIT_EXCEEDED: ErrorCode = ErrorCode::other(-32010);
+
+/// Limits enforced by `BrowserManager` and the tools.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceLimits {
+    /// Open pages (tabs) a script may create.
+    pub max_pages: usize,
+    /// DOM or text returned by one call, in bytes; more is truncated.
+    pub max_content_bytes: usize,
+    /// Largest screenshot as (width, height) in CSS pixels.
+    pub max_screenshot_size: (u32, u32),
+    /// Time any CDP command, including navigation, may take.
+    pub cdp_timeout: Duration,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            max_pages: 20,
+            max_content_bytes: 2 * 1024 * 1024,
+            max_screenshot_size: (4096, 8192),
+            cdp_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Which limit a call hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Limit {
+    MaxPages,
+    MaxScreenshotSize,
+    CdpTimeout,
+}
+
+/// A call refused because it would exceed a limit.
+#[derive(Debug, Clone, Serialize, thiserror::Error)]
+#[error("{message}")]
+pub struct LimitExceeded {
+    pub limit: Limit,
+    pub max: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<u64>,
+    #[serde(skip)]
+    message: String,
+}
+
+impl LimitExceeded {
+    pub fn max_pages(max: usize) -> Self {
+        Self {
+            limit: Limit::MaxPages,
+            max: max as u64,
+            actual: Some(max as u64 + 1),
+            message: format!(
+                "Page limit reached: {} pages are open; close one before opening another",
+                max
+            ),
+        }
+    }
+
+    pub fn screenshot_size(max: (u32, u32), actual: (u32, u32)) -> Self {
+        let too_wide = actual.0 > max.0;
+        Self {
+            limit: Limit::MaxScreenshotSize,
+            max: u64::from(if too_wide { max.0 } else { max.1 }),
+            actual: Some(u64::from(if too_wide { actual.0 } else { actual.1 })),
+            message: format!(
+                "Screenshot of {}x{} exceeds the {}x{} limit; screenshot an element or the viewport instead",
+                actual.0, actual.1, max.0, max.1
+            ),
+        }
+    }
+
+    pub fn cdp_timeout(timeout: Duration) -> Self {
+        Self {
+            limit: Limit::CdpTimeout,
+            max: timeout.as_millis() as u64,
+            actual: None,
+            message: format!(
+                "A browser operation did not complete within {}ms",
+                timeout.as_millis()
+            ),
+        }
+    }
+}
+
+impl From<LimitExceeded> for pmcp::Error {
+    fn from(e: LimitExceeded) -> Self {
+        pmcp::Error::Protocol {
+            code: LIMIT_EXCEEDED,
+            message: e.to_string(),
+            data: serde_json::to_value(&e).ok(),
+        }
+    }
+}
+
+/// Cut `text` to at most `max` bytes on a character boundary and append a
+/// marker saying how much was dropped. Returns the original size if cut.
+pub fn truncate(text: &mut String, max: usize) -> Option<usize> {
+    let total = text.len();
+    if total <= max {
+        return None;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    text.push_str(&format!("\n[truncated: {} of {} bytes shown]", end, total));
+    Some(total)
+}
+
+/// Fit `texts` into `max` bytes altogether: the text that crosses the limit
+/// is truncated and later ones are dropped. Returns whether anything was cut.
+pub fn truncate_all(texts: &mut Vec<String>, max: usize) -> bool {
+    let mut remaining = max;
+    let Some(cut) = texts.iter().position(|text| {
+        let fits = text.len() <= remaining;
+        remaining = remaining.saturating_sub(text.len());
+        !fits
+    }) else {
+        return false;
+    };
+    let budget = max - texts[..cut].iter().map(String::len).sum::<usize>();
+    truncate(&mut texts[cut], budget);
+    texts.truncate(cut + 1);
+    true
+}
+
+/// Keep the leading `values` whose JSON fits in `max` bytes altogether.
+/// Returns whether any were dropped.
+pub fn truncate_values(values: &mut Vec<serde_json::Value>, max: usize) -> bool {
+    let mut used = 0;
+    let Some(cut) = values.iter().position(|value| {
+        used += value.to_string().len();
+        used > max
+    }) else {
+        return false;
+    };
+    values.truncate(cut);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_on_char_boundary() {
+        let mut short = "hello".to_string();
+        assert_eq!(truncate(&mut short, 5), None);
+        assert_eq!(short, "hello");
+
+        // "é" is two bytes, so a cut at 2 falls inside it.
+        let mut text = "aébc".to_string();
+        assert_eq!(truncate(&mut text, 2), Some(5));
+        assert_eq!(text, "a\n[truncated: 1 of 5 bytes shown]");
+    }
+
+    #[test]
+    fn test_truncate_all() {
+        let mut texts = vec!["aaaa".to_string(), "bbbb".to_string(), "cccc".to_string()];
+        assert!(truncate_all(&mut texts, 6));
+        assert_eq!(texts.len(), 2);
+        assert_eq!(texts[0], "aaaa");
+        assert!(texts[1].starts_with("bb\n[truncated: 2 of 4"));
+
+        let mut fits = vec!["aaaa".to_string()];
+        assert!(!truncate_all(&mut fits, 4));
+    }
+
+    #[test]
+    fn test_truncate_values() {
+        let mut values = vec![
+            serde_json::json!("aaaa"),
+            serde_json::json!("bbbb"),
+            serde_json::json!("cccc"),
+        ];
+        // Each serializes to 6 bytes with its quotes.
+        assert!(truncate_values(&mut values, 13));
+        assert_eq!(
+            values,
+            vec![serde_json::json!("aaaa"), serde_json::json!("bbbb")]
+        );
+        assert!(!truncate_values(&mut values, 12));
+    }
+
+    #[test]
+    fn test_limit_error_is_structured() {
+        let error: pmcp::Error = LimitExceeded::screenshot_size((4096, 8192), (1280, 40000)).into();
+        match error {
+            pmcp::Error::Protocol { code, data, .. } => {
+                assert_eq!(code, LIMIT_EXCEEDED);
+                let data = data.unwrap();
+                assert_eq!(data["limit"], "max_screenshot_size");
+                assert_eq!(data["max"], 8192);
+                assert_eq!(data["actual"], 40000);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+}
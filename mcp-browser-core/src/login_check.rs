@@ -0,0 +1,317 @@
+//! Login checks: probe whether a profile's saved session is still logged in.
+//!
+//! A check opens a URL with the profile and tests one success condition: an
+//! element that only appears when logged in, the page landing on (or staying
+//! off) certain URLs, or a session cookie being present.
+//!
+//! `setup-login` uses the same conditions to notice when a manual login has
+//! finished, then stores them as the profile's check.
+
+use crate::glob::url_matches;
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::network::Cookie;
+use chromiumoxide::Page;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// How often conditions are re-evaluated while waiting.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long a page may keep redirecting before a URL check passes.
+const REDIRECT_SETTLE: Duration = Duration::from_secs(3);
+
+/// What a logged-in session looks like.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginCondition {
+    /// A CSS selector matches an element, e.g. the account avatar.
+    Selector(String),
+    /// The final URL matches this pattern (`*` wildcards, otherwise a
+    /// substring), e.g. `https://app.example.com/dashboard*`.
+    UrlMatches(String),
+    /// The final URL does not contain this text, e.g. `/login` or the SSO
+    /// provider's host.
+    UrlNotContaining(String),
+    /// A cookie with this name is set for the page.
+    Cookie(String),
+}
+
+/// A URL plus the condition that shows the session is logged in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoginCheck {
+    pub url: String,
+    pub condition: LoginCondition,
+    /// Seconds to wait for the condition after the page loads.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    10
+}
+
+/// Outcome of running a login check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationResult {
+    pub logged_in: bool,
+    /// URL the page ended up on.
+    pub final_url: String,
+    /// Human-readable reason for the result.
+    pub detail: String,
+}
+
+impl LoginCheck {
+    pub fn new(url: impl Into<String>, condition: LoginCondition) -> Self {
+        Self {
+            url: url.into(),
+            condition,
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+
+    /// Navigate `page` to the check URL and evaluate the condition.
+    pub async fn run(&self, page: &Page) -> Result<VerificationResult> {
+        page.goto(&self.url)
+            .await
+            .with_context(|| format!("Failed to open {}", self.url))?;
+
+        let timeout = Duration::from_secs(self.timeout_secs);
+        let deadline = tokio::time::Instant::now() + timeout;
+        let settle = tokio::time::Instant::now() + timeout.min(REDIRECT_SETTLE);
+
+        loop {
+            let url = current_url(page).await;
+            let now = tokio::time::Instant::now();
+
+            let decided = match &self.condition {
+                LoginCondition::Selector(selector) => {
+                    if page.find_element(selector.as_str()).await.is_ok() {
+                        Some((true, format!("Found element '{}'", selector)))
+                    } else if now >= deadline {
+                        Some((false, format!("Element '{}' not found", selector)))
+                    } else {
+                        None
+                    }
+                }
+                LoginCondition::UrlMatches(pattern) => {
+                    if url_matches(pattern, &url) {
+                        Some((true, format!("Landed on {}", url)))
+                    } else if now >= deadline {
+                        Some((false, format!("Ended on {}, not '{}'", url, pattern)))
+                    } else {
+                        None
+                    }
+                }
+                LoginCondition::UrlNotContaining(pattern) => {
+                    if url.contains(pattern.as_str()) {
+                        Some((false, format!("Redirected to login page {}", url)))
+                    } else if now >= settle {
+                        Some((true, format!("Stayed off '{}'", pattern)))
+                    } else {
+                        None
+                    }
+                }
+                LoginCondition::Cookie(name) => {
+                    let cookies = page.get_cookies().await.unwrap_or_default();
+                    if cookies.iter().any(|c| &c.name == name) {
+                        Some((true, format!("Cookie '{}' is set", name)))
+                    } else if now >= deadline {
+                        Some((false, format!("Cookie '{}' is not set", name)))
+                    } else {
+                        None
+                    }
+                }
+            };
+
+            if let Some((logged_in, detail)) = decided {
+                return Ok(VerificationResult {
+                    logged_in,
+                    final_url: url,
+                    detail,
+                });
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// What marks a finished manual login: a URL pattern, a selector, or both
+/// (then both must match).
+#[derive(Debug, Clone, Default)]
+pub struct LoginSuccess {
+    pub url: Option<String>,
+    pub selector: Option<String>,
+}
+
+impl LoginSuccess {
+    pub fn is_empty(&self) -> bool {
+        self.url.is_none() && self.selector.is_none()
+    }
+
+    /// Watch for a stored login check's condition. Only element and URL
+    /// conditions can be watched; others give an empty success.
+    pub fn from_check(check: &LoginCheck) -> Self {
+        match &check.condition {
+            LoginCondition::Selector(selector) => Self {
+                selector: Some(selector.clone()),
+                ..Default::default()
+            },
+            LoginCondition::UrlMatches(pattern) => Self {
+                url: Some(pattern.clone()),
+                ..Default::default()
+            },
+            LoginCondition::UrlNotContaining(_) | LoginCondition::Cookie(_) => Self::default(),
+        }
+    }
+
+    /// Poll `page` until the success condition holds, returning the URL the
+    /// page is on at that point. Never returns if the login never finishes,
+    /// so callers race it against a timeout.
+    pub async fn wait(&self, page: &Page) -> String {
+        loop {
+            let url = current_url(page).await;
+            let url_ok = self.url.as_deref().is_none_or(|p| url_matches(p, &url));
+            let selector_ok = match self.selector.as_deref() {
+                Some(selector) if url_ok => page.find_element(selector).await.is_ok(),
+                _ => true,
+            };
+            if url_ok && selector_ok {
+                return url;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// The login check to store once success was seen at `final_url`. A
+    /// selector is the stronger signal, so it wins when both are set.
+    pub fn to_check(&self, final_url: &str) -> Option<LoginCheck> {
+        let condition = match (&self.selector, &self.url) {
+            (Some(selector), _) => LoginCondition::Selector(selector.clone()),
+            (None, Some(pattern)) => LoginCondition::UrlMatches(pattern.clone()),
+            (None, None) => return None,
+        };
+        Some(LoginCheck::new(final_url, condition))
+    }
+}
+
+/// Cookie names grouped by domain, for reporting what a login captured
+/// without showing any values.
+pub fn cookie_domains(cookies: &[Cookie]) -> BTreeMap<String, Vec<String>> {
+    let mut domains: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for cookie in cookies {
+        let names = domains.entry(cookie.domain.clone()).or_default();
+        if !names.contains(&cookie.name) {
+            names.push(cookie.name.clone());
+        }
+    }
+    for names in domains.values_mut() {
+        names.sort();
+    }
+    domains
+}
+
+async fn current_url(page: &Page) -> String {
+    page.url().await.ok().flatten().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_login_check_json_shape() {
+        let check = LoginCheck::new(
+            "https://app.example.com/home",
+            LoginCondition::UrlNotContaining("/login".to_string()),
+        );
+        let json = serde_json::to_value(&check).unwrap();
+        assert_eq!(json["condition"]["url_not_containing"], "/login");
+        assert_eq!(json["timeout_secs"], 10);
+
+        let parsed: LoginCheck = serde_json::from_str(
+            r##"{"url": "https://a.example", "condition": {"selector": "#avatar"}}"##,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.condition,
+            LoginCondition::Selector("#avatar".to_string())
+        );
+        assert_eq!(parsed.timeout_secs, 10);
+    }
+
+    #[test]
+    fn test_login_success_from_check() {
+        let check = |condition| LoginCheck::new("https://app.example.com", condition);
+
+        let success =
+            LoginSuccess::from_check(&check(LoginCondition::Selector("#avatar".to_string())));
+        assert_eq!(success.selector.as_deref(), Some("#avatar"));
+        assert_eq!(success.url, None);
+
+        let success = LoginSuccess::from_check(&check(LoginCondition::UrlMatches(
+            "*/dashboard".to_string(),
+        )));
+        assert_eq!(success.url.as_deref(), Some("*/dashboard"));
+        assert_eq!(success.selector, None);
+
+        // Conditions that cannot be watched leave the user to close the window.
+        assert!(
+            LoginSuccess::from_check(&check(LoginCondition::Cookie("sid".to_string()))).is_empty()
+        );
+        assert!(
+            LoginSuccess::from_check(&check(LoginCondition::UrlNotContaining(
+                "/login".to_string()
+            )))
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_login_success_to_check() {
+        let success = LoginSuccess {
+            url: Some("/home".to_string()),
+            selector: Some("#avatar".to_string()),
+        };
+        let check = success.to_check("https://app.example.com/home").unwrap();
+        assert_eq!(check.url, "https://app.example.com/home");
+        assert_eq!(
+            check.condition,
+            LoginCondition::Selector("#avatar".to_string())
+        );
+
+        let url_only = LoginSuccess {
+            url: Some("/home".to_string()),
+            selector: None,
+        };
+        assert_eq!(
+            url_only
+                .to_check("https://app.example.com/home")
+                .unwrap()
+                .condition,
+            LoginCondition::UrlMatches("/home".to_string())
+        );
+        assert!(LoginSuccess::default().to_check("https://x").is_none());
+    }
+
+    #[test]
+    fn test_cookie_domains() {
+        let cookie = |domain: &str, name: &str| {
+            serde_json::from_value::<Cookie>(serde_json::json!({
+                "name": name, "value": "secret", "domain": domain, "path": "/",
+                "expires": -1.0, "size": 10, "httpOnly": true, "secure": true,
+                "session": true, "priority": "Medium", "sameParty": false,
+                "sourceScheme": "Secure", "sourcePort": 443
+            }))
+            .unwrap()
+        };
+        let domains = cookie_domains(&[
+            cookie(".example.com", "SID"),
+            cookie(".example.com", "HSID"),
+            cookie("app.example.com", "session"),
+            cookie(".example.com", "SID"),
+        ]);
+        assert_eq!(domains[".example.com"], vec!["HSID", "SID"]);
+        assert_eq!(domains["app.example.com"], vec!["session"]);
+    }
+}
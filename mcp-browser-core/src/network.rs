@@ -0,0 +1,597 @@
+//! Network configuration: condition emulation, proxy, headers and auth.
+//!
+//! Conditions (offline mode, throttling, cache control) are kept on the
+//! `BrowserManager` and applied through the CDP `Network` domain to every open
+//! page and to pages created later, the same way device emulation is.
+//!
+//! `NetworkSettings` covers what is fixed per launch: the upstream proxy and
+//! certificate handling become Chrome arguments, while extra headers and
+//! HTTP/proxy credentials are applied to each page.
+
+use anyhow::{Context, Result};
+// `Network.emulateNetworkConditions` is deprecated in favour of
+// `emulateNetworkConditionsByRule`, which only recent Chrome releases support.
+#[allow(deprecated)]
+use chromiumoxide::cdp::browser_protocol::network::EmulateNetworkConditionsParams;
+use chromiumoxide::cdp::browser_protocol::network::{
+    Headers, SetCacheDisabledParams, SetExtraHttpHeadersParams,
+};
+use chromiumoxide::Page;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Named throttling profiles, matching Chrome DevTools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum NetworkPreset {
+    /// No throttling.
+    Online,
+    /// No connectivity.
+    Offline,
+    /// 2000ms latency, 400 kbit/s down and up.
+    #[serde(rename = "3g")]
+    ThreeG,
+    /// 562.5ms latency, 1.44 Mbit/s down, 675 kbit/s up.
+    Slow4g,
+    /// 165ms latency, 8.1 Mbit/s down, 1.35 Mbit/s up.
+    Fast4g,
+}
+
+impl NetworkPreset {
+    fn throttle(self) -> Option<Throttle> {
+        let (latency_ms, download_kbps, upload_kbps) = match self {
+            NetworkPreset::Online => return None,
+            NetworkPreset::Offline => {
+                return Some(Throttle {
+                    offline: true,
+                    ..Throttle::UNLIMITED
+                })
+            }
+            NetworkPreset::ThreeG => (2000.0, 400.0, 400.0),
+            NetworkPreset::Slow4g => (562.5, 1440.0, 675.0),
+            NetworkPreset::Fast4g => (165.0, 8100.0, 1350.0),
+        };
+        Some(Throttle {
+            offline: false,
+            latency_ms,
+            download_kbps: Some(download_kbps),
+            upload_kbps: Some(upload_kbps),
+        })
+    }
+}
+
+/// Effective throttling applied to pages.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Throttle {
+    pub offline: bool,
+    /// Added latency per request, in milliseconds.
+    pub latency_ms: f64,
+    /// Download limit in kbit/s (`None` = unlimited).
+    pub download_kbps: Option<f64>,
+    /// Upload limit in kbit/s (`None` = unlimited).
+    pub upload_kbps: Option<f64>,
+}
+
+impl Throttle {
+    const UNLIMITED: Throttle = Throttle {
+        offline: false,
+        latency_ms: 0.0,
+        download_kbps: None,
+        upload_kbps: None,
+    };
+
+    #[allow(deprecated)]
+    fn to_params(self) -> EmulateNetworkConditionsParams {
+        // CDP wants bytes per second, -1 for no limit.
+        let bytes_per_sec = |kbps: Option<f64>| kbps.map_or(-1.0, |k| k * 1000.0 / 8.0);
+        EmulateNetworkConditionsParams::new(
+            self.offline,
+            self.latency_ms,
+            bytes_per_sec(self.download_kbps),
+            bytes_per_sec(self.upload_kbps),
+        )
+    }
+}
+
+/// A requested change to network conditions.
+///
+/// Throttling fields start from `preset` (or no throttling) and override it.
+/// If none of them is set, the current throttling is kept. `cache_disabled`
+/// is kept when unset.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct NetworkConditionsUpdate {
+    /// Named throttling profile
+    #[serde(default)]
+    #[schemars(
+        description = "Throttling profile: 'online' (no throttling), 'offline', '3g', 'slow-4g' or 'fast-4g'"
+    )]
+    pub preset: Option<NetworkPreset>,
+
+    /// Emulate no connectivity
+    #[serde(default)]
+    #[schemars(description = "Emulate no connectivity (overrides the preset)")]
+    pub offline: Option<bool>,
+
+    /// Added latency per request
+    #[serde(default)]
+    #[schemars(description = "Minimum latency per request in milliseconds")]
+    pub latency_ms: Option<f64>,
+
+    /// Download throughput limit
+    #[serde(default)]
+    #[schemars(description = "Download throughput limit in kbit/s")]
+    pub download_kbps: Option<f64>,
+
+    /// Upload throughput limit
+    #[serde(default)]
+    #[schemars(description = "Upload throughput limit in kbit/s")]
+    pub upload_kbps: Option<f64>,
+
+    /// Disable the HTTP cache
+    #[serde(default)]
+    #[schemars(description = "Disable the browser HTTP cache (kept as-is when omitted)")]
+    pub cache_disabled: Option<bool>,
+}
+
+impl NetworkConditionsUpdate {
+    fn sets_throttle(&self) -> bool {
+        self.preset.is_some()
+            || self.offline.is_some()
+            || self.latency_ms.is_some()
+            || self.download_kbps.is_some()
+            || self.upload_kbps.is_some()
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (name, value) in [
+            ("latency_ms", self.latency_ms),
+            ("download_kbps", self.download_kbps),
+            ("upload_kbps", self.upload_kbps),
+        ] {
+            if let Some(v) = value {
+                if !v.is_finite() || v < 0.0 {
+                    anyhow::bail!("{} must be a non-negative number, got {}", name, v);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Network conditions in effect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct NetworkConditions {
+    /// `None` means no throttling.
+    pub throttle: Option<Throttle>,
+    pub cache_disabled: bool,
+}
+
+impl NetworkConditions {
+    /// True if nothing is emulated.
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Apply an update, returning the resulting conditions.
+    pub fn updated(&self, update: &NetworkConditionsUpdate) -> Result<NetworkConditions> {
+        update.validate()?;
+
+        let throttle = if update.sets_throttle() {
+            let base = update.preset.and_then(NetworkPreset::throttle);
+            let customized = update.offline.is_some()
+                || update.latency_ms.is_some()
+                || update.download_kbps.is_some()
+                || update.upload_kbps.is_some();
+            if customized {
+                let mut t = base.unwrap_or(Throttle::UNLIMITED);
+                if let Some(offline) = update.offline {
+                    t.offline = offline;
+                }
+                if let Some(latency) = update.latency_ms {
+                    t.latency_ms = latency;
+                }
+                if update.download_kbps.is_some() {
+                    t.download_kbps = update.download_kbps;
+                }
+                if update.upload_kbps.is_some() {
+                    t.upload_kbps = update.upload_kbps;
+                }
+                Some(t).filter(|t| *t != Throttle::UNLIMITED)
+            } else {
+                base
+            }
+        } else {
+            self.throttle
+        };
+
+        Ok(NetworkConditions {
+            throttle,
+            cache_disabled: update.cache_disabled.unwrap_or(self.cache_disabled),
+        })
+    }
+
+    /// Apply these conditions to a page.
+    pub async fn apply(&self, page: &Page) -> Result<()> {
+        let throttle = self.throttle.unwrap_or(Throttle::UNLIMITED);
+        page.execute(throttle.to_params())
+            .await
+            .context("Failed to set network conditions")?;
+        page.execute(SetCacheDisabledParams::new(self.cache_disabled))
+            .await
+            .context("Failed to set cache state")?;
+        Ok(())
+    }
+}
+
+/// Upstream proxy configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProxySettings {
+    /// Proxy server, e.g. `http://proxy.corp:3128` or `socks5://127.0.0.1:1080`.
+    pub server: String,
+    /// Hosts that bypass the proxy (Chrome `--proxy-bypass-list` syntax).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bypass: Vec<String>,
+    /// Credentials answered when the proxy challenges.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Credentials>,
+}
+
+/// A username/password pair for proxy or HTTP Basic/Digest auth.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+impl FromStr for Credentials {
+    type Err = String;
+
+    /// Parse `"user:password"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (username, password) = s
+            .split_once(':')
+            .ok_or_else(|| "Invalid credentials: expected 'user:password'".to_string())?;
+        if username.is_empty() {
+            return Err("Invalid credentials: username is empty".to_string());
+        }
+        Ok(Self {
+            username: username.to_string(),
+            password: password.to_string(),
+        })
+    }
+}
+
+/// Parse an extra header given as `"Name: value"`.
+pub fn parse_header(s: &str) -> Result<(String, String), String> {
+    let (name, value) = s
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid header '{}': expected 'Name: value'", s))?;
+    let name = name.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(format!("Invalid header name in '{}'", s));
+    }
+    Ok((name.to_string(), value.trim().to_string()))
+}
+
+/// Proxy, header, auth and certificate settings.
+///
+/// Set per profile in `ProfileMetadata` and per server via serve options;
+/// `overlay` combines the two with serve options taking precedence.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetworkSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxySettings>,
+    /// Proxy credentials given without a proxy server, for the proxy the
+    /// settings are overlaid on (a profile's). Never stored.
+    #[serde(skip)]
+    pub proxy_auth: Option<Credentials>,
+    /// Headers added to every request.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra_headers: BTreeMap<String, String>,
+    /// Credentials answered to HTTP Basic/Digest challenges.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_credentials: Option<Credentials>,
+    /// Fail on TLS certificate errors instead of ignoring them, which is
+    /// the default.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict_https: bool,
+    /// Base64 SHA-256 hashes of certificate public keys (SPKI) whose errors
+    /// are ignored — the way to trust specific self-signed internal hosts.
+    /// Setting any turns on strict checking for all other certificates.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore_certificate_spki: Vec<String>,
+}
+
+impl NetworkSettings {
+    /// True if nothing is configured.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Combine `self` (e.g. profile settings) with `other` (e.g. serve
+    /// options). Values set in `other` win; headers and SPKI lists merge.
+    pub fn overlay(&self, other: &NetworkSettings) -> NetworkSettings {
+        let mut extra_headers = self.extra_headers.clone();
+        extra_headers.extend(other.extra_headers.clone());
+        let mut ignore_certificate_spki = self.ignore_certificate_spki.clone();
+        for spki in &other.ignore_certificate_spki {
+            if !ignore_certificate_spki.contains(spki) {
+                ignore_certificate_spki.push(spki.clone());
+            }
+        }
+        let mut proxy = other.proxy.clone().or_else(|| self.proxy.clone());
+        let mut proxy_auth = other.proxy_auth.clone().or_else(|| self.proxy_auth.clone());
+        if let Some(ref mut proxy) = proxy {
+            if proxy_auth.is_some() {
+                proxy.auth = proxy_auth.take();
+            }
+        }
+        NetworkSettings {
+            proxy,
+            proxy_auth,
+            extra_headers,
+            http_credentials: other
+                .http_credentials
+                .clone()
+                .or_else(|| self.http_credentials.clone()),
+            strict_https: self.strict_https || other.strict_https,
+            ignore_certificate_spki,
+        }
+    }
+
+    /// Whether certificate errors are ignored: unless strict checking or
+    /// an SPKI allowlist is configured.
+    pub fn ignores_https_errors(&self) -> bool {
+        !self.strict_https && self.ignore_certificate_spki.is_empty()
+    }
+
+    /// These settings without proxy or HTTP credentials, for storing in a
+    /// profile: `profiles.json` is plain text.
+    pub fn without_credentials(&self) -> NetworkSettings {
+        let mut settings = self.clone();
+        if let Some(ref mut proxy) = settings.proxy {
+            proxy.auth = None;
+        }
+        settings.proxy_auth = None;
+        settings.http_credentials = None;
+        settings
+    }
+
+    /// Chrome command-line arguments for these settings.
+    pub fn chrome_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(ref proxy) = self.proxy {
+            args.push(format!("--proxy-server={}", proxy.server));
+            if !proxy.bypass.is_empty() {
+                args.push(format!("--proxy-bypass-list={}", proxy.bypass.join(";")));
+            }
+        }
+        if !self.ignore_certificate_spki.is_empty() {
+            args.push(format!(
+                "--ignore-certificate-errors-spki-list={}",
+                self.ignore_certificate_spki.join(",")
+            ));
+        }
+        args
+    }
+
+    /// The credentials to answer auth challenges with.
+    ///
+    /// Chrome reports proxy and server challenges through the same Fetch
+    /// event and the CDP handler answers both with one credential pair, so
+    /// proxy auth and HTTP credentials must agree when both are set.
+    pub fn auth_credentials(&self) -> Result<Option<Credentials>> {
+        if self.proxy_auth.is_some() {
+            anyhow::bail!("Proxy credentials are set but no proxy is configured");
+        }
+        let proxy_auth = self.proxy.as_ref().and_then(|p| p.auth.clone());
+        match (proxy_auth, self.http_credentials.clone()) {
+            (Some(proxy), Some(http)) if proxy != http => anyhow::bail!(
+                "Proxy auth and HTTP credentials differ; only one set of credentials \
+                 can be answered per browser"
+            ),
+            (proxy, http) => Ok(http.or(proxy)),
+        }
+    }
+
+    /// Apply per-page settings (extra headers, auth) to a page.
+    pub async fn apply(&self, page: &Page) -> Result<()> {
+        if !self.extra_headers.is_empty() {
+            let headers = serde_json::to_value(&self.extra_headers)?;
+            page.execute(SetExtraHttpHeadersParams::new(Headers::new(headers)))
+                .await
+                .context("Failed to set extra HTTP headers")?;
+        }
+        if let Some(credentials) = self.auth_credentials()? {
+            page.authenticate(chromiumoxide::auth::Credentials {
+                username: credentials.username,
+                password: credentials.password,
+            })
+            .await
+            .context("Failed to enable HTTP authentication")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(value: serde_json::Value) -> NetworkConditionsUpdate {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_preset_and_override() {
+        let conditions = NetworkConditions::default()
+            .updated(&update(
+                serde_json::json!({ "preset": "3g", "latency_ms": 100 }),
+            ))
+            .unwrap();
+        let throttle = conditions.throttle.unwrap();
+        assert_eq!(throttle.latency_ms, 100.0);
+        assert_eq!(throttle.download_kbps, Some(400.0));
+        assert!(!throttle.offline);
+    }
+
+    #[test]
+    fn test_online_clears_throttle_and_keeps_cache() {
+        let current = NetworkConditions {
+            throttle: NetworkPreset::Offline.throttle(),
+            cache_disabled: true,
+        };
+        let conditions = current
+            .updated(&update(serde_json::json!({ "preset": "online" })))
+            .unwrap();
+        assert_eq!(conditions.throttle, None);
+        assert!(conditions.cache_disabled);
+    }
+
+    #[test]
+    fn test_cache_only_keeps_throttle() {
+        let current = NetworkConditions {
+            throttle: NetworkPreset::Slow4g.throttle(),
+            cache_disabled: false,
+        };
+        let conditions = current
+            .updated(&update(serde_json::json!({ "cache_disabled": true })))
+            .unwrap();
+        assert_eq!(conditions.throttle, current.throttle);
+        assert!(conditions.cache_disabled);
+    }
+
+    #[test]
+    fn test_rejects_negative_values() {
+        let result = NetworkConditions::default()
+            .updated(&update(serde_json::json!({ "download_kbps": -5 })));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_kbps_to_bytes() {
+        let params = Throttle {
+            offline: false,
+            latency_ms: 10.0,
+            download_kbps: Some(800.0),
+            upload_kbps: None,
+        }
+        .to_params();
+        assert_eq!(params.download_throughput, 100_000.0);
+        assert_eq!(params.upload_throughput, -1.0);
+    }
+
+    #[test]
+    fn test_parse_credentials_and_header() {
+        let creds: Credentials = "alice:s3:cret".parse().unwrap();
+        assert_eq!(creds.username, "alice");
+        assert_eq!(creds.password, "s3:cret");
+        assert!("nopassword".parse::<Credentials>().is_err());
+        assert!(!format!("{:?}", creds).contains("s3:cret"));
+
+        assert_eq!(
+            parse_header("X-Env:  staging ").unwrap(),
+            ("X-Env".to_string(), "staging".to_string())
+        );
+        assert!(parse_header("no colon").is_err());
+    }
+
+    #[test]
+    fn test_overlay_and_chrome_args() {
+        let profile = NetworkSettings {
+            proxy: Some(ProxySettings {
+                server: "http://proxy.corp:3128".to_string(),
+                bypass: vec!["localhost".to_string(), "*.internal".to_string()],
+                auth: None,
+            }),
+            extra_headers: BTreeMap::from([("X-Team".to_string(), "qa".to_string())]),
+            ..Default::default()
+        };
+        let serve = NetworkSettings {
+            extra_headers: BTreeMap::from([("X-Env".to_string(), "ci".to_string())]),
+            ignore_certificate_spki: vec!["abc=".to_string()],
+            ..Default::default()
+        };
+
+        let merged = profile.overlay(&serve);
+        assert_eq!(merged.extra_headers.len(), 2);
+        assert_eq!(
+            merged.chrome_args(),
+            vec![
+                "--proxy-server=http://proxy.corp:3128".to_string(),
+                "--proxy-bypass-list=localhost;*.internal".to_string(),
+                "--ignore-certificate-errors-spki-list=abc=".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_certificate_errors_ignored_by_default() {
+        assert!(NetworkSettings::default().ignores_https_errors());
+        let strict = NetworkSettings {
+            strict_https: true,
+            ..Default::default()
+        };
+        assert!(!strict.ignores_https_errors());
+        let allowlist = NetworkSettings {
+            ignore_certificate_spki: vec!["abc=".to_string()],
+            ..Default::default()
+        };
+        assert!(!allowlist.ignores_https_errors());
+    }
+
+    #[test]
+    fn test_proxy_auth_applies_to_profile_proxy() {
+        let creds = |s: &str| Some(s.parse::<Credentials>().unwrap());
+        let profile = NetworkSettings {
+            proxy: Some(ProxySettings {
+                server: "http://proxy.corp:3128".to_string(),
+                bypass: Vec::new(),
+                auth: None,
+            }),
+            ..Default::default()
+        };
+        let serve = NetworkSettings {
+            proxy_auth: creds("alice:pw"),
+            ..Default::default()
+        };
+
+        let merged = profile.overlay(&serve);
+        assert_eq!(merged.proxy.as_ref().unwrap().auth, creds("alice:pw"));
+        assert_eq!(merged.auth_credentials().unwrap(), creds("alice:pw"));
+        assert!(merged.without_credentials().proxy.unwrap().auth.is_none());
+
+        let no_proxy = NetworkSettings::default().overlay(&serve);
+        assert!(no_proxy.auth_credentials().is_err());
+    }
+
+    #[test]
+    fn test_auth_credentials_must_agree() {
+        let creds = |s: &str| Some(s.parse::<Credentials>().unwrap());
+        let mut settings = NetworkSettings {
+            proxy: Some(ProxySettings {
+                server: "http://proxy:8080".to_string(),
+                bypass: Vec::new(),
+                auth: creds("proxy:pw"),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(settings.auth_credentials().unwrap(), creds("proxy:pw"));
+
+        settings.http_credentials = creds("web:pw");
+        assert!(settings.auth_credentials().is_err());
+    }
+}
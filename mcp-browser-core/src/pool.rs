@@ -0,0 +1,257 @@
+//! Worker browsers for parallel `execute_code` runs.
+//!
+//! All other tools share one browser and its active page, so two scripts
+//! running at once would interleave on the same tab. With `code_workers`
+//! set, each run leases a worker of its own: a separate browser process
+//! with a fresh ephemeral user-data-dir, launched on first use and kept for
+//! later runs. Each lease starts from a clean session: the previous run's
+//! pages are closed and its cookies and storage left behind in a disposed
+//! browser context. Runs queue when every worker is busy.
+//!
+//! Workers do not share the active profile, since Chrome cannot open one
+//! user-data-dir twice, so pooled scripts start logged out.
+
+use crate::browser::{BrowserManager, BrowserManagerConfig};
+use crate::profile::ProfileManager;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// A fixed set of worker browsers leased one run at a time.
+pub struct WorkerPool {
+    workers: Vec<Arc<BrowserManager>>,
+    /// Indices of workers not leased.
+    idle: Mutex<Vec<usize>>,
+    permits: Arc<Semaphore>,
+    /// Runs waiting for a worker.
+    queued: AtomicUsize,
+    monitors_started: AtomicBool,
+}
+
+impl WorkerPool {
+    /// A pool of `size` workers configured like `config`, each with its own
+    /// ephemeral user-data-dir and no profile.
+    pub fn new(
+        size: usize,
+        config: &BrowserManagerConfig,
+        profile_manager: Arc<ProfileManager>,
+    ) -> Self {
+        let worker_config = BrowserManagerConfig {
+            profile: None,
+            ephemeral: true,
+            code_workers: 0,
+            ..config.clone()
+        };
+        let workers = (0..size)
+            .map(|_| {
+                Arc::new(BrowserManager::new(
+                    worker_config.clone(),
+                    profile_manager.clone(),
+                ))
+            })
+            .collect();
+        Self {
+            workers,
+            // Popped from the end, so worker 0 is leased first.
+            idle: Mutex::new((0..size).rev().collect()),
+            permits: Arc::new(Semaphore::new(size)),
+            queued: AtomicUsize::new(0),
+            monitors_started: AtomicBool::new(false),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Workers leased right now.
+    pub fn busy(&self) -> usize {
+        self.size() - self.permits.available_permits()
+    }
+
+    /// Runs waiting for a worker.
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+
+    /// Wait for a free worker and lease it until the lease is dropped.
+    pub async fn lease(self: &Arc<Self>) -> WorkerLease {
+        // The idle monitors need a runtime, which `new` may not run in.
+        if !self.monitors_started.swap(true, Ordering::SeqCst) {
+            for worker in &self.workers {
+                worker.spawn_lifecycle_monitor();
+            }
+        }
+
+        let started = Instant::now();
+        let queued = Queued::new(&self.queued);
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("worker pool semaphore is never closed");
+        drop(queued);
+
+        let index = self
+            .idle
+            .lock()
+            .unwrap()
+            .pop()
+            .expect("a permit guarantees an idle worker");
+        let lease = WorkerLease {
+            manager: self.workers[index].clone(),
+            worker: Some(index),
+            queue_wait: started.elapsed(),
+            slot: Some((self.clone(), permit)),
+        };
+        if let Err(e) = lease.manager.reset_session().await {
+            // A browser that cannot be reset is replaced on first use.
+            tracing::warn!(
+                worker = index,
+                "Failed to reset worker, restarting it: {:#}",
+                e
+            );
+            lease.manager.shutdown().await;
+        }
+        lease
+    }
+
+    /// Shut down every worker's browser.
+    pub async fn shutdown(&self) {
+        for worker in &self.workers {
+            worker.shutdown().await;
+        }
+    }
+}
+
+/// A run counted as waiting for a worker until dropped, so a call cancelled
+/// while queued is no longer counted.
+struct Queued<'a>(&'a AtomicUsize);
+
+impl<'a> Queued<'a> {
+    fn new(count: &'a AtomicUsize) -> Self {
+        count.fetch_add(1, Ordering::SeqCst);
+        Self(count)
+    }
+}
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A browser leased for one `execute_code` run.
+pub struct WorkerLease {
+    manager: Arc<BrowserManager>,
+    worker: Option<usize>,
+    queue_wait: Duration,
+    /// Pool and permit to give the worker back to; `None` for the shared
+    /// browser.
+    slot: Option<(Arc<WorkerPool>, OwnedSemaphorePermit)>,
+}
+
+impl WorkerLease {
+    /// A lease of the shared browser, used when there is no pool.
+    pub fn shared(manager: Arc<BrowserManager>) -> Self {
+        Self {
+            manager,
+            worker: None,
+            queue_wait: Duration::ZERO,
+            slot: None,
+        }
+    }
+
+    pub fn manager(&self) -> &Arc<BrowserManager> {
+        &self.manager
+    }
+
+    /// Index of the leased worker, or `None` for the shared browser.
+    pub fn worker(&self) -> Option<usize> {
+        self.worker
+    }
+
+    /// How long the run waited for a free worker.
+    pub fn queue_wait(&self) -> Duration {
+        self.queue_wait
+    }
+}
+
+impl Drop for WorkerLease {
+    fn drop(&mut self) {
+        if let (Some((pool, permit)), Some(index)) = (self.slot.take(), self.worker) {
+            // Return the worker before its permit, so the next lease finds it.
+            pool.idle.lock().unwrap().push(index);
+            drop(permit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(size: usize) -> (Arc<WorkerPool>, tempfile::TempDir) {
+        let tmp = tempfile::tempdir().unwrap();
+        let profile_manager = Arc::new(ProfileManager::with_dir(tmp.path().to_path_buf()).unwrap());
+        let pool = Arc::new(WorkerPool::new(
+            size,
+            &BrowserManagerConfig::default(),
+            profile_manager,
+        ));
+        (pool, tmp)
+    }
+
+    #[tokio::test]
+    async fn test_leases_distinct_workers_and_queues() {
+        let (pool, _tmp) = pool(2);
+        let a = pool.lease().await;
+        let b = pool.lease().await;
+        assert_eq!(a.worker(), Some(0));
+        assert_eq!(b.worker(), Some(1));
+        assert!(!Arc::ptr_eq(a.manager(), b.manager()));
+        assert_eq!(pool.busy(), 2);
+
+        let waiting = tokio::spawn({
+            let pool = pool.clone();
+            async move {
+                let lease = pool.lease().await;
+                (lease.worker(), lease.queue_wait())
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(pool.queued(), 1);
+
+        drop(b);
+        let (worker, waited) = waiting.await.unwrap();
+        assert_eq!(worker, Some(1));
+        assert!(waited >= Duration::from_millis(50));
+        assert_eq!(pool.queued(), 0);
+        assert_eq!(pool.busy(), 1);
+        drop(a);
+        assert_eq!(pool.busy(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_wait_is_not_counted() {
+        let (pool, _tmp) = pool(1);
+        let lease = pool.lease().await;
+
+        let waiting = tokio::spawn({
+            let pool = pool.clone();
+            async move {
+                pool.lease().await;
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(pool.queued(), 1);
+
+        waiting.abort();
+        let _ = waiting.await;
+        assert_eq!(pool.queued(), 0);
+        drop(lease);
+        assert_eq!(pool.busy(), 0);
+    }
+}
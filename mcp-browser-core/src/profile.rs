@@ -4,10 +4,17 @@
 //! and reuse it later. Profiles persist Chrome's user-data-dir so cookies, localStorage,
 //! and saved passwords carry across sessions.
 
+use crate::glob::glob_matches;
+use crate::launch::LaunchOptions;
+use crate::login_check::{LoginCheck, VerificationResult};
+use crate::network::NetworkSettings;
+use crate::profile_lock::ProfileLock;
+use crate::profile_vault::VaultKey;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 /// Metadata for a single browser profile.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,12 +34,33 @@ pub struct ProfileMetadata {
     pub login_notes: String,
     /// Hours before session is considered expired.
     pub session_timeout_hours: u64,
+    /// Proxy, extra headers, HTTP auth and certificate settings used
+    /// whenever this profile is launched.
+    #[serde(default, skip_serializing_if = "NetworkSettings::is_empty")]
+    pub network: NetworkSettings,
+    /// Extra Chrome arguments and extensions used whenever this profile is
+    /// launched.
+    #[serde(default, skip_serializing_if = "LaunchOptions::is_empty")]
+    pub launch: LaunchOptions,
+    /// How `verify_profile` tells whether the saved session is logged in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub login_check: Option<LoginCheck>,
+    /// When the login check last ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_verified_at: Option<DateTime<Utc>>,
+    /// Result of the last login check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_verification: Option<VerificationResult>,
+    /// The user-data-dir is stored encrypted (see `profile_vault`) and only
+    /// exists decrypted while a browser uses it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encrypted: bool,
 }
 
 /// Top-level profiles.json structure.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct ProfilesFile {
-    profiles: std::collections::HashMap<String, ProfileMetadata>,
+pub(crate) struct ProfilesFile {
+    pub(crate) profiles: std::collections::HashMap<String, ProfileMetadata>,
     version: String,
 }
 
@@ -58,7 +86,7 @@ impl Default for CreateOpts {
     fn default() -> Self {
         Self {
             description: String::new(),
-            browser_channel: "chrome".to_string(),
+            browser_channel: crate::check::DEFAULT_CHANNEL.to_string(),
             requires_human_login: false,
             login_notes: String::new(),
             session_timeout_hours: 24,
@@ -74,6 +102,42 @@ pub struct ProfileValidation {
     pub session_valid: bool,
 }
 
+/// A profile as reported to users: metadata plus login state. Network
+/// settings are reduced to a flag so credentials are never shown.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileSummary {
+    pub name: String,
+    pub description: String,
+    pub browser_channel: String,
+    pub user_data_dir: PathBuf,
+    pub created_at: DateTime<Utc>,
+    pub last_used: DateTime<Utc>,
+    pub usage_count: u64,
+    pub requires_human_login: bool,
+    pub login_notes: String,
+    pub session_timeout_hours: u64,
+    pub has_cookies: bool,
+    pub session_valid: bool,
+    /// A human has to log in again before automation will work.
+    pub needs_login: bool,
+    pub launch: LaunchOptions,
+    pub has_network_settings: bool,
+    pub login_check: Option<LoginCheck>,
+    pub last_verified_at: Option<DateTime<Utc>>,
+    pub last_verification: Option<VerificationResult>,
+    pub encrypted: bool,
+}
+
+/// Which profiles `prune_candidates` selects. When both are set a profile
+/// must match both.
+#[derive(Debug, Clone, Default)]
+pub struct PruneFilter {
+    /// Not used for at least this many days.
+    pub unused_days: Option<u64>,
+    /// Name matches this glob (`*` matches any characters).
+    pub pattern: Option<String>,
+}
+
 /// Manages browser profiles on disk.
 ///
 /// Profile storage:
@@ -81,7 +145,9 @@ pub struct ProfileValidation {
 /// - Linux: `~/.local/share/mcp-browser-server/profiles/`
 /// - Override: `BROWSER_PROFILES_DIR` env var
 pub struct ProfileManager {
-    profiles_dir: PathBuf,
+    pub(crate) profiles_dir: PathBuf,
+    /// Key for encrypted profiles; resolved from the environment when unset.
+    pub(crate) vault_key: Option<VaultKey>,
 }
 
 impl ProfileManager {
@@ -91,7 +157,10 @@ impl ProfileManager {
         std::fs::create_dir_all(&profiles_dir).with_context(|| {
             format!("Failed to create profiles dir: {}", profiles_dir.display())
         })?;
-        Ok(Self { profiles_dir })
+        Ok(Self {
+            profiles_dir,
+            vault_key: None,
+        })
     }
 
     /// Create a ProfileManager with a specific directory (useful for testing).
@@ -99,7 +168,10 @@ impl ProfileManager {
         std::fs::create_dir_all(&profiles_dir).with_context(|| {
             format!("Failed to create profiles dir: {}", profiles_dir.display())
         })?;
-        Ok(Self { profiles_dir })
+        Ok(Self {
+            profiles_dir,
+            vault_key: None,
+        })
     }
 
     /// List all profiles.
@@ -119,81 +191,138 @@ impl ProfileManager {
 
     /// Create a new profile.
     pub fn create_profile(&self, name: &str, opts: CreateOpts) -> Result<ProfileMetadata> {
-        let mut file = self.load_profiles_file()?;
-
-        if file.profiles.contains_key(name) {
-            anyhow::bail!("Profile '{}' already exists", name);
-        }
-
-        let profile_data_dir = self.profiles_dir.join(name);
-        std::fs::create_dir_all(&profile_data_dir).with_context(|| {
-            format!(
-                "Failed to create profile data dir: {}",
-                profile_data_dir.display()
-            )
-        })?;
-
-        let now = Utc::now();
-        let metadata = ProfileMetadata {
-            name: name.to_string(),
-            description: opts.description,
-            user_data_dir: profile_data_dir,
-            browser_channel: opts.browser_channel,
-            created_at: now,
-            last_used: now,
-            usage_count: 0,
-            requires_human_login: opts.requires_human_login,
-            login_notes: opts.login_notes,
-            session_timeout_hours: opts.session_timeout_hours,
-        };
+        validate_profile_name(name)?;
+        self.update_profiles(|file| {
+            if file.profiles.contains_key(name) {
+                anyhow::bail!("Profile '{}' already exists", name);
+            }
 
-        file.profiles.insert(name.to_string(), metadata.clone());
-        self.save_profiles_file(&file)?;
+            let profile_data_dir = self.profiles_dir.join(name);
+            std::fs::create_dir_all(&profile_data_dir).with_context(|| {
+                format!(
+                    "Failed to create profile data dir: {}",
+                    profile_data_dir.display()
+                )
+            })?;
 
-        Ok(metadata)
+            let now = Utc::now();
+            let metadata = ProfileMetadata {
+                name: name.to_string(),
+                description: opts.description,
+                user_data_dir: profile_data_dir,
+                browser_channel: opts.browser_channel,
+                created_at: now,
+                last_used: now,
+                usage_count: 0,
+                requires_human_login: opts.requires_human_login,
+                login_notes: opts.login_notes,
+                session_timeout_hours: opts.session_timeout_hours,
+                network: NetworkSettings::default(),
+                launch: LaunchOptions::default(),
+                login_check: None,
+                last_verified_at: None,
+                last_verification: None,
+                encrypted: false,
+            };
+
+            file.profiles.insert(name.to_string(), metadata.clone());
+            Ok(metadata)
+        })
     }
 
     /// Delete a profile and its data directory.
+    /// Refuses while another process holds the profile's lock.
     pub fn delete_profile(&self, name: &str) -> Result<()> {
-        let mut file = self.load_profiles_file()?;
-
-        let profile = file
-            .profiles
-            .remove(name)
-            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?;
-
-        // Remove the user-data-dir
-        if profile.user_data_dir.exists() {
-            std::fs::remove_dir_all(&profile.user_data_dir).with_context(|| {
-                format!(
-                    "Failed to remove profile data: {}",
-                    profile.user_data_dir.display()
-                )
-            })?;
-        }
+        self.delete_locked_profile(self.lock_profile(name)?)
+    }
 
-        self.save_profiles_file(&file)?;
+    /// Delete the profile `lock` is held for. Holding the lock throughout
+    /// keeps another process from launching the profile mid-delete.
+    pub fn delete_locked_profile(&self, lock: ProfileLock) -> Result<()> {
+        let name = lock.profile();
+        self.update_profiles(|file| {
+            let profile = file
+                .profiles
+                .remove(name)
+                .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?;
+
+            // Remove the user-data-dir
+            if profile.user_data_dir.exists() {
+                std::fs::remove_dir_all(&profile.user_data_dir).with_context(|| {
+                    format!(
+                        "Failed to remove profile data: {}",
+                        profile.user_data_dir.display()
+                    )
+                })?;
+            }
+            let vault = self.vault_path(name);
+            if vault.exists() {
+                std::fs::remove_file(&vault).with_context(|| {
+                    format!("Failed to remove encrypted profile: {}", vault.display())
+                })?;
+            }
+            Ok(())
+        })?;
+        // Releasing the lock removes its file with the profile.
+        drop(lock);
         Ok(())
     }
 
     /// Update last_used timestamp and increment usage count.
     pub fn touch_profile(&self, name: &str) -> Result<()> {
-        let mut file = self.load_profiles_file()?;
-        let profile = file
-            .profiles
-            .get_mut(name)
-            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?;
+        self.update_profile(name, |profile| {
+            profile.last_used = Utc::now();
+            profile.usage_count += 1;
+        })
+    }
 
-        profile.last_used = Utc::now();
-        profile.usage_count += 1;
-        self.save_profiles_file(&file)?;
-        Ok(())
+    /// Replace a profile's network settings.
+    pub fn set_network_settings(&self, name: &str, network: NetworkSettings) -> Result<()> {
+        self.update_profile(name, |profile| {
+            profile.network = network;
+        })
+    }
+
+    /// Replace a profile's default launch arguments and extensions.
+    pub fn set_launch_options(&self, name: &str, launch: LaunchOptions) -> Result<()> {
+        self.update_profile(name, |profile| {
+            profile.launch = launch;
+        })
+    }
+
+    /// Set or clear the login check used by `verify_profile`.
+    pub fn set_login_check(&self, name: &str, check: Option<LoginCheck>) -> Result<()> {
+        self.update_profile(name, |profile| {
+            profile.login_check = check;
+        })
+    }
+
+    /// Store the result of a login check.
+    pub fn record_verification(&self, name: &str, result: &VerificationResult) -> Result<()> {
+        self.update_profile(name, |profile| {
+            profile.last_verified_at = Some(Utc::now());
+            profile.last_verification = Some(result.clone());
+        })
     }
 
     /// Check if the profile's session is still valid (not expired).
+    ///
+    /// A failed login check since the last use means the session is gone; a
+    /// passed one counts like a use and restarts the timeout.
     pub fn is_session_valid(&self, name: &str) -> Result<bool> {
         let profile = self.get_profile(name)?;
-        let elapsed = Utc::now() - profile.last_used;
+        let mut since = profile.last_used;
+        if let (Some(verified_at), Some(result)) =
+            (profile.last_verified_at, &profile.last_verification)
+        {
+            if !result.logged_in && verified_at >= profile.last_used {
+                return Ok(false);
+            }
+            if result.logged_in {
+                since = since.max(verified_at);
+            }
+        }
+        let elapsed = Utc::now() - since;
         let timeout = chrono::Duration::hours(profile.session_timeout_hours as i64);
         Ok(elapsed < timeout)
     }
@@ -217,9 +346,15 @@ impl ProfileManager {
             }
         };
 
-        let has_cookies = profile.user_data_dir.exists()
-            && (profile.user_data_dir.join("Default/Cookies").exists()
-                || profile.user_data_dir.join("Cookies").exists());
+        // An encrypted profile can't be inspected without its key; count a
+        // non-empty vault as having cookies.
+        let has_cookies = if profile.encrypted {
+            std::fs::metadata(self.vault_path(name)).is_ok_and(|m| m.len() > 0)
+        } else {
+            profile.user_data_dir.exists()
+                && (profile.user_data_dir.join("Default/Cookies").exists()
+                    || profile.user_data_dir.join("Cookies").exists())
+        };
 
         let session_valid = self.is_session_valid(name).unwrap_or(false);
 
@@ -230,6 +365,160 @@ impl ProfileManager {
         })
     }
 
+    /// Summarize a profile with its login state.
+    pub fn summarize(&self, profile: &ProfileMetadata) -> ProfileSummary {
+        let validation = self.validate_profile(&profile.name).ok();
+        let has_cookies = validation.as_ref().is_some_and(|v| v.has_cookies);
+        let session_valid = validation.as_ref().is_some_and(|v| v.session_valid);
+
+        ProfileSummary {
+            name: profile.name.clone(),
+            description: profile.description.clone(),
+            browser_channel: profile.browser_channel.clone(),
+            user_data_dir: profile.user_data_dir.clone(),
+            created_at: profile.created_at,
+            last_used: profile.last_used,
+            usage_count: profile.usage_count,
+            requires_human_login: profile.requires_human_login,
+            login_notes: profile.login_notes.clone(),
+            session_timeout_hours: profile.session_timeout_hours,
+            has_cookies,
+            session_valid,
+            needs_login: (profile.requires_human_login || profile.login_check.is_some())
+                && !(has_cookies && session_valid),
+            launch: profile.launch.clone(),
+            has_network_settings: !profile.network.is_empty(),
+            login_check: profile.login_check.clone(),
+            last_verified_at: profile.last_verified_at,
+            last_verification: profile.last_verification.clone(),
+            encrypted: profile.encrypted,
+        }
+    }
+
+    /// Rename a profile, moving its data directory if it lives in the
+    /// profiles dir.
+    pub fn rename_profile(&self, name: &str, new_name: &str) -> Result<ProfileMetadata> {
+        validate_profile_name(new_name)?;
+        self.ensure_unlocked(name)?;
+        self.update_profiles(|file| {
+            if file.profiles.contains_key(new_name) {
+                anyhow::bail!("Profile '{}' already exists", new_name);
+            }
+            let mut profile = file
+                .profiles
+                .remove(name)
+                .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?;
+
+            let moves_dir = profile.user_data_dir == self.profiles_dir.join(name);
+            let new_dir = self.profiles_dir.join(new_name);
+            if moves_dir && new_dir.exists() {
+                anyhow::bail!("Directory {} already exists", new_dir.display());
+            }
+
+            // The vault moves first, so a failed directory move can put it
+            // back and leave the profile as it was.
+            let vault = self.vault_path(name);
+            let new_vault = self.vault_path(new_name);
+            let moved_vault = vault.exists();
+            if moved_vault {
+                std::fs::rename(&vault, &new_vault)
+                    .with_context(|| format!("Failed to move {}", vault.display()))?;
+            }
+            if moves_dir {
+                if profile.user_data_dir.exists() {
+                    if let Err(e) = std::fs::rename(&profile.user_data_dir, &new_dir) {
+                        if moved_vault {
+                            let _ = std::fs::rename(&new_vault, &vault);
+                        }
+                        return Err(e).with_context(|| {
+                            format!(
+                                "Failed to move {} to {}",
+                                profile.user_data_dir.display(),
+                                new_dir.display()
+                            )
+                        });
+                    }
+                }
+                profile.user_data_dir = new_dir;
+            }
+            profile.name = new_name.to_string();
+
+            file.profiles.insert(new_name.to_string(), profile.clone());
+            Ok(profile)
+        })
+    }
+
+    /// Copy a profile, including its cookies and storage, under a new name.
+    pub fn clone_profile(&self, name: &str, new_name: &str) -> Result<ProfileMetadata> {
+        validate_profile_name(new_name)?;
+        self.update_profiles(|file| {
+            if file.profiles.contains_key(new_name) {
+                anyhow::bail!("Profile '{}' already exists", new_name);
+            }
+            let source = file
+                .profiles
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?;
+
+            let new_dir = self.profiles_dir.join(new_name);
+            if new_dir.exists() {
+                anyhow::bail!("Directory {} already exists", new_dir.display());
+            }
+            if source.encrypted {
+                let vault = self.vault_path(name);
+                std::fs::copy(&vault, self.vault_path(new_name))
+                    .with_context(|| format!("Failed to copy {}", vault.display()))?;
+            } else {
+                copy_profile_dir(&source.user_data_dir, &new_dir).with_context(|| {
+                    format!(
+                        "Failed to copy {} to {}",
+                        source.user_data_dir.display(),
+                        new_dir.display()
+                    )
+                })?;
+            }
+
+            let now = Utc::now();
+            let profile = ProfileMetadata {
+                name: new_name.to_string(),
+                user_data_dir: new_dir,
+                created_at: now,
+                last_used: now,
+                usage_count: 0,
+                ..source
+            };
+
+            file.profiles.insert(new_name.to_string(), profile.clone());
+            Ok(profile)
+        })
+    }
+
+    /// Profiles selected by a prune filter, sorted by name. At least one
+    /// criterion is required so an empty filter never selects everything.
+    pub fn prune_candidates(&self, filter: &PruneFilter) -> Result<Vec<ProfileMetadata>> {
+        if filter.unused_days.is_none() && filter.pattern.is_none() {
+            anyhow::bail!("Prune needs an unused-days limit, a name pattern, or both");
+        }
+        let cutoff = filter
+            .unused_days
+            .map(|days| Utc::now() - chrono::Duration::days(days as i64));
+
+        let mut selected: Vec<ProfileMetadata> = self
+            .list_profiles()?
+            .into_iter()
+            .filter(|p| cutoff.is_none_or(|cutoff| p.last_used < cutoff))
+            .filter(|p| {
+                filter
+                    .pattern
+                    .as_deref()
+                    .is_none_or(|pattern| glob_matches(pattern, &p.name))
+            })
+            .collect();
+        selected.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(selected)
+    }
+
     /// Get or create a profile (used by setup-login and serve).
     pub fn get_or_create_profile(&self, name: &str, opts: CreateOpts) -> Result<ProfileMetadata> {
         match self.get_profile(name) {
@@ -254,15 +543,121 @@ impl ProfileManager {
         Ok(file)
     }
 
+    /// Read-modify-write `profiles.json` under an exclusive file lock so
+    /// concurrent processes never lose each other's updates. Changes are
+    /// saved only if `f` succeeds.
+    pub(crate) fn update_profiles<T>(
+        &self,
+        f: impl FnOnce(&mut ProfilesFile) -> Result<T>,
+    ) -> Result<T> {
+        let _lock = self.lock_metadata()?;
+        let mut file = self.load_profiles_file()?;
+        let value = f(&mut file)?;
+        self.save_profiles_file(&file)?;
+        Ok(value)
+    }
+
+    /// Take the exclusive lock on the profiles dir's metadata, held until
+    /// the returned file is dropped.
+    pub(crate) fn lock_metadata(&self) -> Result<std::fs::File> {
+        // Lock a separate file: profiles.json itself is replaced on save.
+        let lock_path = self.profiles_dir.join("profiles.json.lock");
+        let lock = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+        lock.lock()
+            .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+        Ok(lock)
+    }
+
+    /// Apply `f` to one profile's metadata and save.
+    fn update_profile(&self, name: &str, f: impl FnOnce(&mut ProfileMetadata)) -> Result<()> {
+        self.update_profiles(|file| {
+            let profile = file
+                .profiles
+                .get_mut(name)
+                .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?;
+            f(profile);
+            Ok(())
+        })
+    }
+
+    /// Write `profiles.json` atomically: readers see the old or the new
+    /// file, never a partial one.
     fn save_profiles_file(&self, file: &ProfilesFile) -> Result<()> {
         let path = self.profiles_file_path();
+        let tmp = self
+            .profiles_dir
+            .join(format!("profiles.json.tmp-{}", std::process::id()));
         let contents = serde_json::to_string_pretty(file)?;
-        std::fs::write(&path, contents)
-            .with_context(|| format!("Failed to write {}", path.display()))?;
+        {
+            let mut out = std::fs::File::create(&tmp)
+                .with_context(|| format!("Failed to write {}", tmp.display()))?;
+            out.write_all(contents.as_bytes())
+                .and_then(|_| out.sync_all())
+                .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        }
+        std::fs::rename(&tmp, &path)
+            .with_context(|| format!("Failed to replace {}", path.display()))?;
         Ok(())
     }
 }
 
+/// Check that a profile name is safe to use as a directory name.
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 100
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        anyhow::bail!(
+            "Invalid profile name '{}': use letters, digits, '-', '_' and '.', not starting with '.'",
+            name
+        );
+    }
+    // Lock files, vaults and profiles.json share the profiles dir.
+    if name.starts_with("profiles.json")
+        || [".lock", ".age", ".json"]
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+    {
+        anyhow::bail!(
+            "Invalid profile name '{}': names ending in .lock, .age or .json are reserved",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Recursively copy a user-data-dir, skipping Chrome's singleton lock files
+/// so the copy can be launched independently.
+fn copy_profile_dir(from: &Path, to: &Path) -> Result<()> {
+    std::fs::create_dir_all(to)?;
+    if !from.exists() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        if file_name.to_string_lossy().starts_with("Singleton") {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        let target = to.join(&file_name);
+        if file_type.is_dir() {
+            copy_profile_dir(&entry.path(), &target)?;
+        } else if file_type.is_file() {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
 /// Resolve the profiles directory using OS-appropriate paths.
 ///
 /// Priority:
@@ -367,6 +762,158 @@ mod tests {
         assert!(manager.is_session_valid("session").unwrap());
     }
 
+    #[test]
+    fn test_network_settings_roundtrip() {
+        let (manager, _tmp) = test_manager();
+        manager
+            .create_profile("net", CreateOpts::default())
+            .unwrap();
+        assert!(manager.get_profile("net").unwrap().network.is_empty());
+
+        let network = NetworkSettings {
+            strict_https: true,
+            ..Default::default()
+        };
+        manager
+            .set_network_settings("net", network.clone())
+            .unwrap();
+        assert_eq!(manager.get_profile("net").unwrap().network, network);
+    }
+
+    #[test]
+    fn test_verification_affects_session_validity() {
+        let (manager, _tmp) = test_manager();
+        manager
+            .create_profile("sso", CreateOpts::default())
+            .unwrap();
+        let check = LoginCheck::new(
+            "https://app.example.com",
+            crate::login_check::LoginCondition::Cookie("SID".to_string()),
+        );
+        manager.set_login_check("sso", Some(check.clone())).unwrap();
+        assert!(manager.is_session_valid("sso").unwrap());
+
+        let failed = VerificationResult {
+            logged_in: false,
+            final_url: "https://sso.example.com/login".to_string(),
+            detail: "Cookie 'SID' is not set".to_string(),
+        };
+        manager.record_verification("sso", &failed).unwrap();
+        let profile = manager.get_profile("sso").unwrap();
+        assert_eq!(profile.login_check, Some(check));
+        assert!(profile.last_verified_at.is_some());
+        assert!(!manager.is_session_valid("sso").unwrap());
+
+        manager
+            .record_verification(
+                "sso",
+                &VerificationResult {
+                    logged_in: true,
+                    ..failed
+                },
+            )
+            .unwrap();
+        assert!(manager.is_session_valid("sso").unwrap());
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        let (manager, _tmp) = test_manager();
+        let manager = std::sync::Arc::new(manager);
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let manager = manager.clone();
+                std::thread::spawn(move || {
+                    manager
+                        .create_profile(&format!("p{}", i), CreateOpts::default())
+                        .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(manager.list_profiles().unwrap().len(), 8);
+    }
+
+    #[test]
+    fn test_rename_profile_moves_data() {
+        let (manager, _tmp) = test_manager();
+        let profile = manager
+            .create_profile("old", CreateOpts::default())
+            .unwrap();
+        std::fs::write(profile.user_data_dir.join("Cookies"), "x").unwrap();
+
+        let renamed = manager.rename_profile("old", "new").unwrap();
+        assert_eq!(renamed.name, "new");
+        assert!(renamed.user_data_dir.join("Cookies").exists());
+        assert!(!profile.user_data_dir.exists());
+        assert!(manager.get_profile("old").is_err());
+        assert!(manager.rename_profile("new", "../escape").is_err());
+    }
+
+    #[test]
+    fn test_clone_profile_skips_locks() {
+        let (manager, _tmp) = test_manager();
+        let profile = manager
+            .create_profile("src", CreateOpts::default())
+            .unwrap();
+        std::fs::create_dir_all(profile.user_data_dir.join("Default")).unwrap();
+        std::fs::write(profile.user_data_dir.join("Default/Cookies"), "x").unwrap();
+        std::fs::write(profile.user_data_dir.join("SingletonLock"), "").unwrap();
+
+        let copy = manager.clone_profile("src", "dst").unwrap();
+        assert_eq!(copy.usage_count, 0);
+        assert!(copy.user_data_dir.join("Default/Cookies").exists());
+        assert!(!copy.user_data_dir.join("SingletonLock").exists());
+        assert!(manager.get_profile("src").is_ok());
+    }
+
+    #[test]
+    fn test_prune_candidates() {
+        let (manager, _tmp) = test_manager();
+        manager
+            .create_profile("test-1-0", CreateOpts::default())
+            .unwrap();
+        manager
+            .create_profile("test-1-1", CreateOpts::default())
+            .unwrap();
+        manager
+            .create_profile("work", CreateOpts::default())
+            .unwrap();
+
+        let by_pattern = manager
+            .prune_candidates(&PruneFilter {
+                pattern: Some("test-*".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(by_pattern.len(), 2);
+
+        let stale = manager
+            .prune_candidates(&PruneFilter {
+                unused_days: Some(1),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(stale.is_empty());
+
+        assert!(manager.prune_candidates(&PruneFilter::default()).is_err());
+    }
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("test-1-0").is_ok());
+        assert!(validate_profile_name("corp.sso_v2").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name(".hidden").is_err());
+        assert!(validate_profile_name("a/b").is_err());
+        assert!(validate_profile_name("work.lock").is_err());
+        assert!(validate_profile_name("work.age").is_err());
+        assert!(validate_profile_name("profiles.json").is_err());
+        assert!(validate_profile_name("profiles.json.tmp-1").is_err());
+    }
+
     #[test]
     fn test_validate_nonexistent_profile() {
         let (manager, _tmp) = test_manager();
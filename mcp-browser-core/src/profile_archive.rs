@@ -0,0 +1,618 @@
+//! Portable profile archives for moving a logged-in profile between machines.
+//!
+//! An archive is a gzip-compressed tar holding `manifest.json` (format
+//! version and the profile metadata) followed by the session-bearing parts
+//! of the user-data-dir under `user-data/`: cookies, local and session
+//! storage, IndexedDB, saved logins and preferences. Caches are left out.
+//! With a passphrase the whole archive is encrypted with age (scrypt).
+//! Import leaves out the profile's launch options and network settings
+//! unless they are explicitly trusted.
+//!
+//! Chrome encrypts cookies with an OS-level key (Keychain, DPAPI or the
+//! Linux keyring) unless it runs with `--password-store=basic`, so archives
+//! only carry sessions across machines that can read the same key.
+
+use crate::profile::{validate_profile_name, ProfileManager, ProfileMetadata};
+use age::secrecy::SecretString;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+const FORMAT_VERSION: u32 = 1;
+const MANIFEST_NAME: &str = "manifest.json";
+const DATA_PREFIX: &str = "user-data";
+const AGE_MAGIC: &[u8] = b"age-encryption.org/v1";
+
+/// Top-level files of the user-data-dir worth carrying over. `Local State`
+/// holds the key Chrome uses to encrypt cookies on some platforms.
+const ROOT_ENTRIES: &[&str] = &["Local State"];
+
+/// Files and directories kept from each browser profile directory
+/// (`Default`, `Profile 1`, ...).
+const PROFILE_ENTRIES: &[&str] = &[
+    "Cookies",
+    "Cookies-journal",
+    "Network",
+    "Local Storage",
+    "Session Storage",
+    "IndexedDB",
+    "Login Data",
+    "Login Data-journal",
+    "Preferences",
+    "Secure Preferences",
+];
+
+/// Directories Chrome rebuilds on its own, never worth storing.
+const CACHE_DIRS: &[&str] = &[
+    "Cache",
+    "Code Cache",
+    "GPUCache",
+    "DawnCache",
+    "DawnGraphiteCache",
+    "DawnWebGPUCache",
+    "GrShaderCache",
+    "GraphiteDawnCache",
+    "ShaderCache",
+    "component_crx_cache",
+    "Crashpad",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    format_version: u32,
+    exported_at: DateTime<Utc>,
+    profile: ProfileMetadata,
+}
+
+/// What `export_profile` wrote.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportSummary {
+    pub profile: String,
+    pub path: PathBuf,
+    pub files: usize,
+    pub encrypted: bool,
+}
+
+/// Options for `import_profile`.
+#[derive(Debug, Clone, Default)]
+pub struct ImportOpts {
+    /// Import under this name instead of the one in the archive.
+    pub name: Option<String>,
+    /// Passphrase for encrypted archives.
+    pub passphrase: Option<String>,
+    /// Replace an existing profile with the same name.
+    pub force: bool,
+    /// Keep the archive's launch options and network settings. Chrome
+    /// flags can run commands and a proxy sees all traffic, so by default
+    /// an archive only brings the session.
+    pub trust_settings: bool,
+}
+
+/// What `import_profile` created.
+#[derive(Debug, Clone)]
+pub struct ImportSummary {
+    pub profile: ProfileMetadata,
+    /// The archive had launch options or network settings that were left
+    /// out because `trust_settings` was not set.
+    pub dropped_settings: bool,
+}
+
+impl ProfileManager {
+    /// Write a profile to a portable archive at `dest`.
+    ///
+    /// Holds the profile's lock while reading it, so a profile in use by a
+    /// browser is refused rather than copied mid-write. The archive is
+    /// written next to `dest` and renamed into place once complete.
+    ///
+    /// Without a passphrase, proxy and HTTP credentials are removed from the
+    /// exported metadata; cookies are still included, so treat the archive
+    /// as a secret either way.
+    pub fn export_profile(
+        &self,
+        name: &str,
+        dest: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<ExportSummary> {
+        let mut profile = self.get_profile(name)?;
+        if passphrase.is_none() {
+            strip_credentials(&mut profile);
+        }
+        let _lock = self.lock_profile(name)?;
+        // Encrypted profiles are exported from a temporary decrypted copy
+        // and imported as plain ones.
+        let data = self.open_profile_data(name)?;
+        let source = data.dir().to_path_buf();
+        profile.encrypted = false;
+        let manifest = Manifest {
+            format_version: FORMAT_VERSION,
+            exported_at: Utc::now(),
+            profile,
+        };
+
+        let dir = match dest.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let tmp = tempfile::NamedTempFile::new_in(dir)
+            .with_context(|| format!("Failed to create an archive in {}", dir.display()))?;
+        let file = tmp.as_file().try_clone()?;
+        let files = match passphrase {
+            Some(passphrase) => {
+                let encryptor =
+                    age::Encryptor::with_user_passphrase(SecretString::from(passphrase.to_owned()));
+                let writer = encryptor.wrap_output(file)?;
+                let (writer, files) = write_archive(writer, &manifest, &source)?;
+                writer.finish()?.sync_all()?;
+                files
+            }
+            None => {
+                let (file, files) = write_archive(file, &manifest, &source)?;
+                file.sync_all()?;
+                files
+            }
+        };
+        tmp.persist(dest)
+            .with_context(|| format!("Failed to write {}", dest.display()))?;
+
+        Ok(ExportSummary {
+            profile: name.to_string(),
+            path: dest.to_path_buf(),
+            files,
+            encrypted: passphrase.is_some(),
+        })
+    }
+
+    /// Create a profile from an archive written by `export_profile`.
+    ///
+    /// Refuses to replace an existing profile unless `opts.force` is set.
+    pub fn import_profile(&self, archive: &Path, opts: ImportOpts) -> Result<ImportSummary> {
+        let file =
+            File::open(archive).with_context(|| format!("Failed to open {}", archive.display()))?;
+        let mut reader = BufReader::new(file);
+
+        if reader.fill_buf()?.starts_with(AGE_MAGIC) {
+            let passphrase = opts
+                .passphrase
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("Archive is encrypted; a passphrase is required"))?;
+            let identity = age::scrypt::Identity::new(SecretString::from(passphrase.to_owned()));
+            let decryptor = age::Decryptor::new_buffered(reader)?;
+            let reader = decryptor
+                .decrypt(std::iter::once(&identity as &dyn age::Identity))
+                .context("Failed to decrypt archive (wrong passphrase?)")?;
+            self.read_archive(reader, &opts)
+        } else {
+            self.read_archive(reader, &opts)
+        }
+    }
+
+    fn read_archive<R: Read>(&self, reader: R, opts: &ImportOpts) -> Result<ImportSummary> {
+        let mut archive = tar::Archive::new(GzDecoder::new(reader));
+        let mut entries = archive.entries().context("Not a profile archive")?;
+
+        let mut first = entries
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Archive is empty"))?
+            .context("Not a profile archive")?;
+        if first.path()?.as_ref() != Path::new(MANIFEST_NAME) {
+            anyhow::bail!("Not a profile archive: {} is missing", MANIFEST_NAME);
+        }
+        let mut contents = String::new();
+        first.read_to_string(&mut contents)?;
+        let manifest: Manifest =
+            serde_json::from_str(&contents).context("Failed to parse archive manifest")?;
+        if manifest.format_version > FORMAT_VERSION {
+            anyhow::bail!(
+                "Archive format version {} is newer than supported version {}",
+                manifest.format_version,
+                FORMAT_VERSION
+            );
+        }
+
+        let name = opts
+            .name
+            .clone()
+            .unwrap_or_else(|| manifest.profile.name.clone());
+        validate_profile_name(&name)?;
+        if self.get_profile(&name).is_ok() && !opts.force {
+            anyhow::bail!(
+                "Profile '{}' already exists; import with force to replace it, or under another name",
+                name
+            );
+        }
+
+        let staging = StagingDir::new(self.profiles_dir.join(format!(
+            ".import-{}-{}",
+            name,
+            std::process::id()
+        )))?;
+        for entry in entries {
+            let mut entry = entry?;
+            let path = entry.path()?.into_owned();
+            if !path.starts_with(DATA_PREFIX) {
+                anyhow::bail!("Unexpected archive entry {}", path.display());
+            }
+            let kind = entry.header().entry_type();
+            if !(kind.is_file() || kind.is_dir()) {
+                anyhow::bail!("Unsupported archive entry type for {}", path.display());
+            }
+            entry
+                .unpack_in(&staging.0)
+                .with_context(|| format!("Failed to extract {}", path.display()))?;
+        }
+
+        if self.get_profile(&name).is_ok() {
+            self.delete_profile(&name)?;
+        }
+        let target = self.profiles_dir.join(&name);
+        if target.exists() {
+            anyhow::bail!("Directory {} already exists", target.display());
+        }
+        let extracted = staging.0.join(DATA_PREFIX);
+        if extracted.exists() {
+            std::fs::rename(&extracted, &target)
+                .with_context(|| format!("Failed to move profile data to {}", target.display()))?;
+        } else {
+            std::fs::create_dir_all(&target)?;
+        }
+
+        let mut profile = ProfileMetadata {
+            name: name.clone(),
+            user_data_dir: target,
+            encrypted: false,
+            ..manifest.profile
+        };
+        let mut dropped_settings = false;
+        if !opts.trust_settings {
+            dropped_settings = !profile.launch.is_empty() || !profile.network.is_empty();
+            profile.launch = Default::default();
+            profile.network = Default::default();
+        }
+        self.update_profiles(|file| {
+            file.profiles.insert(name, profile.clone());
+            Ok(())
+        })?;
+        Ok(ImportSummary {
+            profile,
+            dropped_settings,
+        })
+    }
+}
+
+/// Write manifest and user data as a tar.gz, returning the inner writer and
+/// the number of data files written.
+fn write_archive<W: Write>(writer: W, manifest: &Manifest, source: &Path) -> Result<(W, usize)> {
+    let mut builder = tar::Builder::new(GzEncoder::new(writer, Compression::default()));
+
+    let contents = serde_json::to_vec_pretty(manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(manifest.exported_at.timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_NAME, contents.as_slice())?;
+
+    let mut files = 0;
+    for relative in session_entries(source)? {
+        let path = source.join(&relative);
+        let name = Path::new(DATA_PREFIX).join(&relative);
+        if path.is_dir() {
+            files += append_dir(&mut builder, &path, &name)?;
+        } else {
+            builder
+                .append_path_with_name(&path, &name)
+                .with_context(|| format!("Failed to archive {}", path.display()))?;
+            files += 1;
+        }
+    }
+
+    let writer = builder.into_inner()?.finish()?;
+    Ok((writer, files))
+}
+
+/// Paths (relative to the user-data-dir) that make up the session.
+fn session_entries(source: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries = Vec::new();
+    if !source.exists() {
+        return Ok(entries);
+    }
+    for name in ROOT_ENTRIES {
+        if source.join(name).exists() {
+            entries.push(PathBuf::from(name));
+        }
+    }
+    for dir in std::fs::read_dir(source)? {
+        let dir = dir?;
+        let dir_name = dir.file_name().to_string_lossy().into_owned();
+        if !dir.file_type()?.is_dir() || !is_browser_profile_dir(&dir_name) {
+            continue;
+        }
+        for name in PROFILE_ENTRIES {
+            let relative = Path::new(&dir_name).join(name);
+            if source.join(&relative).exists() {
+                entries.push(relative);
+            }
+        }
+    }
+    entries.sort();
+    Ok(entries)
+}
+
+fn is_browser_profile_dir(name: &str) -> bool {
+    name == "Default" || name.starts_with("Profile ")
+}
+
+/// Recursively append regular files, skipping symlinks, lock files and
+/// caches.
+pub(crate) fn append_dir<W: Write>(
+    builder: &mut tar::Builder<W>,
+    dir: &Path,
+    name: &Path,
+) -> Result<usize> {
+    let mut files = 0;
+    let mut children: Vec<_> = std::fs::read_dir(dir)?.collect::<std::io::Result<_>>()?;
+    children.sort_by_key(|entry| entry.file_name());
+    for child in children {
+        let file_type = child.file_type()?;
+        let child_name = name.join(child.file_name());
+        if file_type.is_dir() {
+            if CACHE_DIRS.iter().any(|cache| child.file_name() == *cache) {
+                continue;
+            }
+            files += append_dir(builder, &child.path(), &child_name)?;
+        } else if file_type.is_file() && child.file_name() != "LOCK" {
+            builder
+                .append_path_with_name(child.path(), &child_name)
+                .with_context(|| format!("Failed to archive {}", child.path().display()))?;
+            files += 1;
+        }
+    }
+    Ok(files)
+}
+
+fn strip_credentials(profile: &mut ProfileMetadata) {
+    let network = &mut profile.network;
+    if network.http_credentials.take().is_some() {
+        tracing::warn!("Leaving HTTP credentials out of unencrypted profile archive");
+    }
+    if let Some(proxy) = network.proxy.as_mut() {
+        if proxy.auth.take().is_some() {
+            tracing::warn!("Leaving proxy credentials out of unencrypted profile archive");
+        }
+    }
+}
+
+/// Extraction directory removed on drop, whether or not the import succeeds.
+struct StagingDir(PathBuf);
+
+impl StagingDir {
+    fn new(path: PathBuf) -> Result<Self> {
+        if path.exists() {
+            std::fs::remove_dir_all(&path)?;
+        }
+        std::fs::create_dir_all(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launch::LaunchOptions;
+    use crate::network::Credentials;
+    use crate::profile::CreateOpts;
+
+    fn test_manager(tmp: &tempfile::TempDir, dir: &str) -> ProfileManager {
+        ProfileManager::with_dir(tmp.path().join(dir)).unwrap()
+    }
+
+    fn seed_profile(manager: &ProfileManager, name: &str) -> ProfileMetadata {
+        let profile = manager.create_profile(name, CreateOpts::default()).unwrap();
+        let data = &profile.user_data_dir;
+        std::fs::create_dir_all(data.join("Default/Local Storage/leveldb")).unwrap();
+        std::fs::create_dir_all(data.join("Default/Cache/Cache_Data")).unwrap();
+        std::fs::write(data.join("Local State"), "{}").unwrap();
+        std::fs::write(data.join("Default/Cookies"), "cookies").unwrap();
+        std::fs::write(data.join("Default/Local Storage/leveldb/000003.log"), "ls").unwrap();
+        std::fs::write(data.join("Default/Local Storage/leveldb/LOCK"), "").unwrap();
+        std::fs::write(data.join("Default/Cache/Cache_Data/data_0"), "cache").unwrap();
+        std::fs::write(data.join("SingletonLock"), "").unwrap();
+        profile
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = test_manager(&tmp, "a");
+        seed_profile(&source, "work");
+        let archive = tmp.path().join("work.tar.gz");
+
+        let summary = source.export_profile("work", &archive, None).unwrap();
+        assert_eq!(summary.files, 3);
+        assert!(!summary.encrypted);
+
+        let target = test_manager(&tmp, "b");
+        let imported = target
+            .import_profile(&archive, ImportOpts::default())
+            .unwrap()
+            .profile;
+        let data = &imported.user_data_dir;
+        assert_eq!(imported.name, "work");
+        assert!(data.join("Default/Cookies").exists());
+        assert!(data
+            .join("Default/Local Storage/leveldb/000003.log")
+            .exists());
+        assert!(!data.join("Default/Local Storage/leveldb/LOCK").exists());
+        assert!(!data.join("Default/Cache").exists());
+        assert!(!data.join("SingletonLock").exists());
+        assert!(target.validate_profile("work").unwrap().has_cookies);
+    }
+
+    #[test]
+    fn test_export_refuses_locked_profile() {
+        let tmp = tempfile::tempdir().unwrap();
+        let manager = test_manager(&tmp, "p");
+        seed_profile(&manager, "work");
+        let archive = tmp.path().join("work.tar.gz");
+
+        let lock = manager.lock_profile("work").unwrap();
+        assert!(manager.export_profile("work", &archive, None).is_err());
+        assert!(!archive.exists());
+
+        drop(lock);
+        manager.export_profile("work", &archive, None).unwrap();
+        assert!(archive.exists());
+        let leftovers = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .filter(|entry| entry.as_ref().unwrap().path().is_file())
+            .count();
+        assert_eq!(leftovers, 1);
+    }
+
+    #[test]
+    fn test_import_refuses_overwrite_unless_forced() {
+        let tmp = tempfile::tempdir().unwrap();
+        let manager = test_manager(&tmp, "p");
+        seed_profile(&manager, "work");
+        let archive = tmp.path().join("work.tar.gz");
+        manager.export_profile("work", &archive, None).unwrap();
+
+        let err = manager
+            .import_profile(&archive, ImportOpts::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+
+        let renamed = manager
+            .import_profile(
+                &archive,
+                ImportOpts {
+                    name: Some("work-copy".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .profile;
+        assert!(renamed.user_data_dir.ends_with("work-copy"));
+
+        let forced = manager
+            .import_profile(
+                &archive,
+                ImportOpts {
+                    force: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .profile;
+        assert!(forced.user_data_dir.join("Default/Cookies").exists());
+        assert_eq!(manager.list_profiles().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_encrypted_archive_needs_passphrase() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = test_manager(&tmp, "a");
+        seed_profile(&source, "work");
+        let archive = tmp.path().join("work.tar.gz.age");
+        source
+            .export_profile("work", &archive, Some("correct horse"))
+            .unwrap();
+
+        let target = test_manager(&tmp, "b");
+        assert!(target
+            .import_profile(&archive, ImportOpts::default())
+            .is_err());
+        assert!(target
+            .import_profile(
+                &archive,
+                ImportOpts {
+                    passphrase: Some("wrong".to_string()),
+                    ..Default::default()
+                },
+            )
+            .is_err());
+
+        let imported = target
+            .import_profile(
+                &archive,
+                ImportOpts {
+                    passphrase: Some("correct horse".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .profile;
+        assert!(imported.user_data_dir.join("Default/Cookies").exists());
+    }
+
+    #[test]
+    fn test_plain_export_strips_credentials() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = test_manager(&tmp, "a");
+        seed_profile(&source, "work");
+        let mut network = source.get_profile("work").unwrap().network;
+        network.http_credentials = Some(Credentials {
+            username: "u".to_string(),
+            password: "p".to_string(),
+        });
+        network
+            .extra_headers
+            .insert("X-Team".to_string(), "qa".to_string());
+        source.set_network_settings("work", network).unwrap();
+
+        let archive = tmp.path().join("work.tar.gz");
+        source.export_profile("work", &archive, None).unwrap();
+
+        let imported = test_manager(&tmp, "b")
+            .import_profile(
+                &archive,
+                ImportOpts {
+                    trust_settings: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .profile;
+        assert!(imported.network.http_credentials.is_none());
+        assert_eq!(imported.network.extra_headers["X-Team"], "qa");
+    }
+
+    #[test]
+    fn test_import_drops_settings_unless_trusted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = test_manager(&tmp, "a");
+        seed_profile(&source, "work");
+        source
+            .set_launch_options(
+                "work",
+                LaunchOptions {
+                    args: vec!["--renderer-cmd-prefix=/tmp/evil".to_string()],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let archive = tmp.path().join("work.tar.gz");
+        source.export_profile("work", &archive, None).unwrap();
+
+        let target = test_manager(&tmp, "b");
+        let summary = target
+            .import_profile(&archive, ImportOpts::default())
+            .unwrap();
+        assert!(summary.dropped_settings);
+        assert!(summary.profile.launch.is_empty());
+        assert!(target.get_profile("work").unwrap().launch.is_empty());
+    }
+}
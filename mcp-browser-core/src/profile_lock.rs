@@ -0,0 +1,260 @@
+//! Advisory per-profile locks.
+//!
+//! Two browsers on one user-data-dir corrupt it, so whoever launches a
+//! profile first writes `<profiles_dir>/<name>.lock` recording its PID and
+//! host. Others refuse to use the profile until the lock is released.
+//! A lock whose process has exited on this host is stale and is taken
+//! over; locks from other hosts (shared profile dirs) are always honoured,
+//! as are lock files that cannot be read.
+
+use crate::profile::ProfileManager;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// The process holding a profile lock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockOwner {
+    pub pid: u32,
+    pub host: String,
+    pub acquired_at: DateTime<Utc>,
+}
+
+impl LockOwner {
+    fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            host: current_host(),
+            acquired_at: Utc::now(),
+        }
+    }
+
+    /// False only when the owner is known to have exited.
+    pub fn is_alive(&self) -> bool {
+        self.host != current_host() || process_exists(self.pid)
+    }
+}
+
+impl fmt::Display for LockOwner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "process {} on {} (since {})",
+            self.pid,
+            self.host,
+            self.acquired_at.format("%Y-%m-%d %H:%M:%S UTC")
+        )
+    }
+}
+
+/// A held profile lock, released on drop.
+#[derive(Debug)]
+pub struct ProfileLock {
+    profile: String,
+    path: PathBuf,
+    owner: LockOwner,
+}
+
+impl ProfileLock {
+    /// Name of the locked profile.
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+}
+
+impl Drop for ProfileLock {
+    fn drop(&mut self) {
+        // Only remove the file if it is still ours.
+        if read_owner(&self.path).as_ref() == Some(&self.owner) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+impl ProfileManager {
+    /// Take the profile's lock, replacing a stale one. Fails with the
+    /// holder's PID and host if another live process has it.
+    pub fn lock_profile(&self, name: &str) -> Result<ProfileLock> {
+        self.get_profile(name)?;
+        let path = self.lock_path(name);
+
+        // Lockers take turns, so a stale lock is never removed after
+        // another process has replaced it.
+        let _metadata = self.lock_metadata()?;
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                let owner: LockOwner = serde_json::from_str(&contents).map_err(|_| {
+                    anyhow::anyhow!(
+                        "Lock file {} is unreadable. Delete it if no browser-server is using profile '{}'.",
+                        path.display(),
+                        name
+                    )
+                })?;
+                if owner.is_alive() {
+                    return Err(self.in_use_error(name, &owner));
+                }
+                tracing::warn!(profile = %name, owner = %owner, "Removing stale profile lock");
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+
+        // Write the owner aside and link it into place, so the lock file
+        // never exists without its owner.
+        let owner = LockOwner::current();
+        let mut tmp = tempfile::NamedTempFile::new_in(&self.profiles_dir)
+            .context("Failed to create lock file")?;
+        tmp.write_all(serde_json::to_string(&owner)?.as_bytes())
+            .and_then(|_| tmp.as_file().sync_all())
+            .context("Failed to write lock file")?;
+        match std::fs::hard_link(tmp.path(), &path) {
+            Ok(()) => Ok(ProfileLock {
+                profile: name.to_string(),
+                path,
+                owner,
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                anyhow::bail!("Could not lock profile '{}'", name)
+            }
+            Err(e) => Err(e).with_context(|| format!("Failed to create {}", path.display())),
+        }
+    }
+
+    /// The live process holding the profile's lock, if any.
+    pub fn lock_owner(&self, name: &str) -> Option<LockOwner> {
+        read_owner(&self.lock_path(name)).filter(LockOwner::is_alive)
+    }
+
+    /// Fail if a live process holds the profile's lock.
+    pub(crate) fn ensure_unlocked(&self, name: &str) -> Result<()> {
+        match self.lock_owner(name) {
+            Some(owner) => Err(self.in_use_error(name, &owner)),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn lock_path(&self, name: &str) -> PathBuf {
+        self.profiles_dir.join(format!("{}.lock", name))
+    }
+
+    fn in_use_error(&self, name: &str, owner: &LockOwner) -> anyhow::Error {
+        anyhow::anyhow!(
+            "Profile '{}' is in use by {}. Stop that browser-server first, or delete {} if that process is gone.",
+            name,
+            owner,
+            self.lock_path(name).display()
+        )
+    }
+}
+
+fn read_owner(path: &std::path::Path) -> Option<LockOwner> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+pub(crate) fn current_host() -> String {
+    hostname::get()
+        .map(|h| h.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(unix)]
+pub(crate) fn process_exists(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 performs only the existence and permission check.
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Without a cheap liveness check, assume the owner is still running.
+#[cfg(not(unix))]
+pub(crate) fn process_exists(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::CreateOpts;
+
+    fn test_manager() -> (ProfileManager, tempfile::TempDir) {
+        let tmp = tempfile::tempdir().unwrap();
+        let manager = ProfileManager::with_dir(tmp.path().to_path_buf()).unwrap();
+        manager
+            .create_profile("work", CreateOpts::default())
+            .unwrap();
+        (manager, tmp)
+    }
+
+    #[test]
+    fn test_lock_is_exclusive_and_released_on_drop() {
+        let (manager, _tmp) = test_manager();
+        let lock = manager.lock_profile("work").unwrap();
+        assert_eq!(lock.profile(), "work");
+        assert_eq!(manager.lock_owner("work").unwrap().pid, std::process::id());
+
+        let err = manager.lock_profile("work").unwrap_err().to_string();
+        assert!(err.contains(&format!("process {}", std::process::id())));
+        assert!(manager.delete_profile("work").is_err());
+        assert!(manager.rename_profile("work", "other").is_err());
+
+        drop(lock);
+        assert!(manager.lock_owner("work").is_none());
+        assert!(manager.lock_profile("work").is_ok());
+    }
+
+    #[test]
+    fn test_stale_lock_is_replaced() {
+        let (manager, _tmp) = test_manager();
+        let stale = LockOwner {
+            // Far above any real PID limit, so never a live process.
+            pid: i32::MAX as u32,
+            host: current_host(),
+            acquired_at: Utc::now(),
+        };
+        std::fs::write(
+            manager.lock_path("work"),
+            serde_json::to_string(&stale).unwrap(),
+        )
+        .unwrap();
+        assert!(manager.lock_owner("work").is_none());
+
+        let lock = manager.lock_profile("work").unwrap();
+        assert_eq!(lock.owner.pid, std::process::id());
+    }
+
+    #[test]
+    fn test_unreadable_lock_is_not_taken_over() {
+        let (manager, _tmp) = test_manager();
+        std::fs::write(manager.lock_path("work"), "").unwrap();
+
+        let err = manager.lock_profile("work").unwrap_err().to_string();
+        assert!(err.contains("unreadable"));
+        assert!(manager.lock_path("work").exists());
+    }
+
+    #[test]
+    fn test_lock_from_other_host_is_honoured() {
+        let (manager, _tmp) = test_manager();
+        let remote = LockOwner {
+            pid: 1,
+            host: "some-other-host.example".to_string(),
+            acquired_at: Utc::now(),
+        };
+        std::fs::write(
+            manager.lock_path("work"),
+            serde_json::to_string(&remote).unwrap(),
+        )
+        .unwrap();
+
+        let err = manager.lock_profile("work").unwrap_err().to_string();
+        assert!(err.contains("some-other-host.example"));
+    }
+}
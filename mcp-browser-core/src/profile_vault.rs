@@ -0,0 +1,509 @@
+//! Encrypted-at-rest profile storage.
+//!
+//! An encrypted profile keeps its user-data-dir only as
+//! `<profiles_dir>/<name>.age`: a tar.gz of the directory (caches left out)
+//! encrypted with age to an X25519 key. Launching the profile decrypts it
+//! into a private 0700 directory, under `$XDG_RUNTIME_DIR` or `/dev/shm`
+//! when available so the plaintext stays in memory, and closing the browser
+//! re-encrypts it and removes the directory.
+//!
+//! The key is an `AGE-SECRET-KEY-1...` string taken from
+//! `BROWSER_PROFILE_KEY`, or read from the key file named by
+//! `BROWSER_PROFILE_KEY_FILE` (default: `profile.key` in the OS config
+//! directory). Output of `age-keygen` works as a key file.
+//!
+//! If the process is killed before closing, changes made since launch are
+//! lost and the decrypted copy remains until the runtime directory is
+//! cleared at logout or reboot.
+
+use crate::profile::ProfileManager;
+use crate::profile_archive::append_dir;
+use age::secrecy::ExposeSecret;
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// Private key that encrypts and decrypts profile vaults.
+#[derive(Clone)]
+pub struct VaultKey(age::x25519::Identity);
+
+impl std::fmt::Debug for VaultKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("VaultKey(..)")
+    }
+}
+
+impl VaultKey {
+    /// A new random key.
+    pub fn generate() -> Self {
+        Self(age::x25519::Identity::generate())
+    }
+
+    /// Parse a key, ignoring blank lines and `#` comments as written by
+    /// `age-keygen`.
+    pub fn parse(text: &str) -> Result<Self> {
+        let line = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .context("No key found")?;
+        line.parse()
+            .map(Self)
+            .map_err(|e| anyhow::anyhow!("Invalid profile key: {}", e))
+    }
+
+    /// Load the key from `BROWSER_PROFILE_KEY` or the key file.
+    pub fn load() -> Result<Self> {
+        if let Ok(key) = std::env::var("BROWSER_PROFILE_KEY") {
+            return Self::parse(&key).context("Invalid BROWSER_PROFILE_KEY");
+        }
+        let path = key_file_path()?;
+        if !path.exists() {
+            anyhow::bail!(
+                "No profile key: set BROWSER_PROFILE_KEY or create {} (run `browser-server \
+                 profile encrypt` to generate one)",
+                path.display()
+            );
+        }
+        Self::read_file(&path)
+    }
+
+    /// Load the key, generating and saving a new key file if none is
+    /// configured. Returns the path of a newly created key file.
+    pub fn load_or_create() -> Result<(Self, Option<PathBuf>)> {
+        if std::env::var_os("BROWSER_PROFILE_KEY").is_some() {
+            return Ok((Self::load()?, None));
+        }
+        let path = key_file_path()?;
+        if path.exists() {
+            return Ok((Self::read_file(&path)?, None));
+        }
+        let key = Self::generate();
+        key.save(&path)?;
+        Ok((key, Some(path)))
+    }
+
+    fn read_file(path: &Path) -> Result<Self> {
+        warn_if_shared(path);
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid key file {}", path.display()))
+    }
+
+    /// Write the key to a new file readable only by the owner.
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options
+            .open(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        writeln!(
+            file,
+            "# public key: {}\n{}",
+            self.0.to_public(),
+            self.0.to_string().expose_secret()
+        )
+        .and_then(|_| file.sync_all())
+        .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Where the key file lives: `BROWSER_PROFILE_KEY_FILE`, or `profile.key`
+/// in the OS config directory.
+pub fn key_file_path() -> Result<PathBuf> {
+    if let Ok(path) = std::env::var("BROWSER_PROFILE_KEY_FILE") {
+        return Ok(PathBuf::from(path));
+    }
+    let proj_dirs = directories::ProjectDirs::from("com", "openclaw", "mcp-browser-server")
+        .context("Failed to determine config directory for this OS")?;
+    Ok(proj_dirs.config_dir().join("profile.key"))
+}
+
+#[cfg(unix)]
+fn warn_if_shared(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    if let Ok(meta) = std::fs::metadata(path) {
+        if meta.permissions().mode() & 0o077 != 0 {
+            tracing::warn!(
+                path = %path.display(),
+                "Profile key file is readable by other users; chmod 600 it"
+            );
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn warn_if_shared(_path: &Path) {}
+
+/// A profile's user-data-dir, opened for use by a browser.
+///
+/// For an encrypted profile this is a private decrypted copy: `close`
+/// writes changes back to the vault, and dropping removes the copy either
+/// way. Plain profiles use their directory in place.
+#[derive(Debug)]
+pub struct ProfileData {
+    dir: PathBuf,
+    vault: Option<(PathBuf, VaultKey)>,
+}
+
+impl ProfileData {
+    /// Directory to launch the browser with.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.vault.is_some()
+    }
+
+    /// Re-encrypt the decrypted copy into the vault. Call after the browser
+    /// has exited. Does nothing for plain profiles.
+    pub fn close(self) -> Result<()> {
+        if let Some((vault, key)) = &self.vault {
+            seal(&self.dir, vault, key)?;
+            tracing::info!(vault = %vault.display(), "Re-encrypted profile data");
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ProfileData {
+    fn drop(&mut self) {
+        if self.vault.is_some() {
+            if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+                tracing::warn!(
+                    dir = %self.dir.display(),
+                    "Failed to remove decrypted profile data: {}",
+                    e
+                );
+            }
+        }
+    }
+}
+
+impl ProfileManager {
+    /// Use this key for encrypted profiles instead of resolving one from
+    /// the environment.
+    pub fn with_vault_key(mut self, key: VaultKey) -> Self {
+        self.vault_key = Some(key);
+        self
+    }
+
+    /// Open a profile's user-data-dir, decrypting it if it is encrypted.
+    /// Callers should hold the profile's lock while the data is open.
+    pub fn open_profile_data(&self, name: &str) -> Result<ProfileData> {
+        let profile = self.get_profile(name)?;
+        if !profile.encrypted {
+            return Ok(ProfileData {
+                dir: profile.user_data_dir,
+                vault: None,
+            });
+        }
+
+        let key = self.resolve_vault_key()?;
+        let vault = self.vault_path(name);
+        let dir = private_dir(name)?;
+        // Removes the directory again if decryption fails.
+        let data = ProfileData {
+            dir,
+            vault: Some((vault.clone(), key.clone())),
+        };
+        unseal(&vault, &data.dir, &key)
+            .with_context(|| format!("Failed to decrypt profile '{}'", name))?;
+        tracing::info!(profile = %name, dir = %data.dir.display(), "Decrypted profile data");
+        Ok(data)
+    }
+
+    /// Move a plain profile's user-data-dir into an encrypted vault.
+    pub fn encrypt_profile(&self, name: &str) -> Result<()> {
+        self.ensure_unlocked(name)?;
+        let profile = self.get_profile(name)?;
+        if profile.encrypted {
+            anyhow::bail!("Profile '{}' is already encrypted", name);
+        }
+        let key = self.resolve_vault_key()?;
+
+        std::fs::create_dir_all(&profile.user_data_dir)?;
+        seal(&profile.user_data_dir, &self.vault_path(name), &key)?;
+        self.update_profiles(|file| {
+            if let Some(profile) = file.profiles.get_mut(name) {
+                profile.encrypted = true;
+            }
+            Ok(())
+        })?;
+        std::fs::remove_dir_all(&profile.user_data_dir).with_context(|| {
+            format!(
+                "Encrypted, but failed to remove plaintext {}",
+                profile.user_data_dir.display()
+            )
+        })
+    }
+
+    /// Restore an encrypted profile's user-data-dir as a plain directory.
+    pub fn decrypt_profile(&self, name: &str) -> Result<()> {
+        self.ensure_unlocked(name)?;
+        let profile = self.get_profile(name)?;
+        if !profile.encrypted {
+            anyhow::bail!("Profile '{}' is not encrypted", name);
+        }
+        let key = self.resolve_vault_key()?;
+        let target = &profile.user_data_dir;
+        if target.exists() {
+            anyhow::bail!("Directory {} already exists", target.display());
+        }
+
+        let staging = self
+            .profiles_dir
+            .join(format!(".decrypt-{}-{}", name, std::process::id()));
+        let vault = self.vault_path(name);
+        if let Err(e) = unseal(&vault, &staging, &key) {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(e.context(format!("Failed to decrypt profile '{}'", name)));
+        }
+        std::fs::rename(&staging, target)
+            .with_context(|| format!("Failed to move profile data to {}", target.display()))?;
+        self.update_profiles(|file| {
+            if let Some(profile) = file.profiles.get_mut(name) {
+                profile.encrypted = false;
+            }
+            Ok(())
+        })?;
+        std::fs::remove_file(&vault)
+            .with_context(|| format!("Failed to remove {}", vault.display()))
+    }
+
+    pub(crate) fn vault_path(&self, name: &str) -> PathBuf {
+        self.profiles_dir.join(format!("{}.age", name))
+    }
+
+    fn resolve_vault_key(&self) -> Result<VaultKey> {
+        match &self.vault_key {
+            Some(key) => Ok(key.clone()),
+            None => VaultKey::load(),
+        }
+    }
+}
+
+/// Create a new empty directory only the current user can read, preferring
+/// memory-backed locations. The random suffix keeps two opens of the same
+/// profile apart.
+fn private_dir(name: &str) -> Result<PathBuf> {
+    let base = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+        .or_else(|| Some(PathBuf::from("/dev/shm")).filter(|dir| dir.is_dir()))
+        .unwrap_or_else(std::env::temp_dir);
+    let prefix = format!("mcp-browser-{}-{}-", name, std::process::id());
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(&prefix);
+    #[cfg(unix)]
+    builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o700));
+    let dir = builder
+        .tempdir_in(&base)
+        .with_context(|| format!("Failed to create a private dir in {}", base.display()))?;
+    // `ProfileData` removes it once sealed again.
+    Ok(dir.keep())
+}
+
+/// Encrypt `dir` into `vault`, replacing it atomically.
+fn seal(dir: &Path, vault: &Path, key: &VaultKey) -> Result<()> {
+    let tmp = vault.with_extension(format!("age.tmp-{}", std::process::id()));
+    let result = (|| {
+        let file =
+            File::create(&tmp).with_context(|| format!("Failed to create {}", tmp.display()))?;
+        let recipient = key.0.to_public();
+        let encryptor =
+            age::Encryptor::with_recipients(std::iter::once(&recipient as &dyn age::Recipient))?;
+        let mut builder = tar::Builder::new(GzEncoder::new(
+            encryptor.wrap_output(file)?,
+            Compression::fast(),
+        ));
+        append_dir(&mut builder, dir, Path::new(""))?;
+        builder.into_inner()?.finish()?.finish()?.sync_all()?;
+        std::fs::rename(&tmp, vault)
+            .with_context(|| format!("Failed to replace {}", vault.display()))
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Decrypt `vault` into `dir`, which is created if missing.
+fn unseal(vault: &Path, dir: &Path, key: &VaultKey) -> Result<()> {
+    let file = File::open(vault).with_context(|| format!("Failed to open {}", vault.display()))?;
+    let decryptor = age::Decryptor::new_buffered(BufReader::new(file))?;
+    let reader = decryptor
+        .decrypt(std::iter::once(&key.0 as &dyn age::Identity))
+        .context("Wrong profile key?")?;
+
+    std::fs::create_dir_all(dir)?;
+    let mut archive = tar::Archive::new(GzDecoder::new(reader));
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let kind = entry.header().entry_type();
+        if !(kind.is_file() || kind.is_dir()) {
+            anyhow::bail!("Unsupported entry type for {}", entry.path()?.display());
+        }
+        entry.unpack_in(dir)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::CreateOpts;
+
+    fn test_manager(tmp: &tempfile::TempDir) -> ProfileManager {
+        ProfileManager::with_dir(tmp.path().join("profiles"))
+            .unwrap()
+            .with_vault_key(VaultKey::generate())
+    }
+
+    fn seed_profile(manager: &ProfileManager, name: &str) -> PathBuf {
+        let profile = manager.create_profile(name, CreateOpts::default()).unwrap();
+        let data = profile.user_data_dir;
+        std::fs::create_dir_all(data.join("Default/Cache")).unwrap();
+        std::fs::write(data.join("Default/Cookies"), "cookies").unwrap();
+        std::fs::write(data.join("Default/Cache/data_0"), "cache").unwrap();
+        data
+    }
+
+    #[test]
+    fn test_encrypted_profile_roundtrip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let manager = test_manager(&tmp);
+        let plain = seed_profile(&manager, "work");
+
+        manager.encrypt_profile("work").unwrap();
+        assert!(!plain.exists());
+        assert!(manager.get_profile("work").unwrap().encrypted);
+        let sealed = std::fs::read(manager.vault_path("work")).unwrap();
+        assert!(!sealed.windows(7).any(|w| w == b"cookies"));
+        assert!(manager.validate_profile("work").unwrap().has_cookies);
+
+        let data = manager.open_profile_data("work").unwrap();
+        let dir = data.dir().to_path_buf();
+        assert_ne!(dir, plain);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("Default/Cookies")).unwrap(),
+            "cookies"
+        );
+        assert!(!dir.join("Default/Cache").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+
+        std::fs::write(dir.join("Default/Cookies"), "new session").unwrap();
+        data.close().unwrap();
+        assert!(!dir.exists());
+
+        manager.decrypt_profile("work").unwrap();
+        assert!(!manager.vault_path("work").exists());
+        assert!(!manager.get_profile("work").unwrap().encrypted);
+        assert_eq!(
+            std::fs::read_to_string(plain.join("Default/Cookies")).unwrap(),
+            "new session"
+        );
+    }
+
+    #[test]
+    fn test_dropping_without_close_discards_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let manager = test_manager(&tmp);
+        seed_profile(&manager, "work");
+        manager.encrypt_profile("work").unwrap();
+
+        let data = manager.open_profile_data("work").unwrap();
+        let dir = data.dir().to_path_buf();
+        std::fs::write(dir.join("Default/Cookies"), "changed").unwrap();
+        drop(data);
+        assert!(!dir.exists());
+
+        let data = manager.open_profile_data("work").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(data.dir().join("Default/Cookies")).unwrap(),
+            "cookies"
+        );
+    }
+
+    #[test]
+    fn test_concurrent_opens_use_separate_dirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let manager = test_manager(&tmp);
+        seed_profile(&manager, "work");
+        manager.encrypt_profile("work").unwrap();
+
+        let first = manager.open_profile_data("work").unwrap();
+        let second = manager.open_profile_data("work").unwrap();
+        assert_ne!(first.dir(), second.dir());
+        drop(second);
+        assert!(first.dir().join("Default/Cookies").exists());
+    }
+
+    #[test]
+    fn test_wrong_key_fails() {
+        let tmp = tempfile::tempdir().unwrap();
+        let manager = test_manager(&tmp);
+        seed_profile(&manager, "work");
+        manager.encrypt_profile("work").unwrap();
+
+        let other = ProfileManager::with_dir(tmp.path().join("profiles"))
+            .unwrap()
+            .with_vault_key(VaultKey::generate());
+        assert!(other.open_profile_data("work").is_err());
+        assert!(other.decrypt_profile("work").is_err());
+        assert!(manager.get_profile("work").unwrap().encrypted);
+    }
+
+    #[test]
+    fn test_parse_key_file_format() {
+        let key = VaultKey::generate();
+        let text = format!(
+            "# created: 2024-01-01\n# public key: {}\n{}\n",
+            key.0.to_public(),
+            key.0.to_string().expose_secret()
+        );
+        let parsed = VaultKey::parse(&text).unwrap();
+        assert_eq!(
+            parsed.0.to_public().to_string(),
+            key.0.to_public().to_string()
+        );
+        assert!(VaultKey::parse("# nothing here\n").is_err());
+        assert!(VaultKey::parse("AGE-SECRET-KEY-1NOPE").is_err());
+    }
+
+    #[test]
+    fn test_clone_rename_delete_move_the_vault() {
+        let tmp = tempfile::tempdir().unwrap();
+        let manager = test_manager(&tmp);
+        seed_profile(&manager, "work");
+        manager.encrypt_profile("work").unwrap();
+
+        manager.clone_profile("work", "copy").unwrap();
+        assert!(manager.get_profile("copy").unwrap().encrypted);
+        manager.rename_profile("copy", "moved").unwrap();
+        assert!(!manager.vault_path("copy").exists());
+        let data = manager.open_profile_data("moved").unwrap();
+        assert!(data.dir().join("Default/Cookies").exists());
+        drop(data);
+
+        manager.delete_profile("moved").unwrap();
+        assert!(!manager.vault_path("moved").exists());
+        assert!(manager.vault_path("work").exists());
+    }
+}
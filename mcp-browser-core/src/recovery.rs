@@ -0,0 +1,178 @@
+//! Crash recovery: remember where each tab is so a relaunched browser can
+//! reopen the same tabs.
+//!
+//! A crashed browser can no longer be asked for its pages' URLs, so the
+//! tracker follows main-frame navigations (including same-document ones
+//! such as `history.pushState`) as they happen.
+
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::page::{
+    EventFrameNavigated, EventNavigatedWithinDocument, FrameId,
+};
+use chromiumoxide::cdp::browser_protocol::target::TargetId;
+use chromiumoxide::Page;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Last known location of one tab.
+#[derive(Debug, Clone, Default)]
+struct TabRecord {
+    main_frame: Option<FrameId>,
+    url: String,
+}
+
+/// Last known URL of every open tab.
+#[derive(Default)]
+pub struct TabTracker {
+    tabs: Mutex<HashMap<TargetId, TabRecord>>,
+}
+
+impl TabTracker {
+    /// Start following `page`'s navigations. The listener task ends when
+    /// the page closes, but the record is kept so it survives a crash;
+    /// callers `forget` closed tabs.
+    pub async fn watch(self: &Arc<Self>, page: &Page) -> Result<()> {
+        let mut navigated = page
+            .event_listener::<EventFrameNavigated>()
+            .await
+            .context("Failed to subscribe to navigation events")?;
+        let mut within_document = page
+            .event_listener::<EventNavigatedWithinDocument>()
+            .await
+            .context("Failed to subscribe to navigation events")?;
+
+        let tracker = self.clone();
+        let target = page.target_id().clone();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = navigated.next() => {
+                        let Some(event) = event else { break };
+                        let frame = &event.frame;
+                        if frame.parent_id.is_none() {
+                            let url = match &frame.url_fragment {
+                                Some(fragment) => format!("{}{}", frame.url, fragment),
+                                None => frame.url.clone(),
+                            };
+                            tracker.on_navigated(&target, frame.id.clone(), url);
+                        }
+                    }
+                    event = within_document.next() => {
+                        let Some(event) = event else { break };
+                        tracker.on_navigated_within_document(&target, &event.frame_id, &event.url);
+                    }
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Last known URL of a tab.
+    pub fn url(&self, target: &TargetId) -> Option<String> {
+        self.tabs
+            .lock()
+            .unwrap()
+            .get(target)
+            .map(|tab| tab.url.clone())
+    }
+
+    pub fn forget(&self, target: &TargetId) {
+        self.tabs.lock().unwrap().remove(target);
+    }
+
+    /// Drop all records (e.g. after a browser relaunch).
+    pub fn clear(&self) {
+        self.tabs.lock().unwrap().clear();
+    }
+
+    fn on_navigated(&self, target: &TargetId, frame: FrameId, url: String) {
+        self.tabs.lock().unwrap().insert(
+            target.clone(),
+            TabRecord {
+                main_frame: Some(frame),
+                url,
+            },
+        );
+    }
+
+    fn on_navigated_within_document(&self, target: &TargetId, frame: &FrameId, url: &str) {
+        let mut tabs = self.tabs.lock().unwrap();
+        if let Some(tab) = tabs.get_mut(target) {
+            if tab.main_frame.as_ref() == Some(frame) {
+                tab.url = url.to_string();
+            }
+        }
+    }
+}
+
+/// Tabs of a crashed or idle-shut-down browser, captured before its page
+/// state is cleared.
+#[derive(Debug, Clone)]
+pub(crate) struct TabSnapshot {
+    pub urls: Vec<String>,
+    pub active_idx: usize,
+}
+
+/// What happened when tabs were reopened after a browser crash.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveryReport {
+    pub recovered_at: DateTime<Utc>,
+    /// URLs of the reopened tabs, in tab order.
+    pub restored: Vec<String>,
+    /// URLs that failed to load; their tabs were reopened blank.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<String>,
+    pub active_index: usize,
+}
+
+/// Browser crash, relaunch and reconnect counters.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CrashStats {
+    /// Times the browser was found dead.
+    pub crashes: u64,
+    /// Times it was relaunched after a crash.
+    pub restarts: u64,
+    pub last_crash_at: Option<DateTime<Utc>>,
+    pub last_recovery: Option<RecoveryReport>,
+    /// Times the connection to a `--cdp-url` browser dropped.
+    pub disconnects: u64,
+    /// Times it was connected again.
+    pub reconnects: u64,
+    pub last_disconnect_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracks_main_frame_navigations() {
+        let tracker = TabTracker::default();
+        let target = TargetId::from("tab-1".to_string());
+        let main = FrameId::from("main".to_string());
+        let child = FrameId::from("child".to_string());
+
+        assert_eq!(tracker.url(&target), None);
+        tracker.on_navigated(&target, main.clone(), "https://a.example/".to_string());
+        tracker.on_navigated_within_document(&target, &main, "https://a.example/#inbox");
+        assert_eq!(
+            tracker.url(&target).as_deref(),
+            Some("https://a.example/#inbox")
+        );
+
+        // Same-document navigations of iframes don't move the tab.
+        tracker.on_navigated_within_document(&target, &child, "https://ads.example/");
+        assert_eq!(
+            tracker.url(&target).as_deref(),
+            Some("https://a.example/#inbox")
+        );
+
+        tracker.forget(&target);
+        assert_eq!(tracker.url(&target), None);
+    }
+}
@@ -0,0 +1,146 @@
+//! Attaching to a browser through `--cdp-url`.
+//!
+//! The URL may be the browser's WebSocket URL or its HTTP debugging endpoint
+//! (`http://host:9222`), which is asked for the WebSocket URL through
+//! `/json/version` on every connect, since the browser picks a new one each
+//! time it starts. A dropped connection is retried with backoff; tabs the
+//! remote browser already has are adopted rather than replaced.
+
+use anyhow::{bail, Context, Result};
+use chromiumoxide::cdp::browser_protocol::target::TargetId;
+use reqwest::Url;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// How long `/json/version` may take to answer.
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Delay before the first reconnect attempt; doubled after each failure.
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest delay between reconnect attempts.
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(8);
+
+/// Reconnect attempts before giving up.
+pub const RECONNECT_ATTEMPTS: usize = 6;
+
+#[derive(Deserialize)]
+struct VersionInfo {
+    #[serde(rename = "webSocketDebuggerUrl")]
+    web_socket_debugger_url: String,
+}
+
+/// The browser's WebSocket URL for a `--cdp-url`. WebSocket URLs are used as
+/// given.
+pub async fn resolve_websocket_url(cdp_url: &str) -> Result<String> {
+    let endpoint =
+        Url::parse(cdp_url).with_context(|| format!("Invalid --cdp-url '{}'", cdp_url))?;
+    match endpoint.scheme() {
+        "ws" | "wss" => return Ok(cdp_url.to_string()),
+        "http" | "https" => {}
+        other => bail!(
+            "Unsupported --cdp-url scheme '{}': expected ws://, wss://, http:// or https://",
+            other
+        ),
+    }
+
+    let version_url = endpoint
+        .join("/json/version")
+        .context("Invalid --cdp-url")?;
+    let client = reqwest::Client::builder()
+        .timeout(DISCOVERY_TIMEOUT)
+        .build()
+        .context("Failed to create HTTP client")?;
+    let info: VersionInfo = client
+        .get(version_url.clone())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to reach {}", version_url))?
+        .json()
+        .await
+        .with_context(|| format!("{} did not return a WebSocket URL", version_url))?;
+
+    let url = websocket_url(&endpoint, &info.web_socket_debugger_url)?;
+    tracing::debug!(endpoint = %cdp_url, websocket = %url, "Discovered browser WebSocket URL");
+    Ok(url)
+}
+
+/// The reported WebSocket URL, reached through the endpoint's host and
+/// port. The browser reports the address it listens on (often 127.0.0.1),
+/// which is wrong when the endpoint is forwarded or remote; the WebSocket is
+/// always served by the same server as `/json/version`.
+fn websocket_url(endpoint: &Url, reported: &str) -> Result<String> {
+    let reported = Url::parse(reported)
+        .with_context(|| format!("Browser reported an invalid WebSocket URL '{}'", reported))?;
+    let mut url = endpoint.clone();
+    let scheme = if endpoint.scheme() == "https" {
+        "wss"
+    } else {
+        "ws"
+    };
+    url.set_scheme(scheme)
+        .map_err(|_| anyhow::anyhow!("Cannot build a WebSocket URL from {}", endpoint))?;
+    url.set_path(reported.path());
+    url.set_query(reported.query());
+    Ok(url.to_string())
+}
+
+/// Delays between reconnect attempts.
+pub fn reconnect_delays() -> impl Iterator<Item = Duration> {
+    std::iter::successors(Some(RECONNECT_INITIAL_DELAY), |delay| {
+        Some((*delay * 2).min(RECONNECT_MAX_DELAY))
+    })
+    .take(RECONNECT_ATTEMPTS)
+}
+
+/// Order the remote browser's tabs: tabs that were open before a reconnect
+/// keep their order, tabs opened meanwhile follow.
+pub fn order_targets(mut found: Vec<TargetId>, previous: &[TargetId]) -> Vec<TargetId> {
+    found.sort_by_key(|target| {
+        previous
+            .iter()
+            .position(|p| p == target)
+            .unwrap_or(usize::MAX)
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_websocket_url_uses_endpoint_address() {
+        let endpoint = Url::parse("http://10.0.0.5:9333").unwrap();
+        let url = websocket_url(&endpoint, "ws://127.0.0.1:9222/devtools/browser/3f2a-11").unwrap();
+        assert_eq!(url, "ws://10.0.0.5:9333/devtools/browser/3f2a-11");
+
+        let endpoint = Url::parse("https://browser.example/").unwrap();
+        let url = websocket_url(&endpoint, "ws://localhost/devtools/browser/abc").unwrap();
+        assert_eq!(url, "wss://browser.example/devtools/browser/abc");
+    }
+
+    #[tokio::test]
+    async fn test_websocket_urls_are_used_as_given() {
+        let url = "ws://127.0.0.1:9222/devtools/browser/abc";
+        assert_eq!(resolve_websocket_url(url).await.unwrap(), url);
+        assert!(resolve_websocket_url("ftp://host:9222").await.is_err());
+    }
+
+    #[test]
+    fn test_reconnect_delays() {
+        let delays: Vec<u64> = reconnect_delays().map(|d| d.as_millis() as u64).collect();
+        assert_eq!(delays, vec![500, 1000, 2000, 4000, 8000, 8000]);
+    }
+
+    #[test]
+    fn test_order_targets_keeps_previous_order() {
+        let id = |s: &str| TargetId::from(s.to_string());
+        let ordered = order_targets(
+            vec![id("new"), id("b"), id("a")],
+            &[id("a"), id("gone"), id("b")],
+        );
+        assert_eq!(ordered, vec![id("a"), id("b"), id("new")]);
+    }
+}
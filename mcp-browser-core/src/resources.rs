@@ -9,6 +9,8 @@
 //! the `get_dom` and `get_url` tools.
 
 use crate::browser::BrowserManager;
+use crate::limits;
+use crate::tools::run_tool;
 use pmcp::TypedTool;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -35,20 +37,29 @@ pub fn register_resources(
         TypedTool::new("get_dom", move |_input: GetDomInput, _extra| {
             let m = m.clone();
             Box::pin(async move {
-                let page = m
-                    .page()
-                    .await
-                    .map_err(|e| pmcp::Error::internal(format!("Browser error: {}", e)))?;
+                run_tool(&m, async {
+                    let page = m
+                        .page()
+                        .await
+                        .map_err(|e| pmcp::Error::internal(format!("Browser error: {}", e)))?;
 
-                let html = page
-                    .content()
-                    .await
-                    .map_err(|e| pmcp::Error::internal(format!("Failed to get DOM: {}", e)))?;
+                    let mut html = page
+                        .content()
+                        .await
+                        .map_err(|e| pmcp::Error::internal(format!("Failed to get DOM: {}", e)))?;
+                    let total_bytes = limits::truncate(&mut html, m.limits().max_content_bytes);
 
-                Ok(json!({
-                    "dom": html,
-                    "type": "text/html"
-                }))
+                    let mut result = json!({
+                        "dom": html,
+                        "type": "text/html"
+                    });
+                    if let Some(total_bytes) = total_bytes {
+                        result["truncated"] = json!(true);
+                        result["total_bytes"] = json!(total_bytes);
+                    }
+                    Ok(result)
+                })
+                .await
             })
         })
         .with_description("Get the current page's DOM as HTML."),
@@ -60,21 +71,24 @@ pub fn register_resources(
         TypedTool::new("get_url", move |_input: GetUrlInput, _extra| {
             let m = m.clone();
             Box::pin(async move {
-                let page = m
-                    .page()
-                    .await
-                    .map_err(|e| pmcp::Error::internal(format!("Browser error: {}", e)))?;
+                run_tool(&m, async {
+                    let page = m
+                        .page()
+                        .await
+                        .map_err(|e| pmcp::Error::internal(format!("Browser error: {}", e)))?;
 
-                let url = page
-                    .url()
-                    .await
-                    .map_err(|e| pmcp::Error::internal(format!("Failed to get URL: {}", e)))?
-                    .unwrap_or_default()
-                    .to_string();
+                    let url = page
+                        .url()
+                        .await
+                        .map_err(|e| pmcp::Error::internal(format!("Failed to get URL: {}", e)))?
+                        .unwrap_or_default()
+                        .to_string();
 
-                Ok(json!({
-                    "url": url
-                }))
+                    Ok(json!({
+                        "url": url
+                    }))
+                })
+                .await
             })
         })
         .with_description("Get the current page's URL."),
@@ -0,0 +1,243 @@
+//! Browser status and resource usage, for monitoring leaking or stuck
+//! browsers.
+//!
+//! CPU time per process comes from CDP `SystemInfo.getProcessInfo`. Chrome
+//! does not report process memory over CDP, so for local browsers on Linux
+//! the resident set size is read from `/proc`; elsewhere only per-page
+//! JavaScript heap sizes from `Performance.getMetrics` are available.
+
+use crate::lifecycle::LifecycleStats;
+use crate::recovery::CrashStats;
+use chromiumoxide::cdp::browser_protocol::performance::Metric;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// How the browser was obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchMode {
+    /// Launched and owned by this server.
+    Local,
+    /// An existing browser reached through `--cdp-url`.
+    CdpUrl,
+}
+
+/// Product and protocol version reported by the browser.
+#[derive(Debug, Clone, Serialize)]
+pub struct BrowserVersion {
+    pub product: String,
+    pub protocol_version: String,
+    pub user_agent: String,
+}
+
+/// One browser, renderer, GPU or utility process.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessUsage {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub pid: i64,
+    /// CPU time used since the process started.
+    pub cpu_time_secs: f64,
+    /// Resident memory, when it can be read on this host.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_bytes: Option<u64>,
+}
+
+/// Performance counters of one open page.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PageMetrics {
+    pub index: usize,
+    pub url: String,
+    pub js_heap_used_bytes: u64,
+    pub js_heap_total_bytes: u64,
+    pub dom_nodes: u64,
+    pub documents: u64,
+    pub frames: u64,
+    /// Main-thread time spent on tasks since the page was created.
+    pub task_duration_secs: f64,
+}
+
+impl PageMetrics {
+    pub fn from_metrics(index: usize, url: String, metrics: &[Metric]) -> Self {
+        let mut page = Self {
+            index,
+            url,
+            ..Default::default()
+        };
+        for metric in metrics {
+            let count = metric.value.max(0.0) as u64;
+            match metric.name.as_str() {
+                "JSHeapUsedSize" => page.js_heap_used_bytes = count,
+                "JSHeapTotalSize" => page.js_heap_total_bytes = count,
+                "Nodes" => page.dom_nodes = count,
+                "Documents" => page.documents = count,
+                "Frames" => page.frames = count,
+                "TaskDuration" => page.task_duration_secs = metric.value,
+                _ => {}
+            }
+        }
+        page
+    }
+}
+
+/// Occupancy of the `execute_code` worker pool.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerPoolStatus {
+    pub size: usize,
+    pub busy: usize,
+    /// Runs waiting for a free worker.
+    pub queued: usize,
+}
+
+/// Snapshot returned by the `browser_status` tool.
+#[derive(Debug, Clone, Serialize)]
+pub struct BrowserStatus {
+    pub running: bool,
+    pub mode: LaunchMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cdp_url: Option<String>,
+    pub executable: Option<String>,
+    pub version: Option<BrowserVersion>,
+    pub profile: Option<String>,
+    pub ephemeral: bool,
+    pub headless: bool,
+    pub launched_at: Option<DateTime<Utc>>,
+    pub uptime_secs: Option<u64>,
+    pub pages: usize,
+    #[serde(flatten)]
+    pub crash_stats: CrashStats,
+    #[serde(flatten)]
+    pub lifecycle_stats: LifecycleStats,
+    /// Time since the last tool call ended.
+    pub idle_secs: u64,
+    /// Tabs of an idle-shut-down browser, reopened by the next tool call.
+    pub parked_tabs: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_workers: Option<WorkerPoolStatus>,
+    pub processes: Vec<ProcessUsage>,
+    pub page_metrics: Vec<PageMetrics>,
+    /// Sum of `memory_bytes` over processes, if any could be read.
+    pub total_memory_bytes: Option<u64>,
+    pub total_cpu_time_secs: f64,
+    /// Parts of the status that could not be collected.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl BrowserStatus {
+    /// Fill in the totals from `processes`.
+    pub(crate) fn with_totals(mut self) -> Self {
+        self.total_cpu_time_secs = self.processes.iter().map(|p| p.cpu_time_secs).sum();
+        let memory: Vec<u64> = self
+            .processes
+            .iter()
+            .filter_map(|p| p.memory_bytes)
+            .collect();
+        self.total_memory_bytes = (!memory.is_empty()).then(|| memory.iter().sum());
+        self
+    }
+}
+
+/// Resident memory of a local process.
+#[cfg(target_os = "linux")]
+pub(crate) fn process_memory(pid: i64) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    parse_vm_rss(&status)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn process_memory(_pid: i64) -> Option<u64> {
+    None
+}
+
+/// `VmRSS` from a `/proc/<pid>/status` file, in bytes.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metric(name: &str, value: f64) -> Metric {
+        Metric {
+            name: name.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_page_metrics_from_cdp() {
+        let page = PageMetrics::from_metrics(
+            1,
+            "https://a.example/".to_string(),
+            &[
+                metric("JSHeapUsedSize", 2_000_000.0),
+                metric("JSHeapTotalSize", 4_000_000.0),
+                metric("Nodes", 1500.0),
+                metric("Documents", 3.0),
+                metric("TaskDuration", 0.75),
+                metric("LayoutCount", 12.0),
+            ],
+        );
+        assert_eq!(page.index, 1);
+        assert_eq!(page.js_heap_used_bytes, 2_000_000);
+        assert_eq!(page.js_heap_total_bytes, 4_000_000);
+        assert_eq!(page.dom_nodes, 1500);
+        assert_eq!(page.documents, 3);
+        assert_eq!(page.frames, 0);
+        assert_eq!(page.task_duration_secs, 0.75);
+    }
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tchrome\nVmPeak:\t  900000 kB\nVmRSS:\t  123456 kB\nThreads:\t20\n";
+        assert_eq!(parse_vm_rss(status), Some(123456 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tchrome\n"), None);
+    }
+
+    #[test]
+    fn test_totals() {
+        let process = |cpu: f64, memory: Option<u64>| ProcessUsage {
+            kind: "renderer".to_string(),
+            pid: 1,
+            cpu_time_secs: cpu,
+            memory_bytes: memory,
+        };
+        let status = BrowserStatus {
+            running: true,
+            mode: LaunchMode::Local,
+            cdp_url: None,
+            executable: None,
+            version: None,
+            profile: None,
+            ephemeral: false,
+            headless: true,
+            launched_at: None,
+            uptime_secs: None,
+            pages: 0,
+            crash_stats: CrashStats::default(),
+            lifecycle_stats: LifecycleStats::default(),
+            idle_secs: 0,
+            parked_tabs: 0,
+            code_workers: None,
+            processes: vec![process(1.5, Some(100)), process(0.5, None)],
+            page_metrics: Vec::new(),
+            total_memory_bytes: None,
+            total_cpu_time_secs: 0.0,
+            warnings: Vec::new(),
+        }
+        .with_totals();
+        assert_eq!(status.total_cpu_time_secs, 2.0);
+        assert_eq!(status.total_memory_bytes, Some(100));
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["mode"], "local");
+        assert_eq!(json["restarts"], 0);
+        assert_eq!(json["idle_shutdowns"], 0);
+        assert_eq!(json["processes"][0]["type"], "renderer");
+    }
+}
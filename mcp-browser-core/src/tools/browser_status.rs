@@ -0,0 +1,23 @@
+//! Report the browser's state and resource usage without launching it.
+
+use crate::browser::BrowserManager;
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct BrowserStatusInput {}
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: BrowserStatusInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    serde_json::to_value(manager.status().await).map_err(|e| Error::internal(e.to_string()))
+}
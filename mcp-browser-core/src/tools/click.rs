@@ -1,6 +1,8 @@
 //! Click an element by CSS selector.
 
+use crate::actionability;
 use crate::browser::BrowserManager;
+use crate::tools::{actionability_error, browser_error};
 use pmcp::Error;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -8,6 +10,10 @@ use serde_json::json;
 use std::sync::Arc;
 use validator::Validate;
 
+fn default_timeout_ms() -> u64 {
+    actionability::DEFAULT_TIMEOUT_MS
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
 #[schemars(deny_unknown_fields)]
 pub struct ClickInput {
@@ -15,6 +21,14 @@ pub struct ClickInput {
     #[validate(length(min = 1))]
     #[schemars(description = "CSS selector of the element to click")]
     pub selector: String,
+
+    /// Maximum time to wait for the element to become actionable
+    #[serde(default = "default_timeout_ms")]
+    #[validate(range(max = 120000))]
+    #[schemars(
+        description = "Maximum time in milliseconds to wait for the element to be attached, visible, stable, enabled and not obscured (default: 5000)"
+    )]
+    pub timeout_ms: u64,
 }
 
 pub async fn execute(
@@ -28,17 +42,21 @@ pub async fn execute(
     let page = manager
         .page()
         .await
-        .map_err(|e| Error::internal(format!("Browser error: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
 
-    let element = page
-        .find_element(&input.selector)
-        .await
-        .map_err(|e| Error::internal(format!("Element not found '{}': {}", input.selector, e)))?;
+    let element = actionability::wait_for_element(
+        &page,
+        &input.selector,
+        actionability::CLICK,
+        input.timeout_ms,
+    )
+    .await
+    .map_err(|e| actionability_error(manager, e))?;
 
     element
         .click()
         .await
-        .map_err(|e| Error::internal(format!("Click failed on '{}': {}", input.selector, e)))?;
+        .map_err(|e| browser_error(manager, &format!("Click failed on '{}'", input.selector), e))?;
 
     Ok(json!({
         "status": "clicked",
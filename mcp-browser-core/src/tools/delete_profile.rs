@@ -0,0 +1,77 @@
+//! Delete a saved browser profile and its data.
+
+use crate::browser::BrowserManager;
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct DeleteProfileInput {
+    /// Profile name
+    #[validate(length(min = 1))]
+    #[schemars(description = "Name of the profile to delete")]
+    pub name: String,
+}
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: DeleteProfileInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    if manager.current_profile().await.as_deref() == Some(input.name.as_str()) {
+        return Err(Error::validation(format!(
+            "Profile '{}' is in use by the running browser; switch to another profile first",
+            input.name
+        )));
+    }
+    let lock = manager
+        .profile_manager()
+        .lock_profile(&input.name)
+        .map_err(|e| Error::validation(e.to_string()))?;
+
+    manager
+        .profile_manager()
+        .delete_locked_profile(lock)
+        .map_err(|e| Error::internal(format!("Failed to delete profile: {}", e)))?;
+
+    Ok(json!({
+        "status": "deleted",
+        "name": input.name
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::test_manager;
+
+    fn input(name: &str) -> DeleteProfileInput {
+        DeleteProfileInput {
+            name: name.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refuses_current_and_locked_profiles() {
+        let (manager, _tmp) = test_manager(&["work", "old", "busy"], Some("work"));
+
+        assert!(execute(&manager, input("work")).await.is_err());
+        assert!(manager.profile_manager().get_profile("work").is_ok());
+
+        let lock = manager.profile_manager().lock_profile("busy").unwrap();
+        assert!(execute(&manager, input("busy")).await.is_err());
+        drop(lock);
+
+        let result = execute(&manager, input("old")).await.unwrap();
+        assert_eq!(result["status"], "deleted");
+        assert!(manager.profile_manager().get_profile("old").is_err());
+        assert!(!manager.profile_manager().lock_path("old").exists());
+    }
+}
@@ -0,0 +1,59 @@
+//! Emulate a device, viewport, locale, timezone, geolocation or color scheme.
+
+use crate::browser::BrowserManager;
+use crate::emulation::EmulationSettings;
+use crate::tools::browser_error;
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct EmulateInput {
+    /// Settings to apply; unset fields keep their current value
+    #[serde(flatten)]
+    pub settings: EmulationSettings,
+
+    /// Clear all existing overrides before applying
+    #[serde(default)]
+    #[schemars(
+        description = "Clear all existing emulation overrides first (default: false). With no other fields, restores browser defaults."
+    )]
+    pub reset: bool,
+}
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: EmulateInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    for (name, value) in [
+        ("width", input.settings.width),
+        ("height", input.settings.height),
+    ] {
+        if let Some(value) = value {
+            if !(1..=10000).contains(&value) {
+                return Err(Error::validation(format!(
+                    "Viewport {} {} out of range (1-10000)",
+                    name, value
+                )));
+            }
+        }
+    }
+
+    let resolved = manager
+        .set_emulation(input.settings, input.reset)
+        .await
+        .map_err(|e| browser_error(manager, "Emulation failed", e))?;
+
+    Ok(json!({
+        "status": "applied",
+        "emulation": resolved
+    }))
+}
@@ -0,0 +1,40 @@
+//! Emulate network conditions: offline, throttling and cache control.
+
+use crate::browser::BrowserManager;
+use crate::network::NetworkConditionsUpdate;
+use crate::tools::browser_error;
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct EmulateNetworkInput {
+    /// Conditions to apply
+    #[serde(flatten)]
+    pub conditions: NetworkConditionsUpdate,
+}
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: EmulateNetworkInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    let conditions = manager
+        .set_network_conditions(&input.conditions)
+        .await
+        .map_err(|e| browser_error(manager, "Network emulation failed", e))?;
+
+    Ok(json!({
+        "status": "applied",
+        "offline": conditions.throttle.is_some_and(|t| t.offline),
+        "throttle": conditions.throttle,
+        "cache_disabled": conditions.cache_disabled
+    }))
+}
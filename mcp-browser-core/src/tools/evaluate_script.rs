@@ -1,6 +1,8 @@
 //! Execute arbitrary JavaScript in the browser page.
 
 use crate::browser::BrowserManager;
+use crate::limits;
+use crate::tools::browser_error;
 use pmcp::Error;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -30,18 +32,29 @@ pub async fn execute(
     let page = manager
         .page()
         .await
-        .map_err(|e| Error::internal(format!("Browser error: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
 
     let result = page
         .evaluate_expression(&input.expression)
         .await
-        .map_err(|e| Error::internal(format!("Script evaluation failed: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Script evaluation failed", e))?;
 
     // Try to extract the result as a JSON value
     match result.into_value::<serde_json::Value>() {
-        Ok(value) => Ok(json!({
-            "result": value
-        })),
+        Ok(value) => {
+            // Past the content limit the result is returned as cut JSON text.
+            let mut text = value.to_string();
+            match limits::truncate(&mut text, manager.limits().max_content_bytes) {
+                Some(total_bytes) => Ok(json!({
+                    "result": text,
+                    "truncated": true,
+                    "total_bytes": total_bytes
+                })),
+                None => Ok(json!({
+                    "result": value
+                })),
+            }
+        }
         Err(_) => {
             // If we can't deserialize to Value, return null
             Ok(json!({
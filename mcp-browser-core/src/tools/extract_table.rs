@@ -1,12 +1,20 @@
 //! Extract an HTML table as JSON.
 
+use crate::actionability;
 use crate::browser::BrowserManager;
+use crate::limits;
+use crate::tools::{actionability_error, browser_error};
 use pmcp::Error;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::sync::Arc;
 use validator::Validate;
 
+fn default_timeout_ms() -> u64 {
+    actionability::DEFAULT_TIMEOUT_MS
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
 #[schemars(deny_unknown_fields)]
 pub struct ExtractTableInput {
@@ -14,6 +22,14 @@ pub struct ExtractTableInput {
     #[validate(length(min = 1))]
     #[schemars(description = "CSS selector of the <table> element to extract")]
     pub selector: String,
+
+    /// Maximum time to wait for the table to be attached
+    #[serde(default = "default_timeout_ms")]
+    #[validate(range(max = 120000))]
+    #[schemars(
+        description = "Maximum time in milliseconds to wait for the table to be attached (default: 5000)"
+    )]
+    pub timeout_ms: u64,
 }
 
 /// JavaScript that extracts a table into an array of row objects.
@@ -57,7 +73,16 @@ pub async fn execute(
     let page = manager
         .page()
         .await
-        .map_err(|e| Error::internal(format!("Browser error: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
+
+    actionability::wait_for_element(
+        &page,
+        &input.selector,
+        actionability::ATTACHED,
+        input.timeout_ms,
+    )
+    .await
+    .map_err(|e| actionability_error(manager, e))?;
 
     let js = format!(
         "({})({})",
@@ -68,12 +93,20 @@ pub async fn execute(
     let result: String = page
         .evaluate_expression(js)
         .await
-        .map_err(|e| Error::internal(format!("Table extraction failed: {}", e)))?
+        .map_err(|e| browser_error(manager, "Table extraction failed", e))?
         .into_value()
         .map_err(|e| Error::internal(format!("Failed to parse JS result: {:?}", e)))?;
 
-    let parsed: serde_json::Value = serde_json::from_str(&result)
+    let mut parsed: serde_json::Value = serde_json::from_str(&result)
         .map_err(|e| Error::internal(format!("Failed to parse table JSON: {}", e)))?;
 
+    if let Some(rows) = parsed.get_mut("rows").and_then(|rows| rows.as_array_mut()) {
+        let total_rows = rows.len();
+        if limits::truncate_values(rows, manager.limits().max_content_bytes) {
+            parsed["truncated"] = json!(true);
+            parsed["total_rows"] = json!(total_rows);
+        }
+    }
+
     Ok(parsed)
 }
@@ -1,6 +1,8 @@
 //! Fill a form field.
 
+use crate::actionability;
 use crate::browser::BrowserManager;
+use crate::tools::{actionability_error, browser_error};
 use pmcp::Error;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -8,6 +10,10 @@ use serde_json::json;
 use std::sync::Arc;
 use validator::Validate;
 
+fn default_timeout_ms() -> u64 {
+    actionability::DEFAULT_TIMEOUT_MS
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
 #[schemars(deny_unknown_fields)]
 pub struct FillInput {
@@ -19,6 +25,14 @@ pub struct FillInput {
     /// Value to type into the field
     #[schemars(description = "Text value to type into the field")]
     pub value: String,
+
+    /// Maximum time to wait for the element to become actionable
+    #[serde(default = "default_timeout_ms")]
+    #[validate(range(max = 120000))]
+    #[schemars(
+        description = "Maximum time in milliseconds to wait for the element to be attached, visible, stable, enabled, editable and not obscured (default: 5000)"
+    )]
+    pub timeout_ms: u64,
 }
 
 pub async fn execute(
@@ -32,23 +46,30 @@ pub async fn execute(
     let page = manager
         .page()
         .await
-        .map_err(|e| Error::internal(format!("Browser error: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
 
-    let element = page
-        .find_element(&input.selector)
-        .await
-        .map_err(|e| Error::internal(format!("Element not found '{}': {}", input.selector, e)))?;
+    let element = actionability::wait_for_element(
+        &page,
+        &input.selector,
+        actionability::FILL,
+        input.timeout_ms,
+    )
+    .await
+    .map_err(|e| actionability_error(manager, e))?;
 
     // Click to focus first, then type
     element
         .click()
         .await
-        .map_err(|e| Error::internal(format!("Failed to focus '{}': {}", input.selector, e)))?;
+        .map_err(|e| browser_error(manager, &format!("Failed to focus '{}'", input.selector), e))?;
 
-    element
-        .type_str(&input.value)
-        .await
-        .map_err(|e| Error::internal(format!("Failed to type into '{}': {}", input.selector, e)))?;
+    element.type_str(&input.value).await.map_err(|e| {
+        browser_error(
+            manager,
+            &format!("Failed to type into '{}'", input.selector),
+            e,
+        )
+    })?;
 
     Ok(json!({
         "status": "filled",
@@ -0,0 +1,37 @@
+//! Report the JavaScript dialog open on the active page, if any.
+
+use crate::browser::BrowserManager;
+use crate::tools::browser_error;
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct GetPendingDialogInput {}
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: GetPendingDialogInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    let page = manager
+        .page()
+        .await
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
+
+    let pending = manager.dialogs().pending(page.target_id());
+    let last_auto_handled = manager.dialogs().last_auto_handled(page.target_id());
+
+    Ok(json!({
+        "pending": pending.is_some(),
+        "dialog": pending,
+        "last_auto_handled": last_auto_handled
+    }))
+}
@@ -0,0 +1,71 @@
+//! Describe a saved browser profile and its login state.
+
+use crate::browser::BrowserManager;
+use crate::profile::ProfileMetadata;
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct GetProfileInput {
+    /// Profile name
+    #[validate(length(min = 1))]
+    #[schemars(description = "Name of the profile to describe")]
+    pub name: String,
+}
+
+/// Summarize a profile for tool output. Network settings are reduced to a
+/// flag so proxy and HTTP credentials are never returned.
+pub(crate) fn profile_summary(
+    manager: &BrowserManager,
+    profile: &ProfileMetadata,
+    active: Option<&str>,
+) -> serde_json::Value {
+    let mut summary = json!(manager.profile_manager().summarize(profile));
+    summary["active"] = json!(active == Some(profile.name.as_str()));
+    summary
+}
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: GetProfileInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    let profile = manager
+        .profile_manager()
+        .get_profile(&input.name)
+        .map_err(|e| Error::validation(e.to_string()))?;
+    let active = manager.current_profile().await;
+
+    Ok(profile_summary(manager, &profile, active.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::test_manager;
+
+    fn input(name: &str) -> GetProfileInput {
+        GetProfileInput {
+            name: name.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_profile() {
+        let (manager, _tmp) = test_manager(&["work"], None);
+        let result = execute(&manager, input("work")).await.unwrap();
+        assert_eq!(result["name"], "work");
+        assert_eq!(result["active"], false);
+        assert_eq!(result["has_network_settings"], false);
+
+        assert!(execute(&manager, input("missing")).await.is_err());
+    }
+}
@@ -1,6 +1,9 @@
 //! Get text content of an element.
 
+use crate::actionability;
 use crate::browser::BrowserManager;
+use crate::limits;
+use crate::tools::{actionability_error, browser_error};
 use pmcp::Error;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -8,6 +11,10 @@ use serde_json::json;
 use std::sync::Arc;
 use validator::Validate;
 
+fn default_timeout_ms() -> u64 {
+    actionability::DEFAULT_TIMEOUT_MS
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
 #[schemars(deny_unknown_fields)]
 pub struct GetTextInput {
@@ -15,6 +22,21 @@ pub struct GetTextInput {
     #[validate(length(min = 1))]
     #[schemars(description = "CSS selector of the element to get text from")]
     pub selector: String,
+
+    /// Return text for every matching element instead of the first
+    #[serde(default)]
+    #[schemars(
+        description = "If true, return the text of every element matching the selector as 'texts' (default: false)"
+    )]
+    pub all: bool,
+
+    /// Maximum time to wait for the element to become actionable
+    #[serde(default = "default_timeout_ms")]
+    #[validate(range(max = 120000))]
+    #[schemars(
+        description = "Maximum time in milliseconds to wait for the element to be attached (default: 5000). Not used with 'all'."
+    )]
+    pub timeout_ms: u64,
 }
 
 pub async fn execute(
@@ -28,26 +50,69 @@ pub async fn execute(
     let page = manager
         .page()
         .await
-        .map_err(|e| Error::internal(format!("Browser error: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
 
-    let element = page
-        .find_element(&input.selector)
-        .await
-        .map_err(|e| Error::internal(format!("Element not found '{}': {}", input.selector, e)))?;
+    // Every match is read as is; no match is an empty list, not a timeout.
+    if input.all {
+        let js = format!(
+            "Array.from(document.querySelectorAll({})).map((el) => el.innerText ?? el.textContent ?? '')",
+            serde_json::to_string(&input.selector).unwrap()
+        );
+        let mut texts: Vec<String> = page
+            .evaluate_expression(js)
+            .await
+            .map_err(|e| {
+                browser_error(
+                    manager,
+                    &format!("Failed to get text from '{}'", input.selector),
+                    e,
+                )
+            })?
+            .into_value()
+            .map_err(|e| Error::internal(format!("Failed to parse JS result: {:?}", e)))?;
+
+        let count = texts.len();
+        let truncated = limits::truncate_all(&mut texts, manager.limits().max_content_bytes);
+        let mut result = json!({
+            "texts": texts,
+            "count": count,
+            "selector": input.selector
+        });
+        if truncated {
+            result["truncated"] = json!(true);
+        }
+        return Ok(result);
+    }
+
+    let element = actionability::wait_for_element(
+        &page,
+        &input.selector,
+        actionability::ATTACHED,
+        input.timeout_ms,
+    )
+    .await
+    .map_err(|e| actionability_error(manager, e))?;
 
-    let text = element
+    let mut text = element
         .inner_text()
         .await
         .map_err(|e| {
-            Error::internal(format!(
-                "Failed to get text from '{}': {}",
-                input.selector, e
-            ))
+            browser_error(
+                manager,
+                &format!("Failed to get text from '{}'", input.selector),
+                e,
+            )
         })?
         .unwrap_or_default();
 
-    Ok(json!({
+    let total_bytes = limits::truncate(&mut text, manager.limits().max_content_bytes);
+    let mut result = json!({
         "text": text,
         "selector": input.selector
-    }))
+    });
+    if let Some(total_bytes) = total_bytes {
+        result["truncated"] = json!(true);
+        result["total_bytes"] = json!(total_bytes);
+    }
+    Ok(result)
 }
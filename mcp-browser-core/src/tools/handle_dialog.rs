@@ -2,9 +2,11 @@
 //!
 //! When a JS dialog (alert/confirm/prompt) appears, it blocks the page.
 //! This tool sends the CDP `Page.handleJavaScriptDialog` command to
-//! accept or dismiss the dialog, unblocking the page.
+//! accept or dismiss the dialog, unblocking the page. The dialog recorded by
+//! the page's dialog listener (type, message) is returned alongside.
 
 use crate::browser::BrowserManager;
+use crate::tools::browser_error;
 use chromiumoxide::cdp::browser_protocol::page::HandleJavaScriptDialogParams;
 use pmcp::Error;
 use schemars::JsonSchema;
@@ -38,7 +40,9 @@ pub async fn execute(
     let page = manager
         .page()
         .await
-        .map_err(|e| Error::internal(format!("Browser error: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
+
+    let dialog = manager.dialogs().pending(page.target_id());
 
     let mut params = HandleJavaScriptDialogParams::new(input.accept);
     if let Some(ref text) = input.prompt_text {
@@ -46,11 +50,13 @@ pub async fn execute(
     }
 
     page.execute(params).await.map_err(|e| {
-        Error::internal(format!(
-            "Failed to handle dialog (is there an active dialog?): {}",
-            e
-        ))
+        browser_error(
+            manager,
+            "Failed to handle dialog (is there an active dialog?)",
+            e,
+        )
     })?;
+    manager.dialogs().take_pending(page.target_id());
 
     let action = if input.accept {
         "accepted"
@@ -60,6 +66,7 @@ pub async fn execute(
 
     Ok(json!({
         "status": action,
-        "prompt_text": input.prompt_text
+        "prompt_text": input.prompt_text,
+        "dialog": dialog
     }))
 }
@@ -0,0 +1,135 @@
+//! Inspect an element: tag, attributes, state, geometry, styles and HTML.
+
+use crate::actionability;
+use crate::browser::BrowserManager;
+use crate::tools::{actionability_error, browser_error};
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use validator::Validate;
+
+fn default_timeout_ms() -> u64 {
+    actionability::DEFAULT_TIMEOUT_MS
+}
+
+pub(crate) fn default_max_html_length() -> usize {
+    2000
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct InspectElementInput {
+    /// CSS selector of the element to inspect
+    #[validate(length(min = 1))]
+    #[schemars(description = "CSS selector of the element to inspect")]
+    pub selector: String,
+
+    /// Computed style properties to include
+    #[serde(default)]
+    #[schemars(
+        description = "Computed CSS properties to return, e.g. ['display', 'color', 'font-size'] (optional)"
+    )]
+    pub styles: Vec<String>,
+
+    /// Maximum length of the returned outer HTML
+    #[serde(default = "default_max_html_length")]
+    #[validate(range(max = 100000))]
+    #[schemars(
+        description = "Maximum number of characters of outer HTML to return (default: 2000)"
+    )]
+    pub max_html_length: usize,
+
+    /// Maximum time to wait for the element to be attached
+    #[serde(default = "default_timeout_ms")]
+    #[validate(range(max = 120000))]
+    #[schemars(
+        description = "Maximum time in milliseconds to wait for the element to be attached (default: 5000)"
+    )]
+    pub timeout_ms: u64,
+}
+
+/// JavaScript that summarizes one element. Shared with `query_all`.
+pub(crate) const DESCRIBE_ELEMENT_JS: &str = r#"
+(el, styles, maxHtml) => {
+    const attributes = {};
+    for (const attr of el.attributes) attributes[attr.name] = attr.value;
+
+    const rect = el.getBoundingClientRect();
+    const computed = getComputedStyle(el);
+    const style = {};
+    for (const prop of styles) style[prop] = computed.getPropertyValue(prop);
+
+    const visible = computed.visibility !== 'hidden'
+        && computed.visibility !== 'collapse'
+        && rect.width > 0 && rect.height > 0;
+    const checkable = el instanceof HTMLInputElement && (el.type === 'checkbox' || el.type === 'radio');
+    const disabled = el.disabled === true
+        || el.getAttribute('aria-disabled') === 'true'
+        || !!(el.closest && el.closest('fieldset[disabled]'));
+    const html = el.outerHTML;
+    const text = (el.innerText || el.textContent || '').trim();
+
+    return {
+        tag: el.tagName.toLowerCase(),
+        attributes: attributes,
+        text: text.length > 500 ? text.slice(0, 500) + '…' : text,
+        value: 'value' in el && typeof el.value === 'string' ? el.value : null,
+        checked: checkable ? el.checked : null,
+        disabled: disabled,
+        visible: visible,
+        bounding_box: { x: rect.x, y: rect.y, width: rect.width, height: rect.height },
+        styles: style,
+        outer_html: html.slice(0, maxHtml),
+        outer_html_truncated: html.length > maxHtml,
+    };
+}
+"#;
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: InspectElementInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    let page = manager
+        .page()
+        .await
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
+
+    actionability::wait_for_element(
+        &page,
+        &input.selector,
+        actionability::ATTACHED,
+        input.timeout_ms,
+    )
+    .await
+    .map_err(|e| actionability_error(manager, e))?;
+
+    let js = format!(
+        "(() => {{ const el = document.querySelector({}); return el ? ({})(el, {}, {}) : null; }})()",
+        serde_json::to_string(&input.selector).unwrap(),
+        DESCRIBE_ELEMENT_JS,
+        serde_json::to_string(&input.styles).unwrap(),
+        input.max_html_length
+    );
+
+    let mut summary: serde_json::Value = page
+        .evaluate_expression(js)
+        .await
+        .map_err(|e| browser_error(manager, "Inspection failed", e))?
+        .into_value()
+        .map_err(|e| Error::internal(format!("Failed to parse JS result: {:?}", e)))?;
+
+    if summary.is_null() {
+        return Err(Error::internal(format!(
+            "Element not found '{}'",
+            input.selector
+        )));
+    }
+
+    summary["selector"] = serde_json::Value::String(input.selector);
+    Ok(summary)
+}
@@ -0,0 +1,147 @@
+//! Describe the forms on the current page and their fields.
+
+use crate::browser::BrowserManager;
+use crate::limits;
+use crate::tools::browser_error;
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct ListFormsInput {
+    /// CSS selector restricting which forms are described
+    #[schemars(
+        description = "CSS selector of the form(s) to describe (default: all <form> elements)"
+    )]
+    pub selector: Option<String>,
+}
+
+/// JavaScript that describes each form and its fields. Password values are
+/// never returned; `value` is null and `has_value` reports whether one is set.
+const LIST_FORMS_JS: &str = r#"
+(selector) => {
+    const labelFor = (el) => {
+        if (el.labels && el.labels.length) return el.labels[0].innerText.trim();
+        const aria = el.getAttribute('aria-label');
+        if (aria) return aria.trim();
+        const labelledBy = el.getAttribute('aria-labelledby');
+        if (labelledBy) {
+            const text = labelledBy.split(/\s+/)
+                .map((id) => document.getElementById(id))
+                .filter(Boolean)
+                .map((node) => node.innerText.trim())
+                .join(' ');
+            if (text) return text;
+        }
+        return el.getAttribute('placeholder') || null;
+    };
+
+    const describeField = (el) => {
+        const tag = el.tagName.toLowerCase();
+        const type = tag === 'input' ? (el.type || 'text') : tag;
+        const field = {
+            tag: tag,
+            type: type,
+            name: el.name || null,
+            id: el.id || null,
+            label: labelFor(el),
+            required: el.required === true,
+            disabled: el.disabled === true,
+        };
+        if (type === 'password') {
+            field.value = null;
+            field.has_value = el.value.length > 0;
+        } else if (type === 'checkbox' || type === 'radio') {
+            field.value = el.value;
+            field.checked = el.checked;
+        } else if (tag === 'select') {
+            field.value = el.multiple
+                ? Array.from(el.selectedOptions).map((o) => o.value)
+                : el.value;
+            field.multiple = el.multiple;
+            field.options = Array.from(el.options).map((o) => ({
+                value: o.value,
+                text: o.text.trim(),
+                selected: o.selected,
+            }));
+        } else {
+            field.value = el.value;
+        }
+        return field;
+    };
+
+    // A control named "id", "action" or "elements" shadows the form's own
+    // property, so read attributes and the prototype getter instead.
+    const formElements = Object.getOwnPropertyDescriptor(HTMLFormElement.prototype, 'elements').get;
+    const actionOf = (form) => {
+        const action = form.getAttribute('action');
+        if (!action) return document.URL;
+        try {
+            return new URL(action, document.baseURI).href;
+        } catch (e) {
+            return action;
+        }
+    };
+
+    const forms = Array.from(document.querySelectorAll(selector || 'form'));
+    return forms.map((form, index) => {
+        const isForm = form instanceof HTMLFormElement;
+        const controls = isForm
+            ? Array.from(formElements.call(form))
+            : Array.from(form.querySelectorAll('input, select, textarea, button'));
+        return {
+            index: index,
+            id: form.getAttribute('id') || null,
+            name: form.getAttribute('name'),
+            action: isForm ? actionOf(form) : null,
+            method: (form.getAttribute('method') || 'get').toLowerCase(),
+            fields: controls
+                .filter((el) => el.tagName !== 'FIELDSET' && el.tagName !== 'OBJECT' && el.tagName !== 'OUTPUT')
+                .map(describeField),
+        };
+    });
+}
+"#;
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: ListFormsInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    let page = manager
+        .page()
+        .await
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
+
+    let js = format!(
+        "({})({})",
+        LIST_FORMS_JS,
+        serde_json::to_string(&input.selector).unwrap()
+    );
+
+    let mut forms: Vec<serde_json::Value> = page
+        .evaluate_expression(js)
+        .await
+        .map_err(|e| browser_error(manager, "Form discovery failed", e))?
+        .into_value()
+        .map_err(|e| Error::internal(format!("Failed to parse JS result: {:?}", e)))?;
+
+    let total = forms.len();
+    let truncated = limits::truncate_values(&mut forms, manager.limits().max_content_bytes);
+    let mut result = json!({
+        "forms": forms,
+        "count": forms.len()
+    });
+    if truncated {
+        result["truncated"] = json!(true);
+        result["total"] = json!(total);
+    }
+    Ok(result)
+}
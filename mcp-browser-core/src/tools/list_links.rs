@@ -0,0 +1,169 @@
+//! List the links on the current page.
+
+use crate::browser::BrowserManager;
+use crate::glob::url_matches;
+use crate::tools::browser_error;
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
+use validator::Validate;
+
+fn default_limit() -> usize {
+    200
+}
+
+fn default_dedup() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct ListLinksInput {
+    /// Only return links whose href matches this pattern
+    #[schemars(
+        description = "Only return links whose absolute href matches this pattern. '*' matches any characters; without '*' the pattern is matched as a substring. (optional)"
+    )]
+    pub pattern: Option<String>,
+
+    /// Only return links on the page's own origin
+    #[serde(default)]
+    #[schemars(description = "Only return same-origin links (default: false)")]
+    pub same_origin_only: bool,
+
+    /// Collapse links with the same href
+    #[serde(default = "default_dedup")]
+    #[schemars(description = "Collapse links with the same href into one entry (default: true)")]
+    pub dedup: bool,
+
+    /// Maximum number of links to return
+    #[serde(default = "default_limit")]
+    #[validate(range(min = 1, max = 5000))]
+    #[schemars(description = "Maximum number of links to return (default: 200)")]
+    pub limit: usize,
+}
+
+/// A link found on the page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkInfo {
+    pub href: String,
+    pub text: String,
+    pub rel: Option<String>,
+    pub same_origin: bool,
+}
+
+/// JavaScript that collects every `a[href]` and `area[href]` with its
+/// resolved absolute URL. `javascript:` links are skipped.
+const LIST_LINKS_JS: &str = r#"
+(() => Array.from(document.querySelectorAll('a[href], area[href]'))
+    .filter((el) => el.href && !el.href.startsWith('javascript:'))
+    .map((el) => {
+        let sameOrigin = false;
+        try { sameOrigin = new URL(el.href).origin === location.origin; } catch (e) {}
+        const text = (el.innerText || el.getAttribute('aria-label') || el.title || el.alt || '').trim();
+        return {
+            href: el.href,
+            text: text.replace(/\s+/g, ' ').slice(0, 200),
+            rel: el.getAttribute('rel'),
+            same_origin: sameOrigin,
+        };
+    }))()
+"#;
+
+/// Apply origin, pattern and dedup filters, keeping document order.
+fn filter_links(links: Vec<LinkInfo>, input: &ListLinksInput) -> Vec<LinkInfo> {
+    let mut seen = HashSet::new();
+    links
+        .into_iter()
+        .filter(|link| !input.same_origin_only || link.same_origin)
+        .filter(|link| {
+            input
+                .pattern
+                .as_deref()
+                .is_none_or(|pattern| url_matches(pattern, &link.href))
+        })
+        .filter(|link| !input.dedup || seen.insert(link.href.clone()))
+        .collect()
+}
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: ListLinksInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    let page = manager
+        .page()
+        .await
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
+
+    let links: Vec<LinkInfo> = page
+        .evaluate_expression(LIST_LINKS_JS)
+        .await
+        .map_err(|e| browser_error(manager, "Link discovery failed", e))?
+        .into_value()
+        .map_err(|e| Error::internal(format!("Failed to parse JS result: {:?}", e)))?;
+
+    let mut links = filter_links(links, &input);
+    let total = links.len();
+    links.truncate(input.limit);
+
+    Ok(json!({
+        "links": links,
+        "count": links.len(),
+        "total": total,
+        "truncated": total > links.len()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(href: &str, same_origin: bool) -> LinkInfo {
+        LinkInfo {
+            href: href.to_string(),
+            text: String::new(),
+            rel: None,
+            same_origin,
+        }
+    }
+
+    fn input(value: serde_json::Value) -> ListLinksInput {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_filter_links_dedup_and_origin() {
+        let links = vec![
+            link("https://a.test/x", true),
+            link("https://a.test/x", true),
+            link("https://b.test/y", false),
+        ];
+
+        let all = filter_links(links.clone(), &input(json!({})));
+        assert_eq!(all.len(), 2);
+
+        let no_dedup = filter_links(links.clone(), &input(json!({ "dedup": false })));
+        assert_eq!(no_dedup.len(), 3);
+
+        let same = filter_links(links, &input(json!({ "same_origin_only": true })));
+        assert_eq!(same.len(), 1);
+        assert_eq!(same[0].href, "https://a.test/x");
+    }
+
+    #[test]
+    fn test_filter_links_pattern() {
+        let links = vec![
+            link("https://a.test/products/1", true),
+            link("https://a.test/about", true),
+        ];
+        let filtered = filter_links(links, &input(json!({ "pattern": "*/products/*" })));
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].href, "https://a.test/products/1");
+    }
+}
@@ -1,6 +1,7 @@
 //! List all open browser pages (tabs).
 
 use crate::browser::BrowserManager;
+use crate::tools::browser_error;
 use pmcp::Error;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -23,7 +24,7 @@ pub async fn execute(
     let pages = manager
         .list_pages_info()
         .await
-        .map_err(|e| Error::internal(format!("Failed to list pages: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Failed to list pages", e))?;
 
     Ok(json!({
         "pages": pages,
@@ -0,0 +1,60 @@
+//! List saved browser profiles.
+
+use crate::browser::BrowserManager;
+use crate::tools::get_profile::profile_summary;
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct ListProfilesInput {}
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: ListProfilesInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    let mut profiles = manager
+        .profile_manager()
+        .list_profiles()
+        .map_err(|e| Error::internal(format!("Failed to list profiles: {}", e)))?;
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let active = manager.current_profile().await;
+    let summaries: Vec<serde_json::Value> = profiles
+        .iter()
+        .map(|p| profile_summary(manager, p, active.as_deref()))
+        .collect();
+
+    Ok(json!({
+        "profiles": summaries,
+        "count": summaries.len(),
+        "active": active
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::test_manager;
+
+    #[tokio::test]
+    async fn test_lists_profiles_sorted_with_active() {
+        let (manager, _tmp) = test_manager(&["work", "personal"], Some("work"));
+        let result = execute(&manager, ListProfilesInput {}).await.unwrap();
+
+        assert_eq!(result["count"], 2);
+        assert_eq!(result["active"], "work");
+        assert_eq!(result["profiles"][0]["name"], "personal");
+        assert_eq!(result["profiles"][0]["active"], false);
+        assert_eq!(result["profiles"][1]["name"], "work");
+        assert_eq!(result["profiles"][1]["active"], true);
+    }
+}
@@ -1,24 +1,99 @@
 //! Tool registration for all browser automation tools.
 
+pub mod browser_status;
 pub mod click;
+pub mod delete_profile;
+pub mod emulate;
+pub mod emulate_network;
 pub mod evaluate_script;
 pub mod extract_table;
 pub mod fill;
+pub mod get_pending_dialog;
+pub mod get_profile;
 pub mod get_text;
 pub mod handle_dialog;
 pub mod hover;
+pub mod inspect_element;
+pub mod list_forms;
+pub mod list_links;
 pub mod list_pages;
+pub mod list_profiles;
 pub mod navigate;
 pub mod press_key;
+pub mod query_all;
+pub mod request_human_login;
 pub mod screenshot;
 pub mod select_page;
+pub mod switch_profile;
+pub mod verify_profile;
 pub mod wait;
 
+use crate::actionability::ActionabilityError;
 use crate::browser::BrowserManager;
+use crate::limits::LimitExceeded;
+use chromiumoxide::error::CdpError;
 use pmcp::TypedTool;
+use std::future::Future;
 use std::sync::Arc;
 use validator::Validate;
 
+/// Add a crash recovery no response has reported yet to a tool result, so
+/// the caller learns the browser restarted and its tabs were reopened.
+fn report_recovery(
+    manager: &BrowserManager,
+    result: Result<serde_json::Value, pmcp::Error>,
+) -> Result<serde_json::Value, pmcp::Error> {
+    let mut value = result?;
+    if let Some(object) = value.as_object_mut() {
+        if let Some(report) = manager.take_recovery() {
+            object.insert("browser_recovered".to_string(), serde_json::json!(report));
+        }
+    }
+    Ok(value)
+}
+
+/// A failed browser call as a tool error, described by `context`.
+pub(crate) fn browser_error(
+    manager: &BrowserManager,
+    context: &str,
+    error: impl Into<anyhow::Error>,
+) -> pmcp::Error {
+    let error = error.into();
+    limit_error(manager, &error)
+        .unwrap_or_else(|| pmcp::Error::internal(format!("{}: {:#}", context, error)))
+}
+
+/// An element that never became actionable as a tool error.
+pub(crate) fn actionability_error(
+    manager: &BrowserManager,
+    error: ActionabilityError,
+) -> pmcp::Error {
+    let message = error.to_string();
+    limit_error(manager, &error.into()).unwrap_or_else(|| pmcp::Error::internal(message))
+}
+
+/// Limit errors keep their code and data, and a CDP call that ran into
+/// `limits.cdp_timeout` is reported as that limit.
+fn limit_error(manager: &BrowserManager, error: &anyhow::Error) -> Option<pmcp::Error> {
+    if let Some(limit) = error.downcast_ref::<LimitExceeded>() {
+        return Some(limit.clone().into());
+    }
+    error
+        .chain()
+        .any(|cause| matches!(cause.downcast_ref::<CdpError>(), Some(CdpError::Timeout)))
+        .then(|| LimitExceeded::cdp_timeout(manager.limits().cdp_timeout).into())
+}
+
+/// Run a tool call as browser activity, so an idle shutdown never cuts it
+/// off, and report any crash recovery in its result.
+pub(crate) async fn run_tool(
+    manager: &BrowserManager,
+    call: impl Future<Output = Result<serde_json::Value, pmcp::Error>>,
+) -> Result<serde_json::Value, pmcp::Error> {
+    let _activity = manager.begin_activity().await;
+    report_recovery(manager, call.await)
+}
+
 /// Register all browser tools onto the server builder.
 ///
 /// Each tool captures an `Arc<BrowserManager>` for browser access.
@@ -33,11 +108,30 @@ pub fn register_tools(
         "navigate",
         TypedTool::new("navigate", move |input: navigate::NavigateInput, _extra| {
             let m = m.clone();
-            Box::pin(async move { navigate::execute(&m, input).await })
+            Box::pin(async move { run_tool(&m, navigate::execute(&m, input)).await })
         })
         .with_description("Navigate to a URL. Returns the final URL after any redirects."),
     );
 
+    let m = manager.clone();
+    let builder = builder.tool(
+        "browser_status",
+        TypedTool::new(
+            "browser_status",
+            move |input: browser_status::BrowserStatusInput, _extra| {
+                let m = m.clone();
+                Box::pin(async move { browser_status::execute(&m, input).await })
+            },
+        )
+        .with_description(
+            "Report whether the browser is running, launch mode (local or cdp_url), executable and \
+             version, profile, uptime, open pages, per-process CPU time and memory, per-page JS \
+             heap and DOM size, how often the browser crashed and was restarted, and idle \
+             shutdowns and recycles with the tabs parked for the next call. Does not launch a \
+             browser.",
+        ),
+    );
+
     let m = manager.clone();
     let builder = builder.tool(
         "list_pages",
@@ -45,7 +139,7 @@ pub fn register_tools(
             "list_pages",
             move |input: list_pages::ListPagesInput, _extra| {
                 let m = m.clone();
-                Box::pin(async move { list_pages::execute(&m, input).await })
+                Box::pin(async move { run_tool(&m, list_pages::execute(&m, input)).await })
             },
         )
         .with_description("List all open browser pages (tabs) with their URLs and indices."),
@@ -58,7 +152,7 @@ pub fn register_tools(
             "select_page",
             move |input: select_page::SelectPageInput, _extra| {
                 let m = m.clone();
-                Box::pin(async move { select_page::execute(&m, input).await })
+                Box::pin(async move { run_tool(&m, select_page::execute(&m, input)).await })
             },
         )
         .with_description(
@@ -71,10 +165,12 @@ pub fn register_tools(
         "wait",
         TypedTool::new("wait", move |input: wait::WaitInput, _extra| {
             let m = m.clone();
-            Box::pin(async move { wait::execute(&m, input).await })
+            Box::pin(async move { run_tool(&m, wait::execute(&m, input)).await })
         })
         .with_description(
-            "Wait for a CSS selector to appear on the page, or wait for a specified duration.",
+            "Wait for a page condition: an element state (attached, visible, hidden, detached), text within an element, \
+             the URL matching a pattern, a JavaScript predicate becoming truthy, or network idle. \
+             With no condition, waits for timeout_ms. Returns what matched and how long it took.",
         ),
     );
 
@@ -85,9 +181,11 @@ pub fn register_tools(
         "click",
         TypedTool::new("click", move |input: click::ClickInput, _extra| {
             let m = m.clone();
-            Box::pin(async move { click::execute(&m, input).await })
+            Box::pin(async move { run_tool(&m, click::execute(&m, input)).await })
         })
-        .with_description("Click an element identified by a CSS selector."),
+        .with_description(
+            "Click an element identified by a CSS selector. Waits up to timeout_ms for the element to be visible, stable, enabled and not obscured.",
+        ),
     );
 
     let m = manager.clone();
@@ -95,10 +193,10 @@ pub fn register_tools(
         "fill",
         TypedTool::new("fill", move |input: fill::FillInput, _extra| {
             let m = m.clone();
-            Box::pin(async move { fill::execute(&m, input).await })
+            Box::pin(async move { run_tool(&m, fill::execute(&m, input)).await })
         })
         .with_description(
-            "Fill a form field identified by a CSS selector with the given text value.",
+            "Fill a form field identified by a CSS selector with the given text value. Waits up to timeout_ms for the field to be visible, enabled and editable.",
         ),
     );
 
@@ -109,7 +207,7 @@ pub fn register_tools(
             "press_key",
             move |input: press_key::PressKeyInput, _extra| {
                 let m = m.clone();
-                Box::pin(async move { press_key::execute(&m, input).await })
+                Box::pin(async move { run_tool(&m, press_key::execute(&m, input)).await })
             },
         )
         .with_description(
@@ -122,10 +220,10 @@ pub fn register_tools(
         "hover",
         TypedTool::new("hover", move |input: hover::HoverInput, _extra| {
             let m = m.clone();
-            Box::pin(async move { hover::execute(&m, input).await })
+            Box::pin(async move { run_tool(&m, hover::execute(&m, input)).await })
         })
         .with_description(
-            "Hover over an element identified by a CSS selector. Triggers hover states, dropdowns, and tooltips.",
+            "Hover over an element identified by a CSS selector. Triggers hover states, dropdowns, and tooltips. Waits up to timeout_ms for the element to be visible, stable and not obscured.",
         ),
     );
 
@@ -136,7 +234,7 @@ pub fn register_tools(
             "handle_dialog",
             move |input: handle_dialog::HandleDialogInput, _extra| {
                 let m = m.clone();
-                Box::pin(async move { handle_dialog::execute(&m, input).await })
+                Box::pin(async move { run_tool(&m, handle_dialog::execute(&m, input)).await })
             },
         )
         .with_description(
@@ -144,6 +242,22 @@ pub fn register_tools(
         ),
     );
 
+    let m = manager.clone();
+    let builder = builder.tool(
+        "get_pending_dialog",
+        TypedTool::new(
+            "get_pending_dialog",
+            move |input: get_pending_dialog::GetPendingDialogInput, _extra| {
+                let m = m.clone();
+                Box::pin(async move { run_tool(&m, get_pending_dialog::execute(&m, input)).await })
+            },
+        )
+        .with_description(
+            "Report the JavaScript dialog open on the active page (type, message, default prompt), \
+             and the last dialog answered automatically by the dialog policy.",
+        ),
+    );
+
     // --- Data extraction & debugging ---
 
     let m = manager.clone();
@@ -153,7 +267,7 @@ pub fn register_tools(
             "screenshot",
             move |input: screenshot::ScreenshotInput, _extra| {
                 let m = m.clone();
-                Box::pin(async move { screenshot::execute(&m, input).await })
+                Box::pin(async move { run_tool(&m, screenshot::execute(&m, input)).await })
             },
         )
         .with_description(
@@ -168,11 +282,12 @@ pub fn register_tools(
             "extract_table",
             move |input: extract_table::ExtractTableInput, _extra| {
                 let m = m.clone();
-                Box::pin(async move { extract_table::execute(&m, input).await })
+                Box::pin(async move { run_tool(&m, extract_table::execute(&m, input)).await })
             },
         )
         .with_description(
-            "Extract an HTML table as JSON. Returns headers and rows as structured data.",
+            "Extract an HTML table as JSON. Returns headers and rows as structured data; rows past \
+             the content limit are dropped.",
         ),
     );
 
@@ -181,9 +296,76 @@ pub fn register_tools(
         "get_text",
         TypedTool::new("get_text", move |input: get_text::GetTextInput, _extra| {
             let m = m.clone();
-            Box::pin(async move { get_text::execute(&m, input).await })
+            Box::pin(async move { run_tool(&m, get_text::execute(&m, input)).await })
         })
-        .with_description("Get the text content of an element identified by a CSS selector."),
+        .with_description(
+            "Get the text content of an element identified by a CSS selector. Set all=true to get the text of every match.",
+        ),
+    );
+
+    let m = manager.clone();
+    let builder = builder.tool(
+        "inspect_element",
+        TypedTool::new(
+            "inspect_element",
+            move |input: inspect_element::InspectElementInput, _extra| {
+                let m = m.clone();
+                Box::pin(async move { run_tool(&m, inspect_element::execute(&m, input)).await })
+            },
+        )
+        .with_description(
+            "Inspect an element: tag, attributes, value, checked/disabled/visible state, bounding box, \
+             requested computed styles and (truncated) outer HTML.",
+        ),
+    );
+
+    let m = manager.clone();
+    let builder = builder.tool(
+        "query_all",
+        TypedTool::new(
+            "query_all",
+            move |input: query_all::QueryAllInput, _extra| {
+                let m = m.clone();
+                Box::pin(async move { run_tool(&m, query_all::execute(&m, input)).await })
+            },
+        )
+        .with_description(
+            "Return the same summary as inspect_element for every element matching a CSS selector, \
+             up to a limit and the content limit.",
+        ),
+    );
+
+    let m = manager.clone();
+    let builder = builder.tool(
+        "list_links",
+        TypedTool::new(
+            "list_links",
+            move |input: list_links::ListLinksInput, _extra| {
+                let m = m.clone();
+                Box::pin(async move { run_tool(&m, list_links::execute(&m, input)).await })
+            },
+        )
+        .with_description(
+            "List links on the page with absolute href, text, rel and a same-origin flag. \
+             Supports deduplication and filtering by href pattern.",
+        ),
+    );
+
+    let m = manager.clone();
+    let builder = builder.tool(
+        "list_forms",
+        TypedTool::new(
+            "list_forms",
+            move |input: list_forms::ListFormsInput, _extra| {
+                let m = m.clone();
+                Box::pin(async move { run_tool(&m, list_forms::execute(&m, input)).await })
+            },
+        )
+        .with_description(
+            "Describe the forms on the page: action, method and each field's name, type, label, \
+             required flag, current value and options for selects. Password values are never returned. \
+             Forms past the content limit are dropped.",
+        ),
     );
 
     let m = manager.clone();
@@ -193,11 +375,147 @@ pub fn register_tools(
             "evaluate_script",
             move |input: evaluate_script::EvaluateScriptInput, _extra| {
                 let m = m.clone();
-                Box::pin(async move { evaluate_script::execute(&m, input).await })
+                Box::pin(async move { run_tool(&m, evaluate_script::execute(&m, input)).await })
             },
         )
         .with_description(
-            "Execute JavaScript in the browser page context. Returns the result of the expression.",
+            "Execute JavaScript in the browser page context. Returns the result of the expression; \
+             a result past the content limit is returned as truncated JSON text.",
+        ),
+    );
+
+    // --- Profiles ---
+
+    let m = manager.clone();
+    let builder = builder.tool(
+        "list_profiles",
+        TypedTool::new(
+            "list_profiles",
+            move |input: list_profiles::ListProfilesInput, _extra| {
+                let m = m.clone();
+                Box::pin(async move { run_tool(&m, list_profiles::execute(&m, input)).await })
+            },
+        )
+        .with_description(
+            "List saved browser profiles with their login state: requires_human_login, login_notes, \
+             session_valid, has_cookies and needs_login (a human must log in again). Marks the active profile.",
+        ),
+    );
+
+    let m = manager.clone();
+    let builder = builder.tool(
+        "get_profile",
+        TypedTool::new(
+            "get_profile",
+            move |input: get_profile::GetProfileInput, _extra| {
+                let m = m.clone();
+                Box::pin(async move { run_tool(&m, get_profile::execute(&m, input)).await })
+            },
+        )
+        .with_description(
+            "Describe one saved browser profile, including login notes and whether its session is still valid.",
+        ),
+    );
+
+    let m = manager.clone();
+    let builder = builder.tool(
+        "delete_profile",
+        TypedTool::new(
+            "delete_profile",
+            move |input: delete_profile::DeleteProfileInput, _extra| {
+                let m = m.clone();
+                Box::pin(async move { run_tool(&m, delete_profile::execute(&m, input)).await })
+            },
+        )
+        .with_description(
+            "Delete a saved browser profile and its stored cookies and data. The active profile cannot be deleted.",
+        ),
+    );
+
+    let m = manager.clone();
+    let builder = builder.tool(
+        "switch_profile",
+        TypedTool::new(
+            "switch_profile",
+            move |input: switch_profile::SwitchProfileInput, _extra| {
+                let m = m.clone();
+                Box::pin(async move { run_tool(&m, switch_profile::execute(&m, input)).await })
+            },
+        )
+        .with_description(
+            "Shut down the browser and relaunch it with another saved profile (its cookies and sessions). \
+             Open pages are closed. If the profile reports needs_login, ask the user to log in again.",
+        ),
+    );
+
+    let m = manager.clone();
+    let builder = builder.tool(
+        "verify_profile",
+        TypedTool::new(
+            "verify_profile",
+            move |input: verify_profile::VerifyProfileInput, _extra| {
+                let m = m.clone();
+                Box::pin(async move { run_tool(&m, verify_profile::execute(&m, input)).await })
+            },
+        )
+        .with_description(
+            "Check whether a saved profile is still logged in by running its login check (open a URL, \
+             then look for an element, a cookie, or the absence of a login redirect) in a headless browser. \
+             The result is stored with the profile. Returns logged_in, final_url and detail.",
+        ),
+    );
+
+    let m = manager.clone();
+    let builder = builder.tool(
+        "request_human_login",
+        TypedTool::new(
+            "request_human_login",
+            move |input: request_human_login::RequestHumanLoginInput, _extra| {
+                let m = m.clone();
+                Box::pin(async move { run_tool(&m, request_human_login::execute(&m, input)).await })
+            },
+        )
+        .with_description(
+            "Ask the user to log in when automation hits a login wall (SSO, MFA, CAPTCHA). Relaunches \
+             the active profile in a visible browser window at the login page and waits until \
+             success_url/success_selector (or the profile's login check) matches, or the user closes \
+             the window, then resumes headless with the open tabs reopened and the page the user ended \
+             on active. The user is not prompted through the MCP client (elicitation is not \
+             supported), so tell them a window is opening before calling. Needs a profile and a \
+             display. Returns status: logged_in, window_closed or timed_out.",
+        ),
+    );
+
+    // --- Emulation ---
+
+    let m = manager.clone();
+    let builder = builder.tool(
+        "emulate",
+        TypedTool::new("emulate", move |input: emulate::EmulateInput, _extra| {
+            let m = m.clone();
+            Box::pin(async move { run_tool(&m, emulate::execute(&m, input)).await })
+        })
+        .with_description(
+            "Emulate a device or environment on all pages: a device preset (iphone-15, pixel-7, ipad, ...), \
+             viewport size, device scale factor, mobile/touch, user agent, locale, timezone, geolocation \
+             and prefers-color-scheme. Settings persist for new pages; pass reset=true to clear them.",
+        ),
+    );
+
+    let m = manager.clone();
+    let builder = builder.tool(
+        "emulate_network",
+        TypedTool::new(
+            "emulate_network",
+            move |input: emulate_network::EmulateNetworkInput, _extra| {
+                let m = m.clone();
+                Box::pin(async move { run_tool(&m, emulate_network::execute(&m, input)).await })
+            },
+        )
+        .with_description(
+            "Emulate network conditions on all pages: go offline, throttle with a preset ('3g', 'slow-4g', \
+             'fast-4g') or custom latency_ms/download_kbps/upload_kbps, and disable the HTTP cache. \
+             Use preset 'online' to remove throttling. Conditions persist for new pages.",
         ),
     );
 
@@ -308,26 +626,102 @@ fn register_code_mode_tools(
                         .validate()
                         .map_err(|e| pmcp::Error::validation(format!("Validation failed: {}", e)))?;
 
-                    match code_mode::execute_script(
-                        m,
+                    // Held until the script finishes, so no other run uses
+                    // the worker meanwhile.
+                    let lease = m.lease_code_worker().await;
+                    let worker = lease.manager();
+                    let execution = code_mode::execute_script(
+                        worker.clone(),
                         &input.code,
                         &input.approval_token,
                         input.variables,
-                    )
-                    .await
-                    {
-                        Ok(result) => Ok(result),
-                        Err(e) => Err(pmcp::Error::internal(e)),
+                    );
+                    let mut result =
+                        run_tool(worker, async { execution.await.map_err(pmcp::Error::internal) })
+                            .await?;
+                    if let Some(object) = result.as_object_mut() {
+                        object.insert(
+                            "queue_wait_ms".to_string(),
+                            serde_json::json!(lease.queue_wait().as_millis() as u64),
+                        );
+                        if let Some(index) = lease.worker() {
+                            object.insert("worker".to_string(), serde_json::json!(index));
+                        }
                     }
+                    Ok(result)
                 })
             },
         )
         .with_description(
             "Executes a validated browser automation script. You MUST pass the exact `normalized_code` \
              string returned by validate_code as the `code` parameter, along with the `approval_token`. \
-             Do NOT modify the code in any way — use the normalized_code verbatim.",
+             Do NOT modify the code in any way — use the normalized_code verbatim. When code \
+             workers are configured, each run gets its own browser, waiting for a free one; the \
+             result reports the worker and queue_wait_ms.",
         ),
     );
 
     builder
 }
+
+/// A manager over a temporary profiles dir with `profiles` created and
+/// `current` active. Its browser path does not exist, so launches fail.
+#[cfg(test)]
+pub(crate) fn test_manager(
+    profiles: &[&str],
+    current: Option<&str>,
+) -> (Arc<BrowserManager>, tempfile::TempDir) {
+    let tmp = tempfile::tempdir().unwrap();
+    let profile_manager =
+        crate::profile::ProfileManager::with_dir(tmp.path().to_path_buf()).unwrap();
+    for name in profiles {
+        profile_manager
+            .create_profile(name, Default::default())
+            .unwrap();
+    }
+    let config = crate::browser::BrowserManagerConfig {
+        browser_path: Some(tmp.path().join("no-browser").display().to_string()),
+        profile: current.map(str::to_string),
+        ..Default::default()
+    };
+    let manager = Arc::new(BrowserManager::new(config, Arc::new(profile_manager)));
+    (manager, tmp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limits::LIMIT_EXCEEDED;
+    use anyhow::Context;
+
+    fn code(error: &pmcp::Error) -> Option<pmcp::ErrorCode> {
+        match error {
+            pmcp::Error::Protocol { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_cdp_timeouts_are_limit_errors() {
+        let (manager, _tmp) = test_manager(&[], None);
+
+        let wrapped = Err::<(), _>(CdpError::Timeout)
+            .context("Failed to navigate")
+            .unwrap_err();
+        let error = browser_error(&manager, "Navigation failed", wrapped);
+        assert_eq!(code(&error), Some(LIMIT_EXCEEDED));
+
+        let error = actionability_error(
+            &manager,
+            ActionabilityError::Cdp {
+                selector: "#submit".to_string(),
+                source: CdpError::Timeout,
+            },
+        );
+        assert_eq!(code(&error), Some(LIMIT_EXCEEDED));
+
+        let error = browser_error(&manager, "Click failed", CdpError::NotFound);
+        assert_ne!(code(&error), Some(LIMIT_EXCEEDED));
+        assert!(error.to_string().contains("Click failed: "));
+    }
+}
@@ -1,6 +1,7 @@
 //! Navigate to a URL.
 
 use crate::browser::BrowserManager;
+use crate::tools::browser_error;
 use pmcp::Error;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -37,17 +38,17 @@ pub async fn execute(
     let page = manager
         .page()
         .await
-        .map_err(|e| Error::internal(format!("Browser error: {:#}", e)))?;
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
 
     page.goto(&input.url)
         .await
-        .map_err(|e| Error::internal(format!("Navigation failed: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Navigation failed", e))?;
 
     // Get the final URL after any redirects
     let final_url = page
         .url()
         .await
-        .map_err(|e| Error::internal(format!("Failed to get URL: {}", e)))?
+        .map_err(|e| browser_error(manager, "Failed to get URL", e))?
         .unwrap_or_default()
         .to_string();
 
@@ -1,6 +1,8 @@
 //! Press a keyboard key, optionally with modifiers.
 
+use crate::actionability;
 use crate::browser::BrowserManager;
+use crate::tools::{actionability_error, browser_error};
 use chromiumoxide::cdp::browser_protocol::input::{DispatchKeyEventParams, DispatchKeyEventType};
 use pmcp::Error;
 use schemars::JsonSchema;
@@ -9,6 +11,10 @@ use serde_json::json;
 use std::sync::Arc;
 use validator::Validate;
 
+fn default_timeout_ms() -> u64 {
+    actionability::DEFAULT_TIMEOUT_MS
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
 #[schemars(deny_unknown_fields)]
 pub struct PressKeyInput {
@@ -24,6 +30,14 @@ pub struct PressKeyInput {
         description = "CSS selector of element to focus before pressing the key (optional)"
     )]
     pub selector: Option<String>,
+
+    /// Maximum time to wait for the selector's element to become actionable
+    #[serde(default = "default_timeout_ms")]
+    #[validate(range(max = 120000))]
+    #[schemars(
+        description = "Maximum time in milliseconds to wait for the selector's element to be attached, visible, stable, enabled and not obscured (default: 5000)"
+    )]
+    pub timeout_ms: u64,
 }
 
 /// Parse modifier+key string like "Control+a" into (modifier_flags, key_name).
@@ -109,18 +123,22 @@ pub async fn execute(
     let page = manager
         .page()
         .await
-        .map_err(|e| Error::internal(format!("Browser error: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
 
     // Focus element if selector provided
     if let Some(ref selector) = input.selector {
-        let element = page
-            .find_element(selector)
-            .await
-            .map_err(|e| Error::internal(format!("Element not found '{}': {}", selector, e)))?;
+        let element = actionability::wait_for_element(
+            &page,
+            selector,
+            actionability::CLICK,
+            input.timeout_ms,
+        )
+        .await
+        .map_err(|e| actionability_error(manager, e))?;
         element
             .click()
             .await
-            .map_err(|e| Error::internal(format!("Failed to focus '{}': {}", selector, e)))?;
+            .map_err(|e| browser_error(manager, &format!("Failed to focus '{}'", selector), e))?;
     }
 
     let (modifiers, key_name) = parse_key_combo(&input.key);
@@ -142,7 +160,7 @@ pub async fn execute(
     // Dispatch keyDown
     page.execute(down)
         .await
-        .map_err(|e| Error::internal(format!("Key down failed: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Key down failed", e))?;
 
     // Build keyUp params
     let mut up = DispatchKeyEventParams::new(DispatchKeyEventType::KeyUp);
@@ -160,7 +178,7 @@ pub async fn execute(
     // Dispatch keyUp
     page.execute(up)
         .await
-        .map_err(|e| Error::internal(format!("Key up failed: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Key up failed", e))?;
 
     Ok(json!({
         "status": "pressed",
@@ -0,0 +1,99 @@
+//! Summarize every element matching a selector.
+
+use crate::browser::BrowserManager;
+use crate::limits;
+use crate::tools::browser_error;
+use crate::tools::inspect_element::{default_max_html_length, DESCRIBE_ELEMENT_JS};
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use validator::Validate;
+
+fn default_limit() -> usize {
+    50
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct QueryAllInput {
+    /// CSS selector to match
+    #[validate(length(min = 1))]
+    #[schemars(description = "CSS selector to match against the page")]
+    pub selector: String,
+
+    /// Maximum number of elements to return
+    #[serde(default = "default_limit")]
+    #[validate(range(min = 1, max = 500))]
+    #[schemars(description = "Maximum number of elements to return (default: 50)")]
+    pub limit: usize,
+
+    /// Computed style properties to include for each element
+    #[serde(default)]
+    #[schemars(description = "Computed CSS properties to return for each element (optional)")]
+    pub styles: Vec<String>,
+
+    /// Maximum length of each element's outer HTML
+    #[serde(default = "default_max_html_length")]
+    #[validate(range(max = 100000))]
+    #[schemars(
+        description = "Maximum number of characters of outer HTML per element (default: 2000)"
+    )]
+    pub max_html_length: usize,
+}
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: QueryAllInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    let page = manager
+        .page()
+        .await
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
+
+    let js = format!(
+        r#"(() => {{
+            const describe = {};
+            const all = Array.from(document.querySelectorAll({}));
+            return {{
+                total: all.length,
+                elements: all.slice(0, {}).map((el) => describe(el, {}, {})),
+            }};
+        }})()"#,
+        DESCRIBE_ELEMENT_JS,
+        serde_json::to_string(&input.selector).unwrap(),
+        input.limit,
+        serde_json::to_string(&input.styles).unwrap(),
+        input.max_html_length
+    );
+
+    let result: serde_json::Value = page
+        .evaluate_expression(js)
+        .await
+        .map_err(|e| {
+            browser_error(
+                manager,
+                &format!("Query failed for '{}'", input.selector),
+                e,
+            )
+        })?
+        .into_value()
+        .map_err(|e| Error::internal(format!("Failed to parse JS result: {:?}", e)))?;
+
+    let total = result["total"].as_u64().unwrap_or(0);
+    let mut elements = result["elements"].as_array().cloned().unwrap_or_default();
+    limits::truncate_values(&mut elements, manager.limits().max_content_bytes);
+
+    Ok(json!({
+        "selector": input.selector,
+        "total": total,
+        "count": elements.len(),
+        "truncated": total as usize > elements.len(),
+        "elements": elements
+    }))
+}
@@ -0,0 +1,106 @@
+//! Hand the browser to a human to get past a login wall, then resume.
+//!
+//! The browser is relaunched headed with the active profile. The user
+//! finishes by completing the login (when a success condition is known) or
+//! by closing the window. MCP elicitation would let the server prompt the
+//! user through the client instead, but pmcp does not yet give tool handlers
+//! a way to send requests to the client.
+
+use crate::browser::BrowserManager;
+use crate::login_check::LoginSuccess;
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use validator::Validate;
+
+fn default_timeout_secs() -> u64 {
+    300
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct RequestHumanLoginInput {
+    /// Where the user should log in
+    #[schemars(
+        description = "URL to open for the login (default: the active page's URL, or the profile's login check URL)"
+    )]
+    pub url: Option<String>,
+
+    /// URL pattern that shows the login finished
+    #[schemars(
+        description = "Finish once the page URL matches this pattern. '*' matches any characters; without '*' the pattern is matched as a substring."
+    )]
+    pub success_url: Option<String>,
+
+    /// Selector that shows the login finished
+    #[schemars(
+        description = "Finish once this CSS selector matches an element, e.g. the account avatar"
+    )]
+    pub success_selector: Option<String>,
+
+    /// Maximum time to wait for the user, in seconds
+    #[serde(default = "default_timeout_secs")]
+    #[validate(range(min = 10, max = 3600))]
+    #[schemars(description = "Maximum time to wait for the user in seconds (default: 300)")]
+    pub timeout_secs: u64,
+}
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: RequestHumanLoginInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    let success = LoginSuccess {
+        url: input.success_url,
+        selector: input.success_selector,
+    };
+    let result = manager
+        .human_login(
+            input.url.as_deref(),
+            &success,
+            Duration::from_secs(input.timeout_secs),
+        )
+        .await
+        .map_err(|e| Error::internal(format!("{:#}", e)))?;
+
+    Ok(json!({
+        "status": result.outcome,
+        "url": result.url,
+        "login_check_saved": result.login_check_saved,
+        "profile": manager.current_profile().await
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::test_manager;
+
+    fn input(value: serde_json::Value) -> RequestHumanLoginInput {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_input_defaults_and_range() {
+        let i = input(json!({ "success_url": "/home" }));
+        assert_eq!(i.timeout_secs, 300);
+        assert!(i.validate().is_ok());
+        assert!(input(json!({ "timeout_secs": 5 })).validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_requires_a_profile() {
+        let (manager, _tmp) = test_manager(&[], None);
+        let err = execute(&manager, input(json!({ "url": "https://app.test/login" })))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("needs a profile"));
+        assert!(!manager.is_running().await);
+    }
+}
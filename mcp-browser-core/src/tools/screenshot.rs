@@ -1,6 +1,9 @@
 //! Take a screenshot (base64 PNG).
 
+use crate::actionability;
 use crate::browser::BrowserManager;
+use crate::limits::LimitExceeded;
+use crate::tools::{actionability_error, browser_error};
 use base64::Engine;
 use chromiumoxide::cdp::browser_protocol::page::{
     CaptureScreenshotFormat, CaptureScreenshotParams,
@@ -12,6 +15,10 @@ use serde_json::json;
 use std::sync::Arc;
 use validator::Validate;
 
+fn default_timeout_ms() -> u64 {
+    actionability::DEFAULT_TIMEOUT_MS
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
 #[schemars(deny_unknown_fields)]
 pub struct ScreenshotInput {
@@ -23,6 +30,25 @@ pub struct ScreenshotInput {
     #[serde(default)]
     #[schemars(description = "Capture full scrollable page (default: false)")]
     pub full_page: bool,
+
+    /// Maximum time to wait for the selector's element to become visible
+    #[serde(default = "default_timeout_ms")]
+    #[validate(range(max = 120000))]
+    #[schemars(
+        description = "Maximum time in milliseconds to wait for the selector's element to be attached, visible and stable (default: 5000)"
+    )]
+    pub timeout_ms: u64,
+}
+
+/// Refuse screenshots larger than `limits.max_screenshot_size` before the
+/// browser renders them.
+fn check_size(manager: &BrowserManager, width: f64, height: f64) -> Result<(), Error> {
+    let max = manager.limits().max_screenshot_size;
+    let size = (width.ceil() as u32, height.ceil() as u32);
+    if size.0 > max.0 || size.1 > max.1 {
+        return Err(LimitExceeded::screenshot_size(max, size).into());
+    }
+    Ok(())
 }
 
 pub async fn execute(
@@ -36,21 +62,43 @@ pub async fn execute(
     let page = manager
         .page()
         .await
-        .map_err(|e| Error::internal(format!("Browser error: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
 
     let png_bytes = if let Some(ref selector) = input.selector {
         // Screenshot a specific element
-        let element = page
-            .find_element(selector)
+        let element = actionability::wait_for_element(
+            &page,
+            selector,
+            actionability::SCREENSHOT,
+            input.timeout_ms,
+        )
+        .await
+        .map_err(|e| actionability_error(manager, e))?;
+
+        let bounds = element
+            .bounding_box()
             .await
-            .map_err(|e| Error::internal(format!("Element not found '{}': {}", selector, e)))?;
+            .map_err(|e| browser_error(manager, "Screenshot failed", e))?;
+        check_size(manager, bounds.width, bounds.height)?;
 
         element
             .screenshot(CaptureScreenshotFormat::Png)
             .await
-            .map_err(|e| Error::internal(format!("Screenshot failed: {}", e)))?
+            .map_err(|e| browser_error(manager, "Screenshot failed", e))?
     } else {
         // Screenshot the page
+        let metrics = page
+            .layout_metrics()
+            .await
+            .map_err(|e| browser_error(manager, "Screenshot failed", e))?;
+        if input.full_page {
+            let size = &metrics.css_content_size;
+            check_size(manager, size.width, size.height)?;
+        } else {
+            let viewport = &metrics.css_visual_viewport;
+            check_size(manager, viewport.client_width, viewport.client_height)?;
+        }
+
         let params = CaptureScreenshotParams::builder()
             .format(CaptureScreenshotFormat::Png)
             .capture_beyond_viewport(input.full_page)
@@ -58,7 +106,7 @@ pub async fn execute(
 
         page.screenshot(params)
             .await
-            .map_err(|e| Error::internal(format!("Screenshot failed: {}", e)))?
+            .map_err(|e| browser_error(manager, "Screenshot failed", e))?
     };
 
     let b64 = base64::engine::general_purpose::STANDARD.encode(&png_bytes);
@@ -1,6 +1,7 @@
 //! Switch the active browser page (tab) by index.
 
 use crate::browser::BrowserManager;
+use crate::tools::browser_error;
 use pmcp::Error;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -29,12 +30,12 @@ pub async fn execute(
     let page = manager
         .select_page(input.index)
         .await
-        .map_err(|e| Error::internal(format!("Failed to select page: {}", e)))?;
+        .map_err(|e| browser_error(manager, "Failed to select page", e))?;
 
     let url = page
         .url()
         .await
-        .map_err(|e| Error::internal(format!("Failed to get URL: {}", e)))?
+        .map_err(|e| browser_error(manager, "Failed to get URL", e))?
         .unwrap_or_default()
         .to_string();
 
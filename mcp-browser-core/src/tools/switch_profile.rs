@@ -0,0 +1,73 @@
+//! Relaunch the browser with another saved profile.
+
+use crate::browser::BrowserManager;
+use crate::tools::get_profile::profile_summary;
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct SwitchProfileInput {
+    /// Profile name
+    #[validate(length(min = 1))]
+    #[schemars(description = "Name of the profile to switch to")]
+    pub name: String,
+}
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: SwitchProfileInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    let previous = manager.current_profile().await;
+    manager
+        .switch_profile(&input.name)
+        .await
+        .map_err(|e| Error::internal(format!("{:#}", e)))?;
+
+    let profile = manager
+        .profile_manager()
+        .get_profile(&input.name)
+        .map_err(|e| Error::internal(e.to_string()))?;
+
+    Ok(json!({
+        "status": "switched",
+        "previous": previous,
+        "profile": profile_summary(manager, &profile, Some(&input.name))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::test_manager;
+
+    fn input(name: &str) -> SwitchProfileInput {
+        SwitchProfileInput {
+            name: name.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_switch_keeps_previous_profile() {
+        let (manager, _tmp) = test_manager(&["work", "personal"], Some("work"));
+
+        // The browser cannot launch, so the switch rolls back.
+        assert!(execute(&manager, input("personal")).await.is_err());
+        assert_eq!(manager.current_profile().await.as_deref(), Some("work"));
+        assert!(manager
+            .profile_manager()
+            .ensure_unlocked("personal")
+            .is_ok());
+
+        assert!(execute(&manager, input("missing")).await.is_err());
+        assert_eq!(manager.current_profile().await.as_deref(), Some("work"));
+    }
+}
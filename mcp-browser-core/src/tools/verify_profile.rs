@@ -0,0 +1,39 @@
+//! Probe whether a saved profile is still logged in.
+
+use crate::browser::BrowserManager;
+use pmcp::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[schemars(deny_unknown_fields)]
+pub struct VerifyProfileInput {
+    /// Profile name
+    #[validate(length(min = 1))]
+    #[schemars(description = "Name of the profile whose login check should run")]
+    pub name: String,
+}
+
+pub async fn execute(
+    manager: &Arc<BrowserManager>,
+    input: VerifyProfileInput,
+) -> Result<serde_json::Value, Error> {
+    input
+        .validate()
+        .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+
+    let result = manager
+        .verify_profile(&input.name)
+        .await
+        .map_err(|e| Error::internal(format!("{:#}", e)))?;
+
+    Ok(json!({
+        "profile": input.name,
+        "logged_in": result.logged_in,
+        "final_url": result.final_url,
+        "detail": result.detail
+    }))
+}
@@ -1,17 +1,74 @@
-//! Wait for a selector to appear or a timeout.
+//! Wait for a page condition or a fixed duration.
+//!
+//! Supported conditions (at most one per call):
+//! - element `state` (`attached`, `visible`, `hidden`, `detached`), optionally
+//!   combined with `text` that must appear within the element
+//! - `url` matching a pattern (`*` wildcards, otherwise substring)
+//! - `function`: a JavaScript predicate that becomes truthy
+//! - `network_idle`: no requests in flight for `NETWORK_IDLE_MS`
+//!
+//! Element, text and function conditions are evaluated in the page on DOM
+//! mutations and animation frames; URL and network conditions are driven by
+//! CDP events. With no condition, the tool simply sleeps for `timeout_ms`.
 
+use crate::actionability::is_navigation_error;
 use crate::browser::BrowserManager;
+use crate::glob::url_matches;
+use crate::tools::browser_error;
+use chromiumoxide::cdp::browser_protocol::network::{
+    EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent, RequestId,
+};
+use chromiumoxide::cdp::browser_protocol::page::{
+    EventFrameNavigated, EventNavigatedWithinDocument,
+};
+use chromiumoxide::error::CdpError;
+use chromiumoxide::Page;
+use futures::StreamExt;
 use pmcp::Error;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use validator::Validate;
 
 fn default_timeout_ms() -> u64 {
     10000
 }
 
+/// Quiet period with no requests in flight before the network counts as idle.
+const NETWORK_IDLE_MS: u64 = 500;
+
+/// Upper bound for a single in-page wait (CDP requests time out at 30s).
+const PROBE_SLICE_MS: u64 = 5000;
+
+/// Element state to wait for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WaitState {
+    /// Element is present in the DOM.
+    #[default]
+    Attached,
+    /// Element is present and has a visible, non-empty box.
+    Visible,
+    /// Element is absent or not visible.
+    Hidden,
+    /// Element is absent from the DOM.
+    Detached,
+}
+
+impl WaitState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            WaitState::Attached => "attached",
+            WaitState::Visible => "visible",
+            WaitState::Hidden => "hidden",
+            WaitState::Detached => "detached",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
 #[schemars(deny_unknown_fields)]
 pub struct WaitInput {
@@ -19,6 +76,37 @@ pub struct WaitInput {
     #[schemars(description = "CSS selector to wait for (optional)")]
     pub selector: Option<String>,
 
+    /// Element state to wait for
+    #[schemars(
+        description = "Element state to wait for: 'attached' (default), 'visible', 'hidden' or 'detached'. Requires selector."
+    )]
+    pub state: Option<WaitState>,
+
+    /// Text that must appear within the element
+    #[schemars(
+        description = "Wait until this text appears within the selector's element (or the page body if no selector)"
+    )]
+    pub text: Option<String>,
+
+    /// URL pattern to wait for
+    #[schemars(
+        description = "Wait until the page URL matches this pattern. '*' matches any characters; without '*' the pattern is matched as a substring."
+    )]
+    pub url: Option<String>,
+
+    /// JavaScript predicate to wait for
+    #[schemars(
+        description = "JavaScript expression or function evaluated in the page; waits until it returns a truthy value"
+    )]
+    pub function: Option<String>,
+
+    /// Wait for the network to go idle
+    #[serde(default)]
+    #[schemars(
+        description = "Wait until no network requests have been in flight for 500ms (default: false)"
+    )]
+    pub network_idle: bool,
+
     /// Maximum time to wait in milliseconds
     #[serde(default = "default_timeout_ms")]
     #[validate(range(min = 100, max = 120000))]
@@ -26,6 +114,314 @@ pub struct WaitInput {
     pub timeout_ms: u64,
 }
 
+/// The single condition a `WaitInput` asks for.
+#[derive(Debug, PartialEq, Eq)]
+enum Condition<'a> {
+    Duration,
+    Element {
+        selector: Option<&'a str>,
+        state: WaitState,
+        text: Option<&'a str>,
+    },
+    Url(&'a str),
+    Function(&'a str),
+    NetworkIdle,
+}
+
+impl WaitInput {
+    /// Resolve the requested condition, rejecting ambiguous combinations.
+    fn condition(&self) -> Result<Condition<'_>, String> {
+        let element = self.selector.is_some() || self.text.is_some();
+        let requested = [
+            element,
+            self.url.is_some(),
+            self.function.is_some(),
+            self.network_idle,
+        ]
+        .iter()
+        .filter(|c| **c)
+        .count();
+
+        if requested > 1 {
+            return Err(
+                "Specify only one of selector/text, url, function or network_idle".to_string(),
+            );
+        }
+
+        if self.state.is_some() && self.selector.is_none() {
+            return Err("state requires a selector".to_string());
+        }
+
+        let state = self.state.unwrap_or_default();
+        if self.text.is_some() && matches!(state, WaitState::Hidden | WaitState::Detached) {
+            return Err(format!(
+                "text cannot be combined with state '{}'",
+                state.as_str()
+            ));
+        }
+
+        if element {
+            return Ok(Condition::Element {
+                selector: self.selector.as_deref(),
+                state,
+                text: self.text.as_deref(),
+            });
+        }
+        if let Some(ref url) = self.url {
+            return Ok(Condition::Url(url));
+        }
+        if let Some(ref function) = self.function {
+            return Ok(Condition::Function(function));
+        }
+        if self.network_idle {
+            return Ok(Condition::NetworkIdle);
+        }
+        Ok(Condition::Duration)
+    }
+}
+
+/// In-page waiter. `check` returns `null` until the condition holds, then a
+/// JSON-serializable description of what matched.
+const WAIT_UNTIL_JS: &str = r#"
+(check, budget) => new Promise((resolve) => {
+    let done = false;
+    let observer = null;
+    let timer = null;
+
+    const finish = (result) => {
+        if (done) return;
+        done = true;
+        if (observer) observer.disconnect();
+        if (timer) clearTimeout(timer);
+        resolve(result);
+    };
+
+    const run = () => {
+        if (done) return;
+        try {
+            const matched = check();
+            if (matched !== null && matched !== undefined) finish({ ok: true, matched: matched });
+        } catch (e) {
+            finish({ ok: false, error: String(e && e.message || e) });
+        }
+    };
+
+    const frame = () => {
+        run();
+        if (!done) requestAnimationFrame(frame);
+    };
+
+    observer = new MutationObserver(run);
+    observer.observe(document, { childList: true, subtree: true, attributes: true, characterData: true });
+    timer = setTimeout(() => finish({ ok: false }), budget);
+    frame();
+})
+"#;
+
+/// Shared helpers for element predicates.
+const ELEMENT_HELPERS_JS: &str = r#"
+const isVisible = (el) => {
+    if (!el || !el.isConnected) return false;
+    const style = getComputedStyle(el);
+    if (style.visibility === 'hidden' || style.visibility === 'collapse') return false;
+    const rect = el.getBoundingClientRect();
+    return rect.width > 0 && rect.height > 0;
+};
+const describe = (el) => el
+    ? { tag: el.tagName.toLowerCase(), id: el.id || null, text: (el.innerText || el.textContent || '').trim().slice(0, 200) }
+    : null;
+"#;
+
+/// Build the in-page predicate for an element condition.
+fn element_check_js(selector: Option<&str>, state: WaitState, text: Option<&str>) -> String {
+    let selector = serde_json::to_string(&selector).unwrap();
+    let text = serde_json::to_string(&text).unwrap();
+    let state = serde_json::to_string(state.as_str()).unwrap();
+    format!(
+        r#"() => {{
+            {helpers}
+            const selector = {selector};
+            const text = {text};
+            const state = {state};
+            const el = selector === null ? document.body : document.querySelector(selector);
+            let ok;
+            switch (state) {{
+                case 'attached': ok = !!el; break;
+                case 'visible': ok = isVisible(el); break;
+                case 'hidden': ok = !isVisible(el); break;
+                case 'detached': ok = !el; break;
+            }}
+            if (ok && text !== null) {{
+                ok = !!el && (el.innerText || el.textContent || '').includes(text);
+            }}
+            if (!ok) return null;
+            return {{ state: state, element: describe(el) }};
+        }}"#,
+        helpers = ELEMENT_HELPERS_JS,
+    )
+}
+
+/// Build the in-page predicate for a user-supplied JavaScript function.
+fn function_check_js(function: &str) -> String {
+    format!(
+        r#"() => {{
+            let value = ({function});
+            if (typeof value === 'function') value = value();
+            if (!value) return null;
+            try {{
+                return {{ value: JSON.parse(JSON.stringify(value)) ?? true }};
+            }} catch (e) {{
+                return {{ value: true }};
+            }}
+        }}"#
+    )
+}
+
+/// Run an in-page predicate until it matches or `timeout_ms` elapses.
+///
+/// Each evaluation is capped at `PROBE_SLICE_MS`; navigations that destroy
+/// the execution context are retried in the new document.
+async fn wait_in_page(
+    manager: &BrowserManager,
+    page: &Page,
+    check_js: &str,
+    timeout_ms: u64,
+) -> Result<Option<serde_json::Value>, Error> {
+    let start = Instant::now();
+    let timeout = Duration::from_millis(timeout_ms);
+
+    while start.elapsed() < timeout {
+        let remaining = timeout.saturating_sub(start.elapsed()).as_millis() as u64;
+        let js = format!(
+            "({})({}, {})",
+            WAIT_UNTIL_JS,
+            check_js,
+            remaining.min(PROBE_SLICE_MS)
+        );
+
+        match page.evaluate_expression(js).await {
+            Ok(result) => {
+                let result: serde_json::Value = result
+                    .into_value()
+                    .map_err(|e| browser_error(manager, "Unexpected wait result", e))?;
+                if let Some(error) = result["error"].as_str() {
+                    return Err(Error::internal(format!("Wait condition threw: {}", error)));
+                }
+                if result["ok"].as_bool() == Some(true) {
+                    return Ok(Some(result["matched"].clone()));
+                }
+            }
+            Err(e) if is_navigation_error(&e) => {
+                tracing::debug!("In-page wait interrupted: {}", e);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            Err(CdpError::JavascriptException(details)) => {
+                let message = details
+                    .exception
+                    .as_ref()
+                    .and_then(|exception| exception.description.clone())
+                    .unwrap_or_else(|| details.text.clone());
+                return Err(Error::validation(format!(
+                    "Wait condition failed to evaluate: {}",
+                    message
+                )));
+            }
+            Err(e) => {
+                return Err(browser_error(manager, "Wait condition failed", e));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Wait until the page URL matches `pattern`, driven by navigation events.
+async fn wait_for_url(
+    manager: &BrowserManager,
+    page: &Page,
+    pattern: &str,
+    timeout_ms: u64,
+) -> Result<Option<String>, Error> {
+    let navigated = page
+        .event_listener::<EventFrameNavigated>()
+        .await
+        .map_err(|e| browser_error(manager, "Failed to subscribe to navigations", e))?;
+    let within_document = page
+        .event_listener::<EventNavigatedWithinDocument>()
+        .await
+        .map_err(|e| browser_error(manager, "Failed to subscribe to navigations", e))?;
+    let mut events = futures::stream::select(navigated.map(|_| ()), within_document.map(|_| ()));
+
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(timeout_ms);
+    loop {
+        let url = page
+            .url()
+            .await
+            .map_err(|e| browser_error(manager, "Failed to get URL", e))?
+            .unwrap_or_default();
+        if url_matches(pattern, &url) {
+            return Ok(Some(url));
+        }
+
+        match tokio::time::timeout_at(deadline, events.next()).await {
+            Ok(Some(())) => continue,
+            Ok(None) | Err(_) => return Ok(None),
+        }
+    }
+}
+
+/// Wait until no requests have been in flight for `NETWORK_IDLE_MS`.
+///
+/// Only requests started after the call are tracked. Returns the number of
+/// requests observed while waiting.
+async fn wait_for_network_idle(
+    manager: &BrowserManager,
+    page: &Page,
+    timeout_ms: u64,
+) -> Result<Option<usize>, Error> {
+    let subscribe_err = |e| browser_error(manager, "Failed to subscribe to network", e);
+    let mut started = page
+        .event_listener::<EventRequestWillBeSent>()
+        .await
+        .map_err(subscribe_err)?;
+    let mut finished = page
+        .event_listener::<EventLoadingFinished>()
+        .await
+        .map_err(subscribe_err)?;
+    let mut failed = page
+        .event_listener::<EventLoadingFailed>()
+        .await
+        .map_err(subscribe_err)?;
+
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(timeout_ms);
+    let idle = Duration::from_millis(NETWORK_IDLE_MS);
+    let mut inflight: HashSet<RequestId> = HashSet::new();
+    let mut seen = 0usize;
+    let mut quiet_since = tokio::time::Instant::now();
+
+    loop {
+        let idle_at = quiet_since + idle;
+        tokio::select! {
+            _ = tokio::time::sleep_until(deadline) => return Ok(None),
+            _ = tokio::time::sleep_until(idle_at), if inflight.is_empty() => return Ok(Some(seen)),
+            Some(event) = started.next() => {
+                seen += 1;
+                inflight.insert(event.request_id.clone());
+            }
+            Some(event) = finished.next() => {
+                if inflight.remove(&event.request_id) && inflight.is_empty() {
+                    quiet_since = tokio::time::Instant::now();
+                }
+            }
+            Some(event) = failed.next() => {
+                if inflight.remove(&event.request_id) && inflight.is_empty() {
+                    quiet_since = tokio::time::Instant::now();
+                }
+            }
+        }
+    }
+}
+
 pub async fn execute(
     manager: &Arc<BrowserManager>,
     input: WaitInput,
@@ -33,44 +429,121 @@ pub async fn execute(
     input
         .validate()
         .map_err(|e| Error::validation(format!("Validation failed: {}", e)))?;
+    let condition = input.condition().map_err(Error::validation)?;
+
+    if condition == Condition::Duration {
+        tokio::time::sleep(Duration::from_millis(input.timeout_ms)).await;
+        return Ok(json!({
+            "status": "ready",
+            "waited_ms": input.timeout_ms
+        }));
+    }
 
     let page = manager
         .page()
         .await
-        .map_err(|e| Error::internal(format!("Browser error: {}", e)))?;
-
-    if let Some(ref selector) = input.selector {
-        // Poll for the element with timeout
-        let start = std::time::Instant::now();
-        let timeout = std::time::Duration::from_millis(input.timeout_ms);
-
-        loop {
-            match page.find_element(selector).await {
-                Ok(_) => {
-                    let elapsed = start.elapsed().as_millis();
-                    return Ok(json!({
-                        "status": "ready",
-                        "selector": selector,
-                        "elapsed_ms": elapsed
-                    }));
-                }
-                Err(_) if start.elapsed() < timeout => {
-                    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
-                }
-                Err(e) => {
-                    return Err(Error::internal(format!(
-                        "Timeout waiting for '{}' after {}ms: {}",
-                        selector, input.timeout_ms, e
-                    )));
-                }
-            }
+        .map_err(|e| browser_error(manager, "Browser error", e))?;
+
+    let start = Instant::now();
+    let (kind, matched, description) = match condition {
+        Condition::Element {
+            selector,
+            state,
+            text,
+        } => {
+            let js = element_check_js(selector, state, text);
+            let matched = wait_in_page(manager, &page, &js, input.timeout_ms).await?;
+            let target = selector.unwrap_or("body");
+            let description = match text {
+                Some(text) => format!("'{}' to contain text {:?}", target, text),
+                None => format!("'{}' to be {}", target, state.as_str()),
+            };
+            ("element", matched, description)
+        }
+        Condition::Url(pattern) => {
+            let matched = wait_for_url(manager, &page, pattern, input.timeout_ms)
+                .await?
+                .map(|url| json!({ "url": url }));
+            ("url", matched, format!("URL to match '{}'", pattern))
+        }
+        Condition::Function(function) => {
+            let js = function_check_js(function);
+            let matched = wait_in_page(manager, &page, &js, input.timeout_ms).await?;
+            (
+                "function",
+                matched,
+                "function to return a truthy value".to_string(),
+            )
         }
-    } else {
-        // Just wait for the specified time
-        tokio::time::sleep(std::time::Duration::from_millis(input.timeout_ms)).await;
-        Ok(json!({
+        Condition::NetworkIdle => {
+            let matched = wait_for_network_idle(manager, &page, input.timeout_ms)
+                .await?
+                .map(|seen| json!({ "idle_ms": NETWORK_IDLE_MS, "requests_seen": seen }));
+            ("network_idle", matched, "network to be idle".to_string())
+        }
+        Condition::Duration => unreachable!("handled above"),
+    };
+
+    match matched {
+        Some(matched) => Ok(json!({
             "status": "ready",
-            "waited_ms": input.timeout_ms
-        }))
+            "condition": kind,
+            "selector": input.selector,
+            "matched": matched,
+            "elapsed_ms": start.elapsed().as_millis()
+        })),
+        None => Err(Error::internal(format!(
+            "Timeout waiting for {} after {}ms",
+            description, input.timeout_ms
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(value: serde_json::Value) -> WaitInput {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_condition_defaults_to_duration() {
+        let i = input(json!({ "timeout_ms": 500 }));
+        assert_eq!(i.condition().unwrap(), Condition::Duration);
+    }
+
+    #[test]
+    fn test_condition_element_state() {
+        let i = input(json!({ "selector": "#spinner", "state": "detached" }));
+        assert_eq!(
+            i.condition().unwrap(),
+            Condition::Element {
+                selector: Some("#spinner"),
+                state: WaitState::Detached,
+                text: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_condition_rejects_multiple() {
+        let i = input(json!({ "selector": "#a", "url": "/done" }));
+        assert!(i.condition().is_err());
+
+        let i = input(json!({ "function": "true", "network_idle": true }));
+        assert!(i.condition().is_err());
+    }
+
+    #[test]
+    fn test_condition_state_requires_selector() {
+        let i = input(json!({ "state": "visible" }));
+        assert!(i.condition().is_err());
+    }
+
+    #[test]
+    fn test_condition_text_rejects_hidden() {
+        let i = input(json!({ "selector": "#a", "state": "hidden", "text": "x" }));
+        assert!(i.condition().is_err());
     }
 }
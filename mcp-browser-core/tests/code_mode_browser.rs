@@ -15,7 +15,7 @@
 mod test_helpers;
 mod test_server;
 
-use mcp_browser_core::code_mode;
+use mcp_browser_core::{actionability, code_mode};
 use test_helpers::{preflight_check, run_script, test_manager};
 use test_server::TestServer;
 
@@ -88,7 +88,7 @@ async fn test_screenshot() {
     let inner = &result["result"];
 
     let has_base64 = inner["screenshot"].is_string()
-        || inner.get("data").map_or(false, |d| d.is_string())
+        || inner.get("data").is_some_and(|d| d.is_string())
         || inner.is_string();
     assert!(
         has_base64,
@@ -347,7 +347,7 @@ async fn test_list_pages() {
 
     let pages = inner["pages"].as_array();
     assert!(
-        pages.map_or(false, |p| !p.is_empty()),
+        pages.is_some_and(|p| !p.is_empty()),
         "Expected at least one page, got: {}",
         inner
     );
@@ -501,3 +501,361 @@ async fn test_form_submit_evaluate() {
         result_text
     );
 }
+
+// ---------------------------------------------------------------------------
+// Test 14: Actionability — click waits for enabled + unobscured
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_click_waits_for_actionable() {
+    preflight_check().await;
+    let server = TestServer::start().await;
+    let manager = test_manager();
+
+    let code = format!(
+        r##"
+        await api.post("/navigate", {{ url: "{}" }});
+        await api.post("/click", {{ selector: "#late-button", timeout_ms: 5000 }});
+        const text = await api.post("/get_text", {{ selector: "#result" }});
+        return text;
+    "##,
+        server.url("actionability.html")
+    );
+
+    let result = run_script(manager, &code)
+        .await
+        .expect("script should succeed");
+    let text = result["result"]["text"].as_str().unwrap_or("");
+    assert_eq!(text, "clicked", "Expected click to land, got: {}", text);
+}
+
+// ---------------------------------------------------------------------------
+// Test 15: Actionability — timeout names the failing check
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_click_reports_failed_check() {
+    preflight_check().await;
+    let server = TestServer::start().await;
+    let manager = test_manager();
+
+    let code = format!(
+        r##"
+        await api.post("/navigate", {{ url: "{}" }});
+        await api.post("/wait", {{ timeout_ms: 1000 }});
+        await api.post("/click", {{ selector: "#never-enabled", timeout_ms: 500 }});
+        return "unreachable";
+    "##,
+        server.url("actionability.html")
+    );
+
+    let err = run_script(manager, &code)
+        .await
+        .expect_err("click on a disabled button should time out");
+    assert!(
+        err.contains("to be enabled"),
+        "Expected error to name the 'enabled' check, got: {}",
+        err
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Test 16: Rich wait — text, function predicate and network idle
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_wait_conditions() {
+    preflight_check().await;
+    let server = TestServer::start().await;
+    let manager = test_manager();
+
+    let code = format!(
+        r##"
+        await api.post("/navigate", {{ url: "{}" }});
+        const text = await api.post("/wait", {{ selector: "#container", text: "after 2s", timeout_ms: 5000 }});
+        const fn = await api.post("/wait", {{ function: "document.querySelectorAll('#container div').length === 2" }});
+        const url = await api.post("/wait", {{ url: "*/dynamic.html" }});
+        const idle = await api.post("/wait", {{ network_idle: true }});
+        return {{ text: text, fn: fn, url: url, idle: idle }};
+    "##,
+        server.url("dynamic.html")
+    );
+
+    let result = run_script(manager, &code)
+        .await
+        .expect("script should succeed");
+    let inner = &result["result"];
+
+    assert_eq!(inner["text"]["condition"], "element");
+    assert!(
+        inner["text"]["matched"]["element"]["text"]
+            .as_str()
+            .unwrap_or("")
+            .contains("after 2s"),
+        "Expected matched element text, got: {}",
+        inner["text"]
+    );
+    assert_eq!(inner["fn"]["condition"], "function");
+    assert_eq!(inner["url"]["condition"], "url");
+    assert_eq!(inner["idle"]["condition"], "network_idle");
+}
+
+// ---------------------------------------------------------------------------
+// Test 17: Element inspection — inspect_element, query_all, get_text all
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_element_inspection() {
+    preflight_check().await;
+    let server = TestServer::start().await;
+    let manager = test_manager();
+
+    let code = format!(
+        r##"
+        await api.post("/navigate", {{ url: "{}" }});
+        await api.post("/fill", {{ selector: "#name", value: "Ada" }});
+        const name = await api.post("/inspect_element", {{ selector: "#name", styles: ["display"] }});
+        const fields = await api.post("/query_all", {{ selector: "input, textarea", limit: 2 }});
+        const texts = await api.post("/get_text", {{ selector: "h1, button", all: true }});
+        return {{ name: name, fields: fields, texts: texts }};
+    "##,
+        server.url("form.html")
+    );
+
+    let result = run_script(manager, &code)
+        .await
+        .expect("script should succeed");
+    let inner = &result["result"];
+
+    assert_eq!(inner["name"]["tag"], "input");
+    assert_eq!(inner["name"]["value"], "Ada");
+    assert_eq!(inner["name"]["attributes"]["placeholder"], "Name");
+    assert_eq!(inner["name"]["visible"], true);
+    assert!(inner["name"]["styles"]["display"].is_string());
+    assert!(
+        inner["name"]["bounding_box"]["width"]
+            .as_f64()
+            .unwrap_or(0.0)
+            > 0.0
+    );
+
+    assert_eq!(inner["fields"]["total"], 3);
+    assert_eq!(inner["fields"]["count"], 2);
+    assert_eq!(inner["fields"]["truncated"], true);
+
+    assert_eq!(inner["texts"]["count"], 2);
+}
+
+// ---------------------------------------------------------------------------
+// Test 18: Link and form discovery
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_link_and_form_discovery() {
+    preflight_check().await;
+    let server = TestServer::start().await;
+    let manager = test_manager();
+
+    let code = format!(
+        r##"
+        await api.post("/navigate", {{ url: "{}" }});
+        const links = await api.post("/list_links", {{ pattern: "#link2" }});
+        await api.post("/navigate", {{ url: "{}" }});
+        const forms = await api.post("/list_forms", {{}});
+        return {{ links: links, forms: forms }};
+    "##,
+        server.url("simple.html"),
+        server.url("form.html")
+    );
+
+    let result = run_script(manager, &code)
+        .await
+        .expect("script should succeed");
+    let inner = &result["result"];
+
+    assert_eq!(inner["links"]["count"], 1);
+    let link = &inner["links"]["links"][0];
+    assert!(link["href"]
+        .as_str()
+        .unwrap_or("")
+        .ends_with("simple.html#link2"));
+    assert_eq!(link["text"], "Second Link");
+    assert_eq!(link["same_origin"], true);
+
+    assert_eq!(inner["forms"]["count"], 1);
+    let fields = inner["forms"]["forms"][0]["fields"].as_array().unwrap();
+    assert!(
+        fields
+            .iter()
+            .any(|f| f["name"] == "email" && f["type"] == "email" && f["label"] == "Email"),
+        "Expected email field with placeholder label, got: {:?}",
+        fields
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Test 19: Dialog capture — pending dialog is reported, then accepted
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_dialog_capture() {
+    preflight_check().await;
+    let server = TestServer::start().await;
+    let manager = test_manager();
+
+    let code = format!(
+        r##"
+        await api.post("/navigate", {{ url: "{}" }});
+        await api.post("/evaluate", {{
+            expression: "setTimeout(() => {{ window.answer = confirm('Proceed?'); }}, 100); true"
+        }});
+        await api.post("/wait", {{ timeout_ms: 500 }});
+        const pending = await api.get("/dialog");
+        const handled = await api.post("/handle_dialog", {{ accept: true }});
+        const answer = await api.post("/evaluate", {{ expression: "window.answer" }});
+        return {{ pending: pending, handled: handled, answer: answer }};
+    "##,
+        server.url("simple.html")
+    );
+
+    let result = run_script(manager, &code)
+        .await
+        .expect("script should succeed");
+    let inner = &result["result"];
+
+    assert_eq!(inner["pending"]["pending"], true);
+    assert_eq!(inner["pending"]["dialog"]["dialog_type"], "confirm");
+    assert_eq!(inner["pending"]["dialog"]["message"], "Proceed?");
+    assert_eq!(inner["handled"]["dialog"]["message"], "Proceed?");
+    assert_eq!(inner["answer"]["result"], true);
+}
+
+// ---------------------------------------------------------------------------
+// Test 20: Emulation — device preset, locale, timezone and color scheme
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_emulation() {
+    preflight_check().await;
+    let server = TestServer::start().await;
+    let manager = test_manager();
+
+    let code = format!(
+        r##"
+        await api.post("/navigate", {{ url: "{}" }});
+        const applied = await api.post("/emulate", {{
+            device: "iphone-15",
+            locale: "de-DE",
+            timezone: "Europe/Berlin",
+            color_scheme: "dark"
+        }});
+        const env = await api.post("/evaluate", {{
+            expression: "({{ width: window.innerWidth, dpr: window.devicePixelRatio, ua: navigator.userAgent, tz: Intl.DateTimeFormat().resolvedOptions().timeZone, dark: matchMedia('(prefers-color-scheme: dark)').matches }})"
+        }});
+        await api.post("/emulate", {{ reset: true }});
+        const after = await api.post("/evaluate", {{
+            expression: "matchMedia('(prefers-color-scheme: dark)').matches"
+        }});
+        return {{ applied: applied, env: env, after: after }};
+    "##,
+        server.url("simple.html")
+    );
+
+    let result = run_script(manager, &code)
+        .await
+        .expect("script should succeed");
+    let inner = &result["result"];
+
+    assert_eq!(inner["applied"]["emulation"]["device"], "iphone-15");
+    let env = &inner["env"]["result"];
+    assert_eq!(env["width"], 393);
+    assert_eq!(env["dpr"], 3);
+    assert!(env["ua"].as_str().unwrap_or("").contains("iPhone"));
+    assert_eq!(env["tz"], "Europe/Berlin");
+    assert_eq!(env["dark"], true);
+    assert_eq!(inner["after"]["result"], false);
+}
+
+// ---------------------------------------------------------------------------
+// Test 21: Network emulation — offline navigation fails, online recovers
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_network_emulation() {
+    preflight_check().await;
+    let server = TestServer::start().await;
+    let manager = test_manager();
+
+    let code = format!(
+        r##"
+        await api.post("/navigate", {{ url: "{url}" }});
+        const offline = await api.post("/emulate_network", {{ preset: "offline", cache_disabled: true }});
+        const status = await api.post("/evaluate", {{
+            expression: "fetch(location.href).then(() => 'ok').catch(() => 'failed')"
+        }});
+        const online = await api.post("/emulate_network", {{ preset: "online" }});
+        const after = await api.post("/evaluate", {{
+            expression: "fetch(location.href).then(() => 'ok').catch(() => 'failed')"
+        }});
+        return {{ offline: offline, status: status, online: online, after: after }};
+    "##,
+        url = server.url("simple.html")
+    );
+
+    let result = run_script(manager, &code)
+        .await
+        .expect("script should succeed");
+    let inner = &result["result"];
+
+    assert_eq!(inner["offline"]["offline"], true);
+    assert_eq!(inner["offline"]["cache_disabled"], true);
+    assert_eq!(inner["status"]["result"], "failed");
+    assert_eq!(inner["online"]["throttle"], serde_json::Value::Null);
+    assert_eq!(inner["online"]["cache_disabled"], true);
+    assert_eq!(inner["after"]["result"], "ok");
+}
+
+// ---------------------------------------------------------------------------
+// Test 22: Actionability — click on a tab that is not in front
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_click_on_background_tab() {
+    preflight_check().await;
+    let server = TestServer::start().await;
+    let manager = test_manager();
+
+    let background = manager.page().await.expect("first page");
+    background
+        .goto(server.url("actionability.html"))
+        .await
+        .expect("navigate first page");
+    // The new tab comes to the front and the first one gets no frames.
+    manager
+        .create_new_page(&server.url("simple.html"))
+        .await
+        .expect("open second page");
+
+    let button =
+        actionability::wait_for_element(&background, "#late-button", actionability::CLICK, 5000)
+            .await
+            .expect("button in the background tab should become actionable");
+    button.click().await.expect("click");
+
+    let text: String = background
+        .evaluate_expression("document.getElementById('result').textContent")
+        .await
+        .expect("read result")
+        .into_value()
+        .expect("string result");
+    assert_eq!(text, "clicked");
+}
@@ -6,14 +6,9 @@
 use mcp_browser_core::browser::{BrowserManager, BrowserManagerConfig};
 use mcp_browser_core::code_mode;
 use mcp_browser_core::profile::ProfileManager;
-use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use tokio::sync::OnceCell;
 
-/// Monotonic counter so each test_manager() gets a unique profile name,
-/// avoiding Chrome SingletonLock conflicts between sequential tests.
-static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
-
 /// Cached result of the browser preflight check.
 /// `Ok(())` means the browser can launch; `Err(msg)` means it can't.
 static PREFLIGHT: OnceCell<Result<(), String>> = OnceCell::const_new();
@@ -44,22 +39,15 @@ pub fn resolve_browser_path() -> Option<String> {
 }
 
 /// Create a headless BrowserManager for testing with the selected browser.
-/// Each call gets a unique profile (user-data-dir) to avoid Chrome
-/// SingletonLock conflicts from stale or concurrent sessions.
+/// Ephemeral mode gives each launch its own user-data-dir, so concurrent
+/// tests never share Chrome's SingletonLock and nothing is left behind.
 pub fn test_manager() -> Arc<BrowserManager> {
-    let n = TEST_COUNTER.fetch_add(1, Ordering::Relaxed);
-    let profile_name = format!("test-{}-{}", std::process::id(), n);
     let profile_manager = Arc::new(ProfileManager::new().expect("ProfileManager init"));
-    // Ensure the profile exists so user_data_dir() works
-    let _ = profile_manager.get_or_create_profile(
-        &profile_name,
-        mcp_browser_core::profile::CreateOpts::default(),
-    );
     Arc::new(BrowserManager::new(
         BrowserManagerConfig {
             headless: true,
             browser_path: resolve_browser_path(),
-            profile: Some(profile_name),
+            ephemeral: true,
             ..Default::default()
         },
         profile_manager,
@@ -11,6 +11,7 @@ const SIMPLE_HTML: &str = include_str!("pages/simple.html");
 const FORM_HTML: &str = include_str!("pages/form.html");
 const TABLE_HTML: &str = include_str!("pages/table.html");
 const DYNAMIC_HTML: &str = include_str!("pages/dynamic.html");
+const ACTIONABILITY_HTML: &str = include_str!("pages/actionability.html");
 
 pub struct TestServer {
     pub base_url: String,
@@ -69,6 +70,7 @@ impl TestServer {
                     "form.html" => ("200 OK", FORM_HTML),
                     "table.html" => ("200 OK", TABLE_HTML),
                     "dynamic.html" => ("200 OK", DYNAMIC_HTML),
+                    "actionability.html" => ("200 OK", ACTIONABILITY_HTML),
                     _ => ("404 Not Found", "<h1>404</h1>"),
                 };
 